@@ -18,7 +18,7 @@ use smol::{channel, prelude::*};
 use std::{
     any::{type_name, Any, TypeId},
     cell::RefCell,
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt::{self, Debug},
     hash::{Hash, Hasher},
     marker::PhantomData,
@@ -347,6 +347,8 @@ pub struct MutableAppContext {
 
     actions: HashMap<TypeId, HashMap<String, Vec<Box<ActionCallback>>>>,
     global_actions: HashMap<String, Vec<Box<GlobalActionCallback>>>,
+    action_arg_types: HashMap<String, HashSet<TypeId>>,
+    dispatch_path: Vec<usize>,
 
     keystroke_matcher: keymap::Matcher,
 
@@ -397,6 +399,8 @@ impl MutableAppContext {
 
             actions: HashMap::new(),
             global_actions: HashMap::new(),
+            action_arg_types: HashMap::new(),
+            dispatch_path: Vec::new(),
             keystroke_matcher: keymap::Matcher::default(),
 
             next_entity_id: 0,
@@ -487,6 +491,11 @@ impl MutableAppContext {
             }
         );
 
+        self.action_arg_types
+            .entry(name.clone())
+            .or_default()
+            .insert(TypeId::of::<T>());
+
         self.actions
             .entry(TypeId::of::<V>())
             .or_default()
@@ -513,6 +522,11 @@ impl MutableAppContext {
             }
         });
 
+        self.action_arg_types
+            .entry(name.clone())
+            .or_default()
+            .insert(TypeId::of::<T>());
+
         self.global_actions.entry(name).or_default().push(handler);
     }
 
@@ -554,6 +568,7 @@ impl MutableAppContext {
     ) -> bool {
         self.pending_flushes += 1;
 
+        let prev_dispatch_path = std::mem::replace(&mut self.dispatch_path, path.to_vec());
         let mut halted_dispatch = false;
 
         for view_id in path.iter().rev() {
@@ -602,11 +617,70 @@ impl MutableAppContext {
             self.dispatch_global_action(name, arg);
         }
 
+        self.dispatch_path = prev_dispatch_path;
         self.flush_effects();
 
         halted_dispatch
     }
 
+    /// lista as ações sem argumento que podem ser disparadas a partir da cadeia de resposta,
+    /// junto com as teclas do binding que as dispararia
+    pub fn available_actions(
+        &self,
+
+        window_id: usize,
+        path: &[usize]
+    ) -> Vec<(String, Option<Vec<Keystroke>>)> {
+        let mut context_chain = Vec::new();
+        let mut context = keymap::Context::default();
+
+        // para cada ação, o índice da view mais externa que pode tratá-la
+        let mut handler_depths = BTreeMap::new();
+
+        for (depth, view_id) in path.iter().enumerate() {
+            if let Some(view) = self
+                .ctx
+                .windows
+                .get(&window_id)
+                .and_then(|w| w.views.get(view_id))
+            {
+                context.extend(view.keymap_context(self.downgrade()));
+
+                if let Some(actions) = self.actions.get(&view.as_any().type_id()) {
+                    for name in actions.keys() {
+                        handler_depths.entry(name.as_str()).or_insert(depth);
+                    }
+                }
+            }
+
+            context_chain.push(context.clone());
+        }
+
+        for name in self.global_actions.keys() {
+            handler_depths.insert(name.as_str(), 0);
+        }
+
+        let unit_type = TypeId::of::<()>();
+        let keymap = self.keystroke_matcher.keymap();
+
+        handler_depths
+            .into_iter()
+            .filter(|(name, _)| {
+                self.action_arg_types
+                    .get(*name)
+                    .map_or(false, |types| types.contains(&unit_type))
+            })
+            .map(|(name, depth)| {
+                let keystrokes = context_chain[depth..]
+                    .iter()
+                    .rev()
+                    .find_map(|ctx| keymap.binding_for_action(name, ctx))
+                    .map(|binding| binding.keystrokes().to_vec());
+
+                (name.to_string(), keystrokes)
+            }).collect()
+    }
+
     fn dispatch_global_action(&mut self, name: &str, arg: &dyn Any) {
         if let Some((name, mut handlers)) = self.global_actions.remove_entry(name) {
             self.pending_flushes += 1;
@@ -625,6 +699,14 @@ impl MutableAppContext {
         self.keystroke_matcher.add_bindings(bindings);
     }
 
+    pub fn keymap(&self) -> &keymap::Keymap {
+        self.keystroke_matcher.keymap()
+    }
+
+    pub fn set_keymap(&mut self, keymap: keymap::Keymap) {
+        self.keystroke_matcher.set_keymap(keymap);
+    }
+
     pub fn dispatch_keystroke(
         &mut self,
 
@@ -752,6 +834,16 @@ impl MutableAppContext {
 
 
                         app.update(|ctx| {
+                            // as views acompanham os modificadores por uma ação, já que soltar
+                            // uma tecla não gera um keystroke
+                            if let Event::ModifiersChanged { modifiers } = &event {
+                                let path = presenter.borrow().dispatch_path(ctx.downgrade());
+
+                                ctx.dispatch_action(window_id, &path, "modifiers_changed", modifiers);
+
+                                return;
+                            }
+
                             if let Event::KeyDown { keystroke, .. } = &event {
                                 if ctx
                                     .dispatch_keystroke(
@@ -914,6 +1006,10 @@ impl MutableAppContext {
                     Effect::Focus { window_id, view_id } => {
                         self.focus(window_id, view_id);
                     }
+
+                    Effect::DispatchAction { window_id, path, name, arg } => {
+                        self.dispatch_action(window_id, &path, &name, arg.as_ref());
+                    }
                 }
             }
 
@@ -1572,6 +1668,13 @@ pub enum Effect {
     Focus {
         window_id: usize,
         view_id: usize
+    },
+
+    DispatchAction {
+        window_id: usize,
+        path: Vec<usize>,
+        name: String,
+        arg: Box<dyn Any>
     }
 }
 
@@ -2052,6 +2155,21 @@ impl<'a, T: View> ViewContext<'a, T> {
         self.halt_action_dispatch = false;
     }
 
+    /// a cadeia de resposta da ação que está sendo despachada
+    pub fn dispatch_path(&self) -> &[usize] {
+        &self.app.dispatch_path
+    }
+
+    /// despacha uma ação pela cadeia informada depois que os efeitos pendentes forem aplicados
+    pub fn dispatch_action<A: 'static + Any>(&mut self, path: Vec<usize>, name: &str, arg: A) {
+        self.app.pending_effects.push_back(Effect::DispatchAction {
+            window_id: self.window_id,
+            path,
+            name: name.into(),
+            arg: Box::new(arg)
+        });
+    }
+
     pub fn halt_stream(&mut self) {
         self.halt_stream = true;
     }
@@ -2495,6 +2613,10 @@ impl<T: View> WeakViewHandle<T> {
         }
     }
 
+    pub fn id(&self) -> usize {
+        self.view_id
+    }
+
     pub fn upgrade(&self, app: &AppContext) -> Option<ViewHandle<T>> {
         if app
             .windows
@@ -3558,6 +3680,85 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_available_actions() -> Result<()> {
+        struct View {
+            keymap_context: keymap::Context
+        }
+
+        impl Entity for View {
+            type Event = ();
+        }
+
+        impl super::View for View {
+            fn render<'a>(&self, _: &AppContext) -> Box<dyn Element> {
+                Empty::new().boxed()
+            }
+
+            fn ui_name() -> &'static str {
+                "view"
+            }
+
+            fn keymap_context(&self, _: &AppContext) -> keymap::Context {
+                self.keymap_context.clone()
+            }
+        }
+
+        struct OtherView;
+
+        impl Entity for OtherView {
+            type Event = ();
+        }
+
+        impl super::View for OtherView {
+            fn render<'a>(&self, _: &AppContext) -> Box<dyn Element> {
+                Empty::new().boxed()
+            }
+
+            fn ui_name() -> &'static str {
+                "other_view"
+            }
+        }
+
+        App::test((), |mut app| async move {
+            let mut view_1 = View { keymap_context: keymap::Context::default() };
+            let mut view_2 = View { keymap_context: keymap::Context::default() };
+
+            view_1.keymap_context.set.insert("a".into());
+            view_2.keymap_context.set.insert("b".into());
+
+            let (window_id, view_1) = app.add_window(|_| view_1);
+            let view_2 = app.add_view(window_id, |_| view_2);
+
+            app.add_action("view:a", |_: &mut View, _: &(), _| {});
+            app.add_action("view:b", |_: &mut View, _: &(), _| {});
+            app.add_action("view:with_arg", |_: &mut View, _: &usize, _| {});
+            app.add_action("other_view:a", |_: &mut OtherView, _: &(), _| {});
+            app.add_global_action("global:a", |_: &(), _| {});
+
+            app.add_bindings(vec![
+                keymap::Binding::new("x", "view:a", Some("a")),
+                keymap::Binding::new("y", "view:a", Some("b")),
+                keymap::Binding::new("z", "view:b", Some("c")),
+                keymap::Binding::new("cmd-g", "global:a", None)
+            ]);
+
+            let actions = app.update(|ctx| ctx.available_actions(window_id, &[view_1.id(), view_2.id()]));
+
+            assert_eq!(
+                actions,
+
+                vec![
+                    ("global:a".to_string(), Some(vec![Keystroke::parse("cmd-g")?])),
+                    ("view:a".to_string(), Some(vec![Keystroke::parse("y")?])),
+                    ("view:b".to_string(), None)
+                ]
+            );
+
+            Ok(())
+        })
+    }
+
     // #[test]
     // fn test_ui_and_window_updates() {
     //     struct View {
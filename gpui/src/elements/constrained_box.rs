@@ -41,6 +41,13 @@ impl ConstrainedBox {
         self
     }
 
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.constraint.min.set_x(width);
+        self.constraint.max.set_x(width);
+
+        self
+    }
+
     pub fn with_height(mut self, height: f32) -> Self {
         self.constraint.min.set_y(height);
         self.constraint.max.set_y(height);
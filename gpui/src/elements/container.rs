@@ -64,6 +64,12 @@ impl Container {
         self
     }
 
+    pub fn with_padding_left(mut self, padding: f32) -> Self {
+        self.padding.left = padding;
+
+        self
+    }
+
     pub fn with_padding_right(mut self, padding: f32) -> Self {
         self.padding.right = padding;
 
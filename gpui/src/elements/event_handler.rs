@@ -18,6 +18,8 @@ use std::cell::RefCell;
 pub struct EventHandler {
     child: Box<dyn Element>,
     mouse_down: Option<RefCell<Box<dyn FnMut(&mut EventContext, &AppContext) -> bool>>>,
+    mouse_up: Option<RefCell<Box<dyn FnMut(&mut EventContext, &AppContext) -> bool>>>,
+    hover: Option<RefCell<Box<dyn FnMut(bool, &mut EventContext, &AppContext) -> bool>>>,
     origin: Option<Vector2F>
 }
 
@@ -27,6 +29,8 @@ impl EventHandler {
             child,
 
             mouse_down: None,
+            mouse_up: None,
+            hover: None,
             origin: None
         }
     }
@@ -39,6 +43,32 @@ impl EventHandler {
 
         self
     }
+
+    /// chamado ao soltar o botão sobre o elemento, mesmo que um filho trate o evento, para que
+    /// arrastos sempre terminem
+    pub fn on_mouse_up<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(&mut EventContext, &AppContext) -> bool
+    {
+        self.mouse_up = Some(RefCell::new(Box::new(callback)));
+
+        self
+    }
+
+    /// chamado a cada movimento do mouse, com ou sem botão pressionado, indicando se o cursor
+    /// está sobre o elemento
+    pub fn on_hover<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(bool, &mut EventContext, &AppContext) -> bool
+    {
+        self.hover = Some(RefCell::new(Box::new(callback)));
+
+        self
+    }
+
+    fn contains_point(&self, position: Vector2F) -> bool {
+        try_rect(self.origin, self.size()).unwrap().contains_point(position)
+    }
 }
 
 impl Element for EventHandler {
@@ -67,12 +97,13 @@ impl Element for EventHandler {
     }
 
     fn dispatch_event(&self, event: &Event, ctx: &mut EventContext, app: &AppContext) -> bool {
+        let handled = self.child.dispatch_event(event, ctx, app);
+
         match event {
-            Event::LeftMouseDown { position, .. } => {
+            // um clique tratado por um filho, como um botão, não chega ao elemento
+            Event::LeftMouseDown { position, .. } if !handled => {
                 if let Some(callback) = self.mouse_down.as_ref() {
-                    let rect = try_rect(self.origin, self.size()).unwrap();
-
-                    if rect.contains_point(*position) {
+                    if self.contains_point(*position) {
                         return callback.borrow_mut()(ctx, app);
                     }
                 }
@@ -80,7 +111,27 @@ impl Element for EventHandler {
                 false
             }
 
-            _ => false
+            Event::LeftMouseUp { position } => {
+                if let Some(callback) = self.mouse_up.as_ref() {
+                    if self.contains_point(*position) {
+                        return callback.borrow_mut()(ctx, app) || handled;
+                    }
+                }
+
+                handled
+            }
+
+            Event::MouseMoved { position } | Event::LeftMouseDragged { position } => {
+                if let Some(callback) = self.hover.as_ref() {
+                    let hovered = self.contains_point(*position);
+
+                    return callback.borrow_mut()(hovered, ctx, app) || handled;
+                }
+
+                handled
+            }
+
+            _ => handled
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,220 @@
+use super::{
+    try_rect,
+
+    AfterLayoutContext,
+    AppContext,
+    Element,
+    Event,
+    EventContext,
+    LayoutContext,
+    MutableAppContext,
+    PaintContext,
+    SizeConstraint
+};
+
+use crate::geometry::{
+    rect::RectF,
+    vector::{vec2f, Vector2F}
+};
+
+use parking_lot::Mutex;
+use std::{ops::Range, sync::Arc};
+
+#[derive(Clone)]
+pub struct HorizontalListState(Arc<Mutex<StateInner>>);
+
+struct StateInner {
+    scroll_left: f32,
+    scroll_to: Option<usize>
+}
+
+impl HorizontalListState {
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(StateInner {
+            scroll_left: 0.0,
+            scroll_to: None
+        })))
+    }
+
+    pub fn scroll_to(&self, child_ix: usize) {
+        self.0.lock().scroll_to = Some(child_ix);
+    }
+}
+
+/// linha de elementos que rola horizontalmente quando não cabe na largura disponível
+pub struct HorizontalList {
+    state: HorizontalListState,
+    children: Vec<Box<dyn Element>>,
+    child_offsets: Vec<f32>,
+    scroll_max: f32,
+
+    // filhos que aparecem na área visível e, portanto, foram pintados
+    visible_children: Range<usize>,
+
+    origin: Option<Vector2F>,
+    size: Option<Vector2F>
+}
+
+impl HorizontalList {
+    pub fn new(state: HorizontalListState) -> Self {
+        Self {
+            state,
+            children: Vec::new(),
+            child_offsets: Vec::new(),
+            scroll_max: 0.0,
+            visible_children: 0..0,
+
+            origin: None,
+            size: None
+        }
+    }
+
+    fn scroll(&self, position: Vector2F, delta: Vector2F, ctx: &mut EventContext) -> bool {
+        if !self.rect().unwrap().contains_point(position) {
+            return false;
+        }
+
+        // a roda de um mouse comum só rola verticalmente
+        let delta = if delta.x() != 0.0 { delta.x() } else { delta.y() };
+
+        let mut state = self.state.0.lock();
+
+        state.scroll_left = (state.scroll_left - delta).max(0.0).min(self.scroll_max);
+
+        ctx.dispatch_action("horizontal_list:scroll", state.scroll_left);
+
+        true
+    }
+
+    fn autoscroll(&mut self, list_width: f32) {
+        let mut state = self.state.0.lock();
+
+        if let Some(child_ix) = state.scroll_to.take() {
+            if let (Some(child_left), Some(child)) =
+                (self.child_offsets.get(child_ix), self.children.get(child_ix))
+            {
+                let child_right = child_left + child.size().unwrap().x();
+
+                if *child_left < state.scroll_left {
+                    state.scroll_left = *child_left;
+                } else if child_right > state.scroll_left + list_width {
+                    state.scroll_left = child_right - list_width;
+                }
+            }
+        }
+
+        state.scroll_left = state.scroll_left.max(0.0).min(self.scroll_max);
+    }
+
+    fn scroll_left(&self) -> f32 {
+        self.state.0.lock().scroll_left
+    }
+
+    fn rect(&self) -> Option<RectF> {
+        try_rect(self.origin, self.size)
+    }
+}
+
+impl Extend<Box<dyn Element>> for HorizontalList {
+    fn extend<T: IntoIterator<Item = Box<dyn Element>>>(&mut self, children: T) {
+        self.children.extend(children)
+    }
+}
+
+impl Element for HorizontalList {
+    fn layout(
+        &mut self,
+
+        constraint: SizeConstraint,
+        ctx: &mut LayoutContext,
+        app: &AppContext
+    ) -> Vector2F {
+        if constraint.max.x().is_infinite() {
+            unimplemented!(
+                "horizontallist não suporta renderização com largura irrestrita"
+            );
+        }
+
+        let child_constraint = SizeConstraint::new(
+            vec2f(0.0, constraint.min.y()),
+            vec2f(f32::INFINITY, constraint.max.y())
+        );
+
+        let mut width = 0.0;
+        let mut height = constraint.min.y();
+
+        self.child_offsets.clear();
+
+        for child in &mut self.children {
+            self.child_offsets.push(width);
+
+            let child_size = child.layout(child_constraint, ctx, app);
+
+            width += child_size.x();
+            height = height.max(child_size.y());
+        }
+
+        let size = vec2f(constraint.max.x(), height);
+
+        self.scroll_max = (width - size.x()).max(0.0);
+        self.autoscroll(size.x());
+
+        self.size = Some(size);
+
+        size
+    }
+
+    fn after_layout(&mut self, ctx: &mut AfterLayoutContext, app: &mut MutableAppContext) {
+        for child in &mut self.children {
+            child.after_layout(ctx, app);
+        }
+    }
+
+    fn paint(&mut self, origin: Vector2F, ctx: &mut PaintContext, app: &AppContext) {
+        self.origin = Some(origin);
+
+        let scroll_left = self.scroll_left();
+        let list_width = self.size.unwrap().x();
+
+        let mut start = self.children.len();
+        let mut end = 0;
+
+        let children = self.children.iter_mut().zip(&self.child_offsets);
+
+        for (ix, (child, child_left)) in children.enumerate() {
+            let left = child_left - scroll_left;
+            let right = left + child.size().unwrap().x();
+
+            if right > 0.0 && left < list_width {
+                child.paint(origin + vec2f(left, 0.0), ctx, app);
+
+                start = start.min(ix);
+                end = ix + 1;
+            }
+        }
+
+        self.visible_children = start.min(end)..end;
+    }
+
+    fn size(&self) -> Option<Vector2F> {
+        self.size
+    }
+
+    fn dispatch_event(&self, event: &Event, ctx: &mut EventContext, app: &AppContext) -> bool {
+        let mut handled = false;
+
+        for child in &self.children[self.visible_children.clone()] {
+            if child.dispatch_event(event, ctx, app) {
+                handled = true;
+            }
+        }
+
+        if let Event::ScrollWheel { position, delta, .. } = event {
+            if self.scroll(*position, *delta, ctx) {
+                handled = true;
+            }
+        }
+
+        handled
+    }
+}
@@ -26,6 +26,7 @@ pub struct Label {
     highlights: Option<Highlights>,
     layout_line: Option<Arc<Line>>,
     colors: Option<Vec<(Range<usize>, ColorU)>>,
+    color: ColorU,
     size: Option<Vector2F>
 }
 
@@ -46,10 +47,23 @@ impl Label {
             highlights: None,
             layout_line: None,
             colors: None,
+            color: ColorU::black(),
             size: None
         }
     }
 
+    pub fn with_default_color(mut self, color: ColorU) -> Self {
+        self.color = color;
+
+        self
+    }
+
+    pub fn with_font_properties(mut self, font_properties: Properties) -> Self {
+        self.font_properties = font_properties;
+
+        self
+    }
+
     pub fn with_highlights(
         mut self,
 
@@ -105,13 +119,13 @@ impl Element for Label {
                         colors.push((pending_highlight.clone(), highlights.color));
 
                         styles.push((pending_highlight.end..*ix, font_id));
-                        colors.push((pending_highlight.end..*ix, ColorU::black()));
+                        colors.push((pending_highlight.end..*ix, self.color));
 
                         *pending_highlight = *ix..*ix + 1;
                     }
                 } else {
                     styles.push((0..*ix, font_id));
-                    colors.push((0..*ix, ColorU::black()));
+                    colors.push((0..*ix, self.color));
 
                     pending_highlight = Some(*ix..*ix + 1);
                 }
@@ -124,16 +138,16 @@ impl Element for Label {
                 if text_len > pending_highlight.end {
                     styles.push((pending_highlight.end..text_len, font_id));
 
-                    colors.push((pending_highlight.end..text_len, ColorU::black()));
+                    colors.push((pending_highlight.end..text_len, self.color));
                 }
             } else {
                 styles.push((0..text_len, font_id));
 
-                colors.push((0..text_len, ColorU::black()));
+                colors.push((0..text_len, self.color));
             }
         } else {
             styles = vec![(0..text_len, font_id)];
-            colors = vec![(0..text_len, ColorU::black())];
+            colors = vec![(0..text_len, self.color)];
         }
 
         self.colors = Some(colors);
@@ -4,8 +4,10 @@ mod container;
 mod empty;
 mod event_handler;
 mod flex;
+mod horizontal_list;
 mod label;
 mod line_box;
+mod split;
 mod stack;
 mod svg;
 mod uniform_list;
@@ -17,8 +19,10 @@ pub use container::*;
 pub use empty::*;
 pub use event_handler::*;
 pub use flex::*;
+pub use horizontal_list::*;
 pub use label::*;
 pub use line_box::*;
+pub use split::*;
 pub use stack::*;
 pub use svg::*;
 pub use uniform_list::*;
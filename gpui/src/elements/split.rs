@@ -0,0 +1,346 @@
+use crate::{
+    color::ColorU,
+
+    geometry::{
+        rect::RectF,
+        vector::{vec2f, Vector2F}
+    },
+
+    scene::{Border, Quad},
+
+    AfterLayoutContext,
+    AppContext,
+    Axis,
+    Element,
+    Event,
+    EventContext,
+    LayoutContext,
+    MutableAppContext,
+    PaintContext,
+    SizeConstraint,
+    Vector2FExt
+};
+
+use std::cell::RefCell;
+
+// folga de cada lado da divisória, para que ela seja fácil de agarrar
+const DIVIDER_GRAB_MARGIN: f32 = 3.0;
+
+/// divide o espaço entre os filhos segundo as proporções, com divisórias arrastáveis entre eles
+pub struct Split {
+    axis: Axis,
+    ratios: Vec<f32>,
+    children: Vec<Box<dyn Element>>,
+    min_child_size: f32,
+
+    divider_width: f32,
+    divider_color: ColorU,
+
+    // o arrasto sobrevive a novas renderizações, então quem renderiza informa a divisória
+    dragged_divider: Option<usize>,
+
+    drag_start: Option<RefCell<Box<dyn FnMut(usize, &mut EventContext, &AppContext) -> bool>>>,
+    resize: Option<RefCell<Box<dyn FnMut(Vec<f32>, &mut EventContext, &AppContext) -> bool>>>,
+    layout_callback: Option<Box<dyn FnMut(Vec<RectF>)>>,
+
+    child_offsets: Vec<f32>,
+    origin: Option<Vector2F>,
+    size: Option<Vector2F>
+}
+
+impl Split {
+    pub fn new(axis: Axis, ratios: Vec<f32>) -> Self {
+        Self {
+            axis,
+            ratios,
+            children: Vec::new(),
+            min_child_size: 0.0,
+
+            divider_width: 0.0,
+            divider_color: ColorU::transparent_black(),
+
+            dragged_divider: None,
+
+            drag_start: None,
+            resize: None,
+            layout_callback: None,
+
+            child_offsets: Vec::new(),
+            origin: None,
+            size: None
+        }
+    }
+
+    pub fn with_divider(mut self, width: f32, color: impl Into<ColorU>) -> Self {
+        self.divider_width = width;
+        self.divider_color = color.into();
+
+        self
+    }
+
+    /// tamanho abaixo do qual um filho não encolhe, nem ao arrastar uma divisória nem quando as
+    /// proporções o deixariam menor, enquanto houver espaço para todos os filhos
+    pub fn with_min_child_size(mut self, size: f32) -> Self {
+        self.min_child_size = size;
+
+        self
+    }
+
+    pub fn with_dragged_divider(mut self, divider_ix: Option<usize>) -> Self {
+        self.dragged_divider = divider_ix;
+
+        self
+    }
+
+    /// chamado ao pressionar o botão sobre a divisória que segue o filho de índice informado
+    pub fn on_drag_start<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(usize, &mut EventContext, &AppContext) -> bool
+    {
+        self.drag_start = Some(RefCell::new(Box::new(callback)));
+
+        self
+    }
+
+    /// chamado com as novas proporções enquanto a divisória informada em
+    /// `with_dragged_divider` é arrastada
+    pub fn on_resize<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(Vec<f32>, &mut EventContext, &AppContext) -> bool
+    {
+        self.resize = Some(RefCell::new(Box::new(callback)));
+
+        self
+    }
+
+    /// chamado a cada layout com o retângulo de cada filho, relativo à origem do split
+    pub fn on_layout<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(Vec<RectF>)
+    {
+        self.layout_callback = Some(Box::new(callback));
+
+        self
+    }
+
+    fn ratio(&self, child_ix: usize) -> f32 {
+        self.ratios.get(child_ix).copied().unwrap_or(1.0)
+    }
+
+    /// vetor com `main` ao longo do eixo e `cross` no eixo transversal
+    fn vec_along(&self, main: f32, cross: f32) -> Vector2F {
+        match self.axis {
+            Axis::Horizontal => vec2f(main, cross),
+            Axis::Vertical => vec2f(cross, main)
+        }
+    }
+
+    /// comprimento de cada filho ao longo do eixo segundo as proporções. os que ficariam abaixo
+    /// do tamanho mínimo recebem o mínimo e o restante é repartido entre os demais
+    fn child_lengths(&self, available: f32) -> Vec<f32> {
+        let count = self.children.len();
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let min = self.min_child_size.min(available / count as f32);
+        let mut clamped = vec![false; count];
+
+        loop {
+            let clamped_count = clamped.iter().filter(|clamped| **clamped).count();
+
+            let free_total = available - min * clamped_count as f32;
+            let free_weight = (0..count)
+                .filter(|ix| !clamped[*ix])
+                .map(|ix| self.ratio(ix))
+                .sum::<f32>();
+
+            let length = |ix: usize| {
+                if free_weight > 0.0 {
+                    free_total * self.ratio(ix) / free_weight
+                } else {
+                    free_total / (count - clamped_count) as f32
+                }
+            };
+
+            let mut changed = false;
+
+            for (ix, clamped) in clamped.iter_mut().enumerate() {
+                if !*clamped && length(ix) < min {
+                    *clamped = true;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return clamped
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, clamped)| if *clamped { min } else { length(ix) })
+                    .collect();
+            }
+        }
+    }
+
+    fn divider_rect(&self, divider_ix: usize, margin: f32) -> RectF {
+        let start = self.child_offsets[divider_ix + 1] - self.divider_width - margin;
+        let cross = self.size.unwrap().along(self.axis.invert());
+
+        RectF::new(
+            self.origin.unwrap() + self.vec_along(start, 0.0),
+            self.vec_along(self.divider_width + margin * 2.0, cross)
+        )
+    }
+
+    fn divider_at(&self, position: Vector2F) -> Option<usize> {
+        (0..self.children.len().saturating_sub(1))
+            .find(|ix| self.divider_rect(*ix, DIVIDER_GRAB_MARGIN).contains_point(position))
+    }
+
+    /// proporções com a divisória movida para `position`, redistribuindo apenas o espaço dos
+    /// dois filhos vizinhos a ela
+    fn drag_ratios(&self, divider_ix: usize, position: Vector2F) -> Vec<f32> {
+        let mut ratios = (0..self.children.len()).map(|ix| self.ratio(ix)).collect::<Vec<_>>();
+
+        let start = self.child_offsets[divider_ix];
+        let end = self.child_offsets[divider_ix + 1]
+            + self.children[divider_ix + 1].size().unwrap().along(self.axis);
+
+        let combined = end - start - self.divider_width;
+
+        if combined <= 0.0 {
+            return ratios;
+        }
+
+        let min = self.min_child_size.min(combined / 2.0);
+
+        let first = position.along(self.axis)
+            - self.origin.unwrap().along(self.axis)
+            - start
+            - self.divider_width / 2.0;
+
+        let first = first.max(min).min(combined - min);
+        let pair = ratios[divider_ix] + ratios[divider_ix + 1];
+
+        ratios[divider_ix] = pair * first / combined;
+        ratios[divider_ix + 1] = pair - ratios[divider_ix];
+
+        ratios
+    }
+}
+
+impl Extend<Box<dyn Element>> for Split {
+    fn extend<T: IntoIterator<Item = Box<dyn Element>>>(&mut self, children: T) {
+        self.children.extend(children);
+    }
+}
+
+impl Element for Split {
+    fn layout(
+        &mut self,
+
+        constraint: SizeConstraint,
+        ctx: &mut LayoutContext,
+        app: &AppContext
+    ) -> Vector2F {
+        if constraint.max.x().is_infinite() || constraint.max.y().is_infinite() {
+            unimplemented!("split não suporta renderização com tamanho irrestrito");
+        }
+
+        let cross = constraint.max_along(self.axis.invert());
+        let dividers = self.divider_width * self.children.len().saturating_sub(1) as f32;
+        let available = (constraint.max_along(self.axis) - dividers).max(0.0);
+
+        let lengths = self.child_lengths(available);
+
+        let mut offset = 0.0;
+        let mut child_bounds = Vec::new();
+
+        self.child_offsets.clear();
+
+        for (ix, length) in lengths.into_iter().enumerate() {
+            let child_size = self.vec_along(length, cross);
+
+            self.child_offsets.push(offset);
+            self.children[ix].layout(SizeConstraint::strict(child_size), ctx, app);
+
+            child_bounds.push(RectF::new(self.vec_along(offset, 0.0), child_size));
+
+            offset += length + self.divider_width;
+        }
+
+        self.size = Some(constraint.max);
+
+        if let Some(callback) = self.layout_callback.as_mut() {
+            callback(child_bounds);
+        }
+
+        constraint.max
+    }
+
+    fn after_layout(&mut self, ctx: &mut AfterLayoutContext, app: &mut MutableAppContext) {
+        for child in &mut self.children {
+            child.after_layout(ctx, app);
+        }
+    }
+
+    fn paint(&mut self, origin: Vector2F, ctx: &mut PaintContext, app: &AppContext) {
+        self.origin = Some(origin);
+
+        for ix in 0..self.children.len() {
+            let child_origin = origin + self.vec_along(self.child_offsets[ix], 0.0);
+
+            self.children[ix].paint(child_origin, ctx, app);
+
+            if ix + 1 < self.children.len() {
+                ctx.scene.push_quad(Quad {
+                    bounds: self.divider_rect(ix, 0.0),
+                    background: Some(self.divider_color),
+
+                    border: Border::default(),
+                    corner_radius: 0.0
+                });
+            }
+        }
+    }
+
+    fn size(&self) -> Option<Vector2F> {
+        self.size
+    }
+
+    fn dispatch_event(&self, event: &Event, ctx: &mut EventContext, app: &AppContext) -> bool {
+        match event {
+            // a divisória tem prioridade sobre os filhos dentro da folga
+            Event::LeftMouseDown { position, .. } => {
+                if let (Some(divider_ix), Some(callback)) =
+                    (self.divider_at(*position), self.drag_start.as_ref())
+                {
+                    return callback.borrow_mut()(divider_ix, ctx, app);
+                }
+            }
+
+            Event::LeftMouseDragged { position } => {
+                if let (Some(divider_ix), Some(callback)) = (self.dragged_divider, self.resize.as_ref()) {
+                    if divider_ix + 1 < self.children.len() {
+                        let ratios = self.drag_ratios(divider_ix, *position);
+
+                        return callback.borrow_mut()(ratios, ctx, app);
+                    }
+                }
+            }
+
+            _ => {}
+        }
+
+        let mut handled = false;
+
+        for child in &self.children {
+            if child.dispatch_event(event, ctx, app) {
+                handled = true;
+            }
+        }
+
+        handled
+    }
+}
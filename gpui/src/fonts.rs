@@ -2,7 +2,7 @@ use crate::geometry::vector::{vec2f, Vector2F};
 use anyhow::{anyhow, Result};
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 
-pub use font_kit::properties::{Properties, Weight};
+pub use font_kit::properties::{Properties, Style, Weight};
 use font_kit::{font::Font, loaders::core_text::NativeFont, metrics::Metrics, source::SystemSource,};
 use ordered_float::OrderedFloat;
 use std::{collections::HashMap, sync::Arc};
@@ -2,7 +2,8 @@ use anyhow::anyhow;
 
 use std::{
     any::Any,
-    collections::{HashMap, HashSet}
+    collections::{HashMap, HashSet},
+    fmt
 };
 
 use tree_sitter::{Language, Node, Parser};
@@ -22,8 +23,10 @@ struct Pending {
     context: Option<Context>
 }
 
+#[derive(Clone)]
 pub struct Keymap(Vec<Binding>);
 
+#[derive(Clone)]
 pub struct Binding {
     keystrokes: Vec<Keystroke>,
     action: String,
@@ -47,7 +50,7 @@ pub struct Context {
     pub map: HashMap<String, String>
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 enum ContextPredicate {
     Identifier(String),
 
@@ -62,6 +65,7 @@ enum ContextPredicate {
 
 trait ActionArg {
     fn boxed_clone(&self) -> Box<dyn Any>;
+    fn boxed_clone_arg(&self) -> Box<dyn ActionArg>;
 }
 
 impl<T> ActionArg for T
@@ -71,6 +75,16 @@ where
     fn boxed_clone(&self) -> Box<dyn Any> {
         Box::new(self.clone())
     }
+
+    fn boxed_clone_arg(&self) -> Box<dyn ActionArg> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ActionArg> {
+    fn clone(&self) -> Self {
+        self.boxed_clone_arg()
+    }
 }
 
 pub enum MatchResult {
@@ -120,13 +134,7 @@ impl Matcher {
         let mut retain_pending = false;
 
         for binding in self.keymap.0.iter().rev() {
-            if binding.keystrokes.starts_with(&pending.keystrokes)
-                && binding
-                    .context
-                    .as_ref()
-                    .map(|c| c.eval(ctx))
-                    .unwrap_or(true)
-            {
+            if binding.keystrokes.starts_with(&pending.keystrokes) && binding.matches_context(ctx) {
                 if binding.keystrokes.len() == pending.keystrokes.len() {
                     self.pending.remove(&view_id);
 
@@ -150,6 +158,10 @@ impl Matcher {
             MatchResult::None
         }
     }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
 }
 
 impl Default for Matcher {
@@ -166,6 +178,20 @@ impl Keymap {
     fn add_bindings<T: IntoIterator<Item = Binding>>(&mut self, bindings: T) {
         self.0.extend(bindings.into_iter());
     }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.0
+    }
+
+    /// retorna o binding que dispararia a ação no contexto informado
+    ///
+    /// bindings adicionados depois têm precedência, assim como em `Matcher::push_keystroke`
+    pub fn binding_for_action(&self, action: &str, ctx: &Context) -> Option<&Binding> {
+        self.0
+            .iter()
+            .rev()
+            .find(|binding| binding.action == action && binding.matches_context(ctx))
+    }
 }
 
 impl Default for Keymap {
@@ -181,23 +207,39 @@ impl Default for Keymap {
 
 impl Binding {
     pub fn new<S: Into<String>>(keystrokes: &str, action: S, context: Option<&str>) -> Self {
+        Self::load(keystrokes, action, context).unwrap()
+    }
+
+    /// assim como `new`, mas retorna um erro ao invés de entrar em pânico quando as teclas
+    /// ou o predicado de contexto são inválidos
+    pub fn load<S: Into<String>>(
+        keystrokes: &str,
+        action: S,
+        context: Option<&str>
+    ) -> anyhow::Result<Self> {
         let context = if let Some(context) = context {
-            Some(ContextPredicate::parse(context).unwrap())
+            Some(ContextPredicate::parse(context)?)
         } else {
             None
         };
 
-        Self {
-            keystrokes: keystrokes
-                .split_whitespace()
-                .map(|key| Keystroke::parse(key).unwrap())
-                .collect(),
-            
+        let keystrokes = keystrokes
+            .split_whitespace()
+            .map(Keystroke::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        if keystrokes.is_empty() {
+            return Err(anyhow!("binding sem teclas"));
+        }
+
+        Ok(Self {
+            keystrokes,
+
             action: action.into(),
             action_arg: None,
-            
+
             context
-        }
+        })
     }
 
     pub fn with_arg<T: 'static + Any + Clone>(mut self, arg: T) -> Self {
@@ -205,6 +247,21 @@ impl Binding {
 
         self
     }
+
+    pub fn keystrokes(&self) -> &[Keystroke] {
+        &self.keystrokes
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn matches_context(&self, ctx: &Context) -> bool {
+        self.context
+            .as_ref()
+            .map(|c| c.eval(ctx))
+            .unwrap_or(true)
+    }
 }
 
 impl Keystroke {
@@ -247,11 +304,33 @@ impl Keystroke {
             shift,
             cmd,
 
-            key: key.unwrap()
+            key: key.ok_or_else(|| anyhow!("keystroke inválido `{}`", source))?
         })
     }
 }
 
+impl fmt::Display for Keystroke {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "ctrl-")?;
+        }
+
+        if self.alt {
+            write!(f, "alt-")?;
+        }
+
+        if self.shift {
+            write!(f, "shift-")?;
+        }
+
+        if self.cmd {
+            write!(f, "cmd-")?;
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
 impl Context {
     pub fn extend(&mut self, other: Context) {
         for v in other.set {
@@ -271,10 +350,14 @@ impl ContextPredicate {
 
         parser.set_language(language).unwrap();
         
-        let source = source.as_bytes();
-        let tree = parser.parse(source, None).unwrap();
-        
-        Self::from_node(tree.root_node(), source)
+        let source_bytes = source.as_bytes();
+        let tree = parser.parse(source_bytes, None).unwrap();
+
+        if tree.root_node().has_error() {
+            return Err(anyhow!("predicado de contexto inválido `{}`", source));
+        }
+
+        Self::from_node(tree.root_node(), source_bytes)
     }
 
     fn from_node(node: Node, source: &[u8]) -> anyhow::Result<Self> {
@@ -421,6 +504,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_invalid_bindings() {
+        assert!(Keystroke::parse("cmd").is_err());
+        assert!(Keystroke::parse("cmd-a-b").is_err());
+
+        assert!(Binding::load("", "a", None).is_err());
+        assert!(Binding::load("cmd-a cmd", "a", None).is_err());
+        assert!(Binding::load("cmd-a", "a", Some("a &&")).is_err());
+
+        let binding = Binding::load("cmd-k up", "a", Some("a && !b")).unwrap();
+
+        assert_eq!(binding.keystrokes().len(), 2);
+        assert_eq!(binding.action(), "a");
+    }
+
+    #[test]
+    fn test_keystroke_display() -> anyhow::Result<()> {
+        assert_eq!(Keystroke::parse("cmd-shift-p")?.to_string(), "shift-cmd-p");
+        assert_eq!(Keystroke::parse("ctrl-alt-down")?.to_string(), "ctrl-alt-down");
+        assert_eq!(Keystroke::parse("shift-cmd--")?.to_string(), "shift-cmd--");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binding_for_action() {
+        let keymap = Keymap(vec![
+            Binding::new("a", "x", Some("a")),
+            Binding::new("b", "x", Some("b")),
+            Binding::new("c", "x", Some("a && b")),
+            Binding::new("d", "y", None)
+        ]);
+
+        let mut ctx = Context::default();
+        ctx.set.insert("a".into());
+
+        let keystrokes = |binding: Option<&Binding>| binding.map(|b| b.keystrokes().to_vec());
+
+        assert_eq!(keystrokes(keymap.binding_for_action("x", &ctx)), Some(vec![Keystroke::parse("a").unwrap()]));
+        assert_eq!(keystrokes(keymap.binding_for_action("y", &ctx)), Some(vec![Keystroke::parse("d").unwrap()]));
+        assert_eq!(keystrokes(keymap.binding_for_action("z", &ctx)), None);
+
+        ctx.set.insert("b".into());
+
+        assert_eq!(keystrokes(keymap.binding_for_action("x", &ctx)), Some(vec![Keystroke::parse("c").unwrap()]));
+    }
+
     #[test]
     fn test_context_predicate_parsing() -> anyhow::Result<()> {
         use ContextPredicate::*;
@@ -1,5 +1,14 @@
 use crate::{geometry::vector::Vector2F, keymap::Keystroke};
 
+/// teclas modificadoras pressionadas, enviadas como argumento da ação `modifiers_changed`
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub cmd: bool
+}
+
 #[derive(Debug)]
 pub enum Event {
     KeyDown {
@@ -7,6 +16,10 @@ pub enum Event {
         chars: String
     },
 
+    ModifiersChanged {
+        modifiers: Modifiers
+    },
+
     ScrollWheel {
         position: Vector2F,
         delta: Vector2F,
@@ -15,7 +28,13 @@ pub enum Event {
 
     LeftMouseDown {
         position: Vector2F,
-        cmd: bool
+
+        ctrl: bool,
+        alt: bool,
+        shift: bool,
+        cmd: bool,
+
+        click_count: usize
     },
 
     LeftMouseUp {
@@ -24,5 +43,10 @@ pub enum Event {
 
     LeftMouseDragged {
         position: Vector2F
+    },
+
+    // movimento do mouse sem nenhum botão pressionado
+    MouseMoved {
+        position: Vector2F
     }
 }
\ No newline at end of file
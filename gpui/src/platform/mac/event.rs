@@ -1,4 +1,8 @@
-use crate::{geometry::vector::vec2f, keymap::Keystroke, platform::Event};
+use crate::{
+    geometry::vector::vec2f,
+    keymap::Keystroke,
+    platform::{Event, Modifiers}
+};
 
 use cocoa::appkit::{
     NSDeleteFunctionKey as DELETE_KEY, NSDownArrowFunctionKey as ARROW_DOWN_KEY,
@@ -15,6 +19,8 @@ use cocoa::{
 
 use std::{ffi::CStr, os::raw::c_char};
 
+const TAB_KEY: u16 = 0x09;
+const BACKTAB_KEY: u16 = 0x19;
 const BACKSPACE_KEY: u16 = 0x7f;
 const ENTER_KEY: u16 = 0x0d;
 const ESCAPE_KEY: u16 = 0x1b;
@@ -51,6 +57,8 @@ impl Event {
                         PAGE_UP_KEY => "pageup",
                         PAGE_DOWN_KEY => "pagedown",
                         
+                        // com shift, o tab chega como backtab
+                        TAB_KEY | BACKTAB_KEY => "tab",
                         BACKSPACE_KEY => "backspace",
                         ENTER_KEY => "enter",
                         DELETE_KEY => "delete",
@@ -83,16 +91,34 @@ impl Event {
                 })
             }
 
+            NSEventType::NSFlagsChanged => {
+                let modifiers = native_event.modifierFlags();
+
+                Some(Self::ModifiersChanged {
+                    modifiers: Modifiers {
+                        ctrl: modifiers.contains(NSEventModifierFlags::NSControlKeyMask),
+                        alt: modifiers.contains(NSEventModifierFlags::NSAlternateKeyMask),
+                        shift: modifiers.contains(NSEventModifierFlags::NSShiftKeyMask),
+                        cmd: modifiers.contains(NSEventModifierFlags::NSCommandKeyMask)
+                    }
+                })
+            }
+
             NSEventType::NSLeftMouseDown => {
+                let modifiers = native_event.modifierFlags();
+
                 window_height.map(|window_height| Self::LeftMouseDown {
                     position: vec2f(
                         native_event.locationInWindow().x as f32,
                         window_height - native_event.locationInWindow().y as f32
                     ),
 
-                    cmd: native_event
-                        .modifierFlags()
-                        .contains(NSEventModifierFlags::NSCommandKeyMask)
+                    ctrl: modifiers.contains(NSEventModifierFlags::NSControlKeyMask),
+                    alt: modifiers.contains(NSEventModifierFlags::NSAlternateKeyMask),
+                    shift: modifiers.contains(NSEventModifierFlags::NSShiftKeyMask),
+                    cmd: modifiers.contains(NSEventModifierFlags::NSCommandKeyMask),
+
+                    click_count: native_event.clickCount() as usize
                 })
             }
 
@@ -112,6 +138,13 @@ impl Event {
                 })
             }
 
+            NSEventType::NSMouseMoved => window_height.map(|window_height| Self::MouseMoved {
+                position: vec2f(
+                    native_event.locationInWindow().x as f32,
+                    window_height - native_event.locationInWindow().y as f32
+                )
+            }),
+
             NSEventType::NSScrollWheel => window_height.map(|window_height| Self::ScrollWheel {
                 position: vec2f(
                     native_event.locationInWindow().x as f32,
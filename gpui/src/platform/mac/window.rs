@@ -95,6 +95,12 @@ unsafe fn build_classes() {
             handle_view_event as extern "C" fn(&Object, Sel, id)
         );
 
+        decl.add_method(
+            sel!(flagsChanged:),
+
+            handle_view_event as extern "C" fn(&Object, Sel, id)
+        );
+
         decl.add_method(
             sel!(mouseDown:),
 
@@ -113,6 +119,12 @@ unsafe fn build_classes() {
             handle_view_event as extern "C" fn(&Object, Sel, id)
         );
 
+        decl.add_method(
+            sel!(mouseMoved:),
+
+            handle_view_event as extern "C" fn(&Object, Sel, id)
+        );
+
         decl.add_method(
             sel!(scrollWheel:),
 
@@ -16,7 +16,7 @@ use crate::{
 
 use anyhow::Result;
 use async_task::Runnable;
-pub use event::Event;
+pub use event::{Event, Modifiers};
 use std::{path::PathBuf, rc::Rc, sync::Arc};
 
 pub trait Runner {
@@ -0,0 +1,500 @@
+use crate::{
+    editor::{buffer_view, BufferView},
+    settings::Settings,
+    watch,
+    workspace::{ItemViewHandle, Pane, WorkspaceView},
+    worktree::{match_strings, StringMatch, StringMatchCandidate}
+};
+
+use gpui::{
+    elements::*,
+    fonts::{Properties, Weight},
+    geometry::vector::vec2f,
+    keymap::{self, Binding},
+    platform::Modifiers,
+    App, AppContext, Axis, Border, Entity, View, ViewContext, ViewHandle, WeakViewHandle
+};
+
+use std::cmp;
+
+pub struct BufferSwitcher {
+    handle: WeakViewHandle<Self>,
+    settings: watch::Receiver<Settings>,
+    query_buffer: ViewHandle<BufferView>,
+    items: Vec<(ViewHandle<Pane>, Box<dyn ItemViewHandle>)>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected: usize,
+    list_state: UniformListState,
+
+    // aberto com ctrl-tab, o switcher confirma a seleção quando o ctrl é solto
+    commit_on_release: bool
+}
+
+pub fn init(app: &mut App) {
+    app.add_action("buffer_switcher:toggle", BufferSwitcher::toggle);
+    app.add_action("buffer_switcher:cycle", BufferSwitcher::open_cycling_next);
+    app.add_action("buffer_switcher:cycle_prev", BufferSwitcher::open_cycling_prev);
+
+    app.add_action("buffer_switcher:cycle", BufferSwitcher::cycle_next);
+    app.add_action("buffer_switcher:cycle_prev", BufferSwitcher::cycle_prev);
+    app.add_action("buffer_switcher:confirm", BufferSwitcher::confirm);
+    app.add_action("buffer_switcher:select", BufferSwitcher::select);
+    app.add_action("modifiers_changed", BufferSwitcher::modifiers_changed);
+
+    app.add_action("buffer:move_up", BufferSwitcher::select_prev);
+    app.add_action("buffer:move_down", BufferSwitcher::select_next);
+    app.add_action("uniform_list:scroll", BufferSwitcher::scroll);
+
+    app.add_bindings(vec![
+        Binding::new("ctrl-tab", "buffer_switcher:cycle", None),
+        Binding::new("ctrl-shift-tab", "buffer_switcher:cycle_prev", None),
+
+        Binding::new("escape", "buffer_switcher:toggle", Some("BufferSwitcher")),
+        Binding::new("enter", "buffer_switcher:confirm", Some("BufferSwitcher"))
+    ]);
+}
+
+pub enum Event {
+    Selected(ViewHandle<Pane>, usize),
+
+    Dismissed
+}
+
+impl Entity for BufferSwitcher {
+    type Event = Event;
+}
+
+impl View for BufferSwitcher {
+    fn ui_name() -> &'static str {
+        "BufferSwitcher"
+    }
+
+    fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        let theme = smol::block_on(self.settings.read()).theme.clone();
+
+        Align::new(
+            ConstrainedBox::new(
+                Container::new(
+                    Flex::new(Axis::Vertical)
+                        .with_child(ChildView::new(self.query_buffer.id()).boxed())
+                        .with_child(Expanded::new(1.0, self.render_matches()).boxed())
+                        .boxed()
+                )
+
+                .with_margin_top(12.0)
+                .with_uniform_padding(6.0)
+                .with_corner_radius(6.0)
+                .with_background_color(theme.ui.modal_background)
+
+                .with_shadow(vec2f(0.0, 4.0), 12.0, theme.ui.modal_shadow)
+
+                .boxed()
+            )
+
+            .with_max_width(600.0)
+            .with_max_height(400.0)
+
+            .boxed()
+        )
+
+        .top_center()
+
+        .boxed()
+    }
+
+    fn on_focus(&mut self, ctx: &mut ViewContext<Self>) {
+        ctx.focus(&self.query_buffer);
+    }
+
+    fn keymap_context(&self, _: &AppContext) -> keymap::Context {
+        let mut ctx = Self::default_keymap_context();
+
+        ctx.set.insert("menu".into());
+
+        ctx
+    }
+}
+
+impl BufferSwitcher {
+    fn render_matches(&self) -> Box<dyn Element> {
+        if self.matches.is_empty() {
+            let settings = smol::block_on(self.settings.read());
+
+            return Container::new(
+                Label::new(
+                    "sem combinações".into(),
+
+                    settings.ui_font_family,
+                    settings.ui_font_size
+                ).with_default_color(settings.theme.ui.text).boxed()
+            ).with_margin_top(6.0).boxed();
+        }
+
+        let handle = self.handle.clone();
+
+        let list = UniformList::new(
+            self.list_state.clone(),
+            self.matches.len(),
+
+            move |mut range, items, app| {
+                let switcher = handle.upgrade(app).unwrap();
+                let switcher = switcher.as_ref(app);
+
+                let start = range.start;
+
+                range.end = cmp::min(range.end, switcher.matches.len());
+
+                items.extend(
+                    switcher.matches[range]
+                        .iter()
+                        .enumerate()
+                        .map(move |(i, item_match)| switcher.render_match(item_match, start + i, app))
+                );
+            }
+        );
+
+        let settings = smol::block_on(self.settings.read());
+
+        Container::new(list.boxed())
+            .with_background_color(settings.theme.ui.list_background)
+            .with_border(Border::all(1.0, settings.theme.ui.border))
+            .with_margin_top(6.0)
+            .boxed()
+    }
+
+    fn render_match(
+        &self,
+
+        item_match: &StringMatch,
+        index: usize,
+        app: &AppContext
+    ) -> Box<dyn Element> {
+        let (_, item) = &self.items[item_match.candidate_id];
+
+        let settings = smol::block_on(self.settings.read());
+        let theme = &settings.theme;
+        let bold = *Properties::new().weight(Weight::BOLD);
+
+        let title_color = item
+            .git_status(app)
+            .map_or(theme.ui.text, |status| theme.ui.git_status(status));
+
+        let mut container = Container::new(
+            Label::new(
+                item_match.string.clone(),
+                settings.ui_font_family,
+                settings.ui_font_size
+            )
+
+            .with_default_color(title_color)
+            .with_highlights(theme.ui.match_highlight, bold, item_match.positions.clone())
+            .boxed()
+        ).with_uniform_padding(6.0);
+
+        if index == self.selected || index < self.matches.len() - 1 {
+            container = container.with_border(Border::bottom(1.0, theme.ui.border));
+        }
+
+        if index == self.selected {
+            container = container.with_background_color(theme.ui.selected_item_background);
+        }
+
+        EventHandler::new(container.boxed())
+            .on_mouse_down(move |ctx, _| {
+                ctx.dispatch_action("buffer_switcher:select", index);
+
+                true
+            }).boxed()
+    }
+
+    fn toggle(workspace_view: &mut WorkspaceView, _: &(), ctx: &mut ViewContext<WorkspaceView>) {
+        Self::open(workspace_view, false, 1, ctx);
+    }
+
+    fn open_cycling_next(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        Self::open(workspace_view, true, 1, ctx);
+    }
+
+    fn open_cycling_prev(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        let item_count = workspace_view.items_by_recency(ctx.app()).len();
+
+        Self::open(workspace_view, true, item_count.saturating_sub(1), ctx);
+    }
+
+    fn open(
+        workspace_view: &mut WorkspaceView,
+
+        commit_on_release: bool,
+        selected: usize,
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        workspace_view.toggle_modal(ctx, |ctx, workspace_view| {
+            let items = workspace_view.items_by_recency(ctx.app());
+
+            let handle = ctx.add_view(|ctx| {
+                let mut switcher = Self::new(workspace_view.settings.clone(), items, ctx);
+
+                switcher.commit_on_release = commit_on_release;
+                switcher.selected = cmp::min(selected, switcher.matches.len().saturating_sub(1));
+                switcher.list_state.scroll_to(switcher.selected);
+
+                switcher
+            });
+
+            ctx.subscribe_to_view(&handle, Self::on_event);
+
+            handle
+        });
+    }
+
+    fn on_event(
+        workspace_view: &mut WorkspaceView,
+        _: ViewHandle<BufferSwitcher>,
+        event: &Event,
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        match event {
+            Event::Selected(pane, item_id) => {
+                workspace_view.activate_item(pane, *item_id, ctx);
+
+                workspace_view.dismiss_modal(ctx);
+            }
+
+            Event::Dismissed => {
+                workspace_view.dismiss_modal(ctx);
+            }
+        }
+    }
+
+    pub fn new(
+        settings: watch::Receiver<Settings>,
+        items: Vec<(ViewHandle<Pane>, Box<dyn ItemViewHandle>)>,
+        ctx: &mut ViewContext<Self>
+    ) -> Self {
+        let query_buffer = ctx.add_view(|ctx| BufferView::single_line(settings.clone(), ctx));
+        ctx.subscribe_to_view(&query_buffer, Self::on_query_buffer_event);
+
+        settings.notify_view_on_change(ctx);
+
+        let candidates = items
+            .iter()
+            .enumerate()
+            .map(|(ix, (_, item))| StringMatchCandidate::new(ix, item.title(ctx.app())))
+            .collect::<Vec<_>>();
+
+        let matches = match_strings(&candidates, "", false, candidates.len());
+
+        Self {
+            handle: ctx.handle(),
+            settings,
+            query_buffer,
+            items,
+            candidates,
+            matches,
+            selected: 0,
+            list_state: UniformListState::new(),
+            commit_on_release: false
+        }
+    }
+
+    fn on_query_buffer_event(
+        &mut self,
+
+        _: ViewHandle<BufferView>,
+        event: &buffer_view::Event,
+        ctx: &mut ViewContext<Self>
+    ) {
+        use buffer_view::Event::*;
+
+        match event {
+            Edited => self.update_matches(ctx),
+
+            Blurred => ctx.emit(Event::Dismissed),
+
+            Activate | Scrolled | Navigated(_) => {}
+        }
+    }
+
+    fn update_matches(&mut self, ctx: &mut ViewContext<Self>) {
+        let query = self.query_buffer.as_ref(ctx).text(ctx.app());
+
+        // sem consulta, a ordem é a de uso mais recente
+        self.matches = match_strings(&self.candidates, &query, false, self.candidates.len());
+        self.selected = 0;
+        self.list_state.scroll_to(0);
+
+        ctx.notify();
+    }
+
+    fn select_prev(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        self.list_state.scroll_to(self.selected);
+
+        ctx.notify();
+    }
+
+    fn select_next(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+
+        self.list_state.scroll_to(self.selected);
+
+        ctx.notify();
+    }
+
+    /// avança a seleção enquanto o ctrl continua pressionado, voltando ao início no fim da lista
+    fn cycle_next(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+            self.list_state.scroll_to(self.selected);
+
+            ctx.notify();
+        }
+    }
+
+    fn cycle_prev(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+            self.list_state.scroll_to(self.selected);
+
+            ctx.notify();
+        }
+    }
+
+    fn modifiers_changed(&mut self, modifiers: &Modifiers, ctx: &mut ViewContext<Self>) {
+        if self.commit_on_release && !modifiers.ctrl {
+            self.confirm(&(), ctx);
+        }
+    }
+
+    fn scroll(&mut self, _: &f32, ctx: &mut ViewContext<Self>) {
+        ctx.notify();
+    }
+
+    fn confirm(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let selected = self.selected;
+
+        self.select(&selected, ctx);
+    }
+
+    fn select(&mut self, index: &usize, ctx: &mut ViewContext<Self>) {
+        if let Some(m) = self.matches.get(*index) {
+            let (pane, item) = &self.items[m.candidate_id];
+
+            ctx.emit(Event::Selected(pane.clone(), item.id()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{editor, settings, test::temp_tree, workspace::Workspace};
+    use gpui::App;
+    use serde_json::json;
+
+    #[test]
+    fn test_cycling_in_recency_order() {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "a.txt": "a",
+                "b.txt": "b",
+                "c.txt": "c"
+            }));
+
+            super::init(&mut app);
+            editor::init(&mut app);
+
+            let settings = settings::channel(&app.fonts()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            let (window_id, workspace_view) = app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            app.finish_pending_tasks().await;
+
+            for name in &["a.txt", "b.txt", "c.txt"] {
+                let entry = workspace.read(&app, |workspace, ctx| {
+                    workspace.entry_for_path(&dir.path().join(name), ctx).unwrap()
+                });
+
+                workspace_view.update(&mut app, |view, ctx| view.open_entry(entry, ctx));
+
+                app.finish_pending_tasks().await;
+            }
+
+            let titles = |app: &App| {
+                workspace_view.read(app, |view, ctx| {
+                    view.items_by_recency(ctx)
+                        .iter()
+                        .map(|(_, item)| item.title(ctx))
+                        .collect::<Vec<_>>()
+                })
+            };
+
+            assert_eq!(titles(&app), vec!["c.txt", "b.txt", "a.txt"]);
+
+            // ctrl-tab abre o switcher no item anterior e avança enquanto o ctrl está pressionado
+            app.dispatch_action(window_id, vec![workspace_view.id()], "buffer_switcher:cycle", ());
+
+            let switcher = workspace_view.read(&app, |view, _| {
+                view.modal().cloned().unwrap().downcast::<BufferSwitcher>().unwrap()
+            });
+
+            let query_buffer = switcher.read(&app, |switcher, _| switcher.query_buffer.clone());
+            let chain = vec![workspace_view.id(), switcher.id(), query_buffer.id()];
+
+            switcher.read(&app, |switcher, _| assert_eq!(switcher.selected, 1));
+
+            app.dispatch_action(window_id, chain.clone(), "buffer_switcher:cycle", ());
+
+            switcher.read(&app, |switcher, _| assert_eq!(switcher.selected, 2));
+
+            // mudar outro modificador com o ctrl ainda pressionado não confirma
+            let ctrl_held = Modifiers {
+                ctrl: true,
+
+                ..Default::default()
+            };
+
+            app.dispatch_action(window_id, chain.clone(), "modifiers_changed", ctrl_held);
+
+            assert!(workspace_view.read(&app, |view, _| view.modal().is_some()));
+
+            app.dispatch_action(window_id, chain, "modifiers_changed", Modifiers::default());
+
+            assert!(workspace_view.read(&app, |view, _| view.modal().is_none()));
+            assert_eq!(titles(&app), vec!["a.txt", "c.txt", "b.txt"]);
+
+            // digitar filtra a lista
+            app.dispatch_action(window_id, vec![workspace_view.id()], "buffer_switcher:toggle", ());
+
+            let switcher = workspace_view.read(&app, |view, _| {
+                view.modal().cloned().unwrap().downcast::<BufferSwitcher>().unwrap()
+            });
+
+            let query_buffer = switcher.read(&app, |switcher, _| switcher.query_buffer.clone());
+            let chain = vec![workspace_view.id(), switcher.id(), query_buffer.id()];
+
+            app.dispatch_action(window_id, chain, "buffer:insert", "b".to_string());
+
+            switcher.read(&app, |switcher, _| {
+                assert_eq!(switcher.matches.len(), 1);
+                assert_eq!(switcher.matches[0].string, "b.txt");
+            });
+        });
+    }
+}
@@ -0,0 +1,502 @@
+use crate::{
+    editor::{buffer_view, BufferView},
+    settings::Settings,
+    watch,
+    workspace::WorkspaceView,
+    worktree::{match_strings, StringMatch, StringMatchCandidate}
+};
+
+use gpui::{
+    elements::*,
+    fonts::{Properties, Weight},
+    geometry::vector::vec2f,
+    keymap::{self, Binding, Keystroke},
+    App, AppContext, Axis, Border, Entity, View, ViewContext, ViewHandle, WeakViewHandle
+};
+
+use std::cmp;
+
+pub struct CommandPalette {
+    handle: WeakViewHandle<Self>,
+    settings: watch::Receiver<Settings>,
+    query_buffer: ViewHandle<BufferView>,
+    dispatch_path: Vec<usize>,
+    actions: Vec<PaletteAction>,
+    matches: Vec<StringMatch>,
+    selected: usize,
+    list_state: UniformListState
+}
+
+struct PaletteAction {
+    name: String,
+    humanized_name: String,
+    keystrokes: Option<Vec<Keystroke>>
+}
+
+pub fn init(app: &mut App) {
+    app.add_action("command_palette:toggle", CommandPalette::toggle);
+    app.add_action("command_palette:confirm", CommandPalette::confirm);
+    app.add_action("command_palette:select", CommandPalette::select);
+
+    app.add_action("buffer:move_up", CommandPalette::select_prev);
+    app.add_action("buffer:move_down", CommandPalette::select_next);
+    app.add_action("uniform_list:scroll", CommandPalette::scroll);
+
+    app.add_bindings(vec![
+        Binding::new("shift-cmd-p", "command_palette:toggle", None),
+
+        Binding::new("escape", "command_palette:toggle", Some("CommandPalette")),
+        Binding::new("enter", "command_palette:confirm", Some("CommandPalette"))
+    ]);
+}
+
+pub enum Event {
+    Selected(String),
+
+    Dismissed
+}
+
+impl Entity for CommandPalette {
+    type Event = Event;
+}
+
+impl View for CommandPalette {
+    fn ui_name() -> &'static str {
+        "CommandPalette"
+    }
+
+    fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        let theme = smol::block_on(self.settings.read()).theme.clone();
+
+        Align::new(
+            ConstrainedBox::new(
+                Container::new(
+                    Flex::new(Axis::Vertical)
+                        .with_child(ChildView::new(self.query_buffer.id()).boxed())
+                        .with_child(Expanded::new(1.0, self.render_matches()).boxed())
+                        .boxed()
+                )
+
+                .with_margin_top(12.0)
+                .with_uniform_padding(6.0)
+                .with_corner_radius(6.0)
+                .with_background_color(theme.ui.modal_background)
+
+                .with_shadow(vec2f(0.0, 4.0), 12.0, theme.ui.modal_shadow)
+
+                .boxed()
+            )
+
+            .with_max_width(600.0)
+            .with_max_height(400.0)
+
+            .boxed()
+        )
+
+        .top_center()
+
+        .boxed()
+    }
+
+    fn on_focus(&mut self, ctx: &mut ViewContext<Self>) {
+        // as ações são carregadas aqui e não em `new`, pois a view que abriu a paleta
+        // está fora da janela enquanto trata a ação de abertura
+        if self.actions.is_empty() {
+            self.load_actions(ctx);
+        }
+
+        ctx.focus(&self.query_buffer);
+    }
+
+    fn keymap_context(&self, _: &AppContext) -> keymap::Context {
+        let mut ctx = Self::default_keymap_context();
+
+        ctx.set.insert("menu".into());
+
+        ctx
+    }
+}
+
+impl CommandPalette {
+    fn render_matches(&self) -> Box<dyn Element> {
+        if self.matches.is_empty() {
+            let settings = smol::block_on(self.settings.read());
+
+            return Container::new(
+                Label::new(
+                    "sem combinações".into(),
+
+                    settings.ui_font_family,
+                    settings.ui_font_size
+                ).with_default_color(settings.theme.ui.text).boxed()
+            ).with_margin_top(6.0).boxed();
+        }
+
+        let handle = self.handle.clone();
+
+        let list = UniformList::new(
+            self.list_state.clone(),
+            self.matches.len(),
+
+            move |mut range, items, app| {
+                let palette = handle.upgrade(app).unwrap();
+                let palette = palette.as_ref(app);
+
+                let start = range.start;
+
+                range.end = cmp::min(range.end, palette.matches.len());
+
+                items.extend(
+                    palette.matches[range]
+                        .iter()
+                        .enumerate()
+                        .map(move |(i, action_match)| palette.render_match(action_match, start + i))
+                );
+            }
+        );
+
+        let settings = smol::block_on(self.settings.read());
+
+        Container::new(list.boxed())
+            .with_background_color(settings.theme.ui.list_background)
+            .with_border(Border::all(1.0, settings.theme.ui.border))
+            .with_margin_top(6.0)
+            .boxed()
+    }
+
+    fn render_match(&self, action_match: &StringMatch, index: usize) -> Box<dyn Element> {
+        let action = &self.actions[action_match.candidate_id];
+
+        let settings = smol::block_on(self.settings.read());
+        let theme = &settings.theme;
+        let bold = *Properties::new().weight(Weight::BOLD);
+
+        let mut row = Flex::row().with_child(
+            Expanded::new(
+                1.0,
+
+                Container::new(
+                    Label::new(
+                        action.humanized_name.clone(),
+                        settings.ui_font_family,
+                        settings.ui_font_size
+                    )
+
+                    .with_default_color(theme.ui.text)
+                    .with_highlights(theme.ui.match_highlight, bold, action_match.positions.clone())
+                    .boxed()
+                ).with_padding_right(6.0).boxed()
+            ).boxed()
+        );
+
+        if let Some(keystrokes) = action.keystrokes.as_ref() {
+            row = row.with_child(
+                Label::new(
+                    format_keystrokes(keystrokes),
+                    settings.ui_font_family,
+                    settings.ui_font_size
+                ).with_default_color(theme.ui.text).boxed()
+            );
+        }
+
+        let mut container = Container::new(row.boxed()).with_uniform_padding(6.0);
+
+        if index == self.selected || index < self.matches.len() - 1 {
+            container = container.with_border(Border::bottom(1.0, theme.ui.border));
+        }
+
+        if index == self.selected {
+            container = container.with_background_color(theme.ui.selected_item_background);
+        }
+
+        EventHandler::new(container.boxed())
+            .on_mouse_down(move |ctx, _| {
+                ctx.dispatch_action("command_palette:select", index);
+
+                true
+            }).boxed()
+    }
+
+    fn toggle(workspace_view: &mut WorkspaceView, _: &(), ctx: &mut ViewContext<WorkspaceView>) {
+        let dispatch_path = ctx.dispatch_path().to_vec();
+
+        workspace_view.toggle_modal(ctx, |ctx, workspace_view| {
+            let handle = ctx.add_view(|ctx| Self::new(workspace_view.settings.clone(), dispatch_path, ctx));
+
+            ctx.subscribe_to_view(&handle, Self::on_event);
+
+            handle
+        });
+    }
+
+    fn on_event(
+        workspace_view: &mut WorkspaceView,
+        palette: ViewHandle<CommandPalette>,
+        event: &Event,
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        match event {
+            Event::Selected(action) => {
+                let dispatch_path = palette.as_ref(ctx).dispatch_path.clone();
+
+                workspace_view.dismiss_modal(ctx);
+
+                ctx.dispatch_action(dispatch_path, action, ());
+            }
+
+            Event::Dismissed => {
+                workspace_view.dismiss_modal(ctx);
+            }
+        }
+    }
+
+    pub fn new(
+        settings: watch::Receiver<Settings>,
+        dispatch_path: Vec<usize>,
+        ctx: &mut ViewContext<Self>
+    ) -> Self {
+        let query_buffer = ctx.add_view(|ctx| BufferView::single_line(settings.clone(), ctx));
+        ctx.subscribe_to_view(&query_buffer, Self::on_query_buffer_event);
+
+        settings.notify_view_on_change(ctx);
+
+        Self {
+            handle: ctx.handle(),
+            settings,
+            query_buffer,
+            dispatch_path,
+            actions: Vec::new(),
+            matches: Vec::new(),
+            selected: 0,
+            list_state: UniformListState::new()
+        }
+    }
+
+    fn load_actions(&mut self, ctx: &mut ViewContext<Self>) {
+        let window_id = ctx.window_id();
+
+        self.actions = ctx
+            .app_mut()
+            .available_actions(window_id, &self.dispatch_path)
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("command_palette:"))
+            .map(|(name, keystrokes)| PaletteAction {
+                humanized_name: humanize_action_name(&name),
+
+                name,
+                keystrokes
+            }).collect();
+
+        self.actions.sort_by(|a, b| a.humanized_name.cmp(&b.humanized_name));
+
+        self.update_matches(ctx);
+    }
+
+    fn on_query_buffer_event(
+        &mut self,
+
+        _: ViewHandle<BufferView>,
+        event: &buffer_view::Event,
+        ctx: &mut ViewContext<Self>
+    ) {
+        use buffer_view::Event::*;
+
+        match event {
+            Edited => self.update_matches(ctx),
+
+            Blurred => ctx.emit(Event::Dismissed),
+
+            Activate | Scrolled | Navigated(_) => {}
+        }
+    }
+
+    fn update_matches(&mut self, ctx: &mut ViewContext<Self>) {
+        let query = self.query_buffer.as_ref(ctx).text(ctx.app());
+
+        self.matches = match_actions(&self.actions, &query);
+        self.selected = 0;
+        self.list_state.scroll_to(0);
+
+        ctx.notify();
+    }
+
+    fn select_prev(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.selected > 0 {
+            self.selected -= 1;
+        }
+
+        self.list_state.scroll_to(self.selected);
+
+        ctx.notify();
+    }
+
+    fn select_next(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+
+        self.list_state.scroll_to(self.selected);
+
+        ctx.notify();
+    }
+
+    fn scroll(&mut self, _: &f32, ctx: &mut ViewContext<Self>) {
+        ctx.notify();
+    }
+
+    fn confirm(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(m) = self.matches.get(self.selected) {
+            ctx.emit(Event::Selected(self.actions[m.candidate_id].name.clone()));
+        }
+    }
+
+    fn select(&mut self, index: &usize, ctx: &mut ViewContext<Self>) {
+        if let Some(m) = self.matches.get(*index) {
+            ctx.emit(Event::Selected(self.actions[m.candidate_id].name.clone()));
+        }
+    }
+}
+
+/// converte `buffer:fold_selected_ranges` em `Buffer: Fold Selected Ranges`
+fn humanize_action_name(name: &str) -> String {
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    let humanize_words = |words: &str| {
+        words
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(capitalize)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    match name.find(':') {
+        Some(ix) => format!("{}: {}", humanize_words(&name[..ix]), humanize_words(&name[ix + 1..])),
+
+        None => humanize_words(name)
+    }
+}
+
+fn format_keystrokes(keystrokes: &[Keystroke]) -> String {
+    keystrokes
+        .iter()
+        .map(|keystroke| keystroke.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn match_actions(actions: &[PaletteAction], query: &str) -> Vec<StringMatch> {
+    let query = query.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+
+    let candidates = actions
+        .iter()
+        .enumerate()
+        .map(|(action_ix, action)| StringMatchCandidate::new(action_ix, action.humanized_name.clone()))
+        .collect::<Vec<_>>();
+
+    match_strings(&candidates, &query, false, actions.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{editor, file_finder::{self, FileFinder}, settings, workspace::Workspace};
+    use gpui::App;
+
+    #[test]
+    fn test_humanize_action_name() {
+        assert_eq!(humanize_action_name("buffer:fold_selected_ranges"), "Buffer: Fold Selected Ranges");
+        assert_eq!(humanize_action_name("file_finder:toggle"), "File Finder: Toggle");
+        assert_eq!(humanize_action_name("save"), "Save");
+    }
+
+    #[test]
+    fn test_match_actions() {
+        let actions = ["buffer:fold", "buffer:fold_selected_ranges", "file_finder:toggle"]
+            .iter()
+            .map(|name| PaletteAction {
+                name: name.to_string(),
+                humanized_name: humanize_action_name(name),
+                keystrokes: None
+            }).collect::<Vec<_>>();
+
+        let matches = match_actions(&actions, "fsr");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].candidate_id, 1);
+        assert_eq!(matches[0].positions, vec![8, 13, 22]);
+
+        // inícios de palavra ganham de caracteres soltos
+        let matches = match_actions(&actions, "ft");
+
+        assert_eq!(matches[0].candidate_id, 2);
+
+        assert_eq!(match_actions(&actions, "").len(), 3);
+        assert!(match_actions(&actions, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_dispatching_selected_action() {
+        App::test((), |mut app| async move {
+            super::init(&mut app);
+            editor::init(&mut app);
+            file_finder::init(&mut app);
+
+            let settings = settings::channel(&app.fonts()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![], ctx));
+
+            let (window_id, workspace_view) = app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            app.dispatch_action(
+                window_id,
+
+                vec![workspace_view.id()],
+                "command_palette:toggle".into(),
+
+                ()
+            );
+
+            let (palette, query_buffer) = workspace_view.read(&app, |view, ctx| {
+                let palette = view
+                    .modal()
+                    .cloned()
+                    .unwrap()
+                    .downcast::<CommandPalette>()
+                    .unwrap();
+
+                let query_buffer = palette.as_ref(ctx).query_buffer.clone();
+
+                (palette, query_buffer)
+            });
+
+            palette.read(&app, |palette, _| {
+                assert!(palette.actions.iter().any(|action| action.name == "file_finder:toggle"));
+                assert!(!palette.actions.iter().any(|action| action.name == "command_palette:toggle"));
+            });
+
+            let chain = vec![workspace_view.id(), palette.id(), query_buffer.id()];
+
+            app.dispatch_action(window_id, chain.clone(), "buffer:insert", "fft".to_string());
+
+            palette.read(&app, |palette, _| {
+                let first = &palette.actions[palette.matches[0].candidate_id];
+
+                assert_eq!(first.humanized_name, "File Finder: Toggle");
+            });
+
+            app.dispatch_action(window_id, chain, "command_palette:confirm", ());
+
+            workspace_view.read(&app, |view, _| {
+                assert!(view.modal().unwrap().is::<FileFinder>());
+            });
+        });
+    }
+}
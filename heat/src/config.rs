@@ -0,0 +1,99 @@
+use crate::timer;
+use smol::prelude::*;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime}
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// diretório dos arquivos de configuração do usuário, como o keymap e as configurações
+pub fn dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("heat"))
+}
+
+/// conteúdo atual do arquivo, ou `None` se ele não existir
+pub fn read(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(source) => Ok(Some(source)),
+
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error)
+    }
+}
+
+/// emite o novo conteúdo do arquivo sempre que a sua data de modificação muda,
+/// inclusive quando ele é criado ou removido
+pub fn changes(path: PathBuf) -> impl Stream<Item = io::Result<Option<String>>> + Unpin {
+    changes_with_dependencies(path, |_| Vec::new())
+}
+
+/// como `changes`, mas também emite quando muda algum dos arquivos que `dependencies` extrai
+/// do conteúdo atual do arquivo, como o tema escolhido nas configurações
+pub fn changes_with_dependencies<F>(
+    path: PathBuf,
+    mut dependencies: F
+) -> impl Stream<Item = io::Result<Option<String>>> + Unpin
+where
+    F: FnMut(&str) -> Vec<PathBuf>
+{
+    let mut dependency_paths = match read(&path) {
+        Ok(Some(source)) => dependencies(&source),
+
+        _ => Vec::new()
+    };
+
+    let mut last_modified = modified_times(&path, &dependency_paths);
+
+    timer::repeat(POLL_INTERVAL).filter_map(move |_| {
+        let modified = modified_times(&path, &dependency_paths);
+
+        if modified == last_modified {
+            None
+        } else {
+            let source = read(&path);
+
+            dependency_paths = match &source {
+                Ok(Some(source)) => dependencies(source),
+
+                _ => Vec::new()
+            };
+
+            last_modified = modified_times(&path, &dependency_paths);
+
+            Some(source)
+        }
+    })
+}
+
+fn modified_times(path: &Path, dependency_paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    std::iter::once(path)
+        .chain(dependency_paths.iter().map(PathBuf::as_path))
+        .map(modified_time)
+        .collect()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_read() -> io::Result<()> {
+        let dir = temp_tree(json!({
+            "keymap.json": "[]"
+        }));
+
+        assert_eq!(read(&dir.path().join("keymap.json"))?, Some("[]".to_string()));
+        assert_eq!(read(&dir.path().join("settings.json"))?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{
+    editor::{buffer, buffer_view, diff_lines, Buffer, BufferView, DiffHunk, InlineBlock, InlineBlockKind},
+    settings::Settings,
+    watch,
+    workspace::{Item, ItemHandle as _, ItemView, WorkspaceView}
+};
+
+use gpui::{
+    elements::*, keymap::Binding, App, AppContext, Border, Entity, ModelContext, ModelHandle, View,
+    ViewContext, ViewHandle
+};
+
+use smol::Timer;
+use std::time::Duration;
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+// um diff compara dois buffers editáveis, o antigo e o novo, e recalcula os trechos alterados
+// sempre que um deles muda
+//
+// lado a lado, cada trecho recebe linhas de preenchimento no lado mais curto. como a rolagem do
+// buffer conta as linhas dos blocos, os dois lados têm a mesma altura e a rolagem de um é
+// repassada sem conversão ao outro. no modo unificado apenas o buffer novo é exibido, com as
+// linhas removidas acima de cada trecho
+
+pub fn init(app: &mut App) {
+    app.add_action("diff_view:diff_with_saved", DiffView::diff_with_saved);
+    app.add_action("diff_view:diff_with_head", DiffView::diff_with_head);
+    app.add_action("diff_view:diff_with_prev_item", DiffView::diff_with_prev_item);
+    app.add_action("diff_view:toggle_mode", DiffView::toggle_mode);
+
+    app.add_bindings(vec![Binding::new("alt-cmd-i", "diff_view:toggle_mode", Some("DiffView"))]);
+}
+
+pub struct Diff {
+    old: ModelHandle<Buffer>,
+    new: ModelHandle<Buffer>,
+
+    old_title: String,
+    new_title: String,
+
+    // `head_rows` são as linhas no buffer antigo e `rows` as linhas no novo
+    hunks: Vec<DiffHunk>,
+    update_epoch: usize
+}
+
+impl Diff {
+    pub fn new(
+        old: ModelHandle<Buffer>,
+        old_title: String,
+
+        new: ModelHandle<Buffer>,
+        new_title: String,
+
+        ctx: &mut ModelContext<Self>
+    ) -> Self {
+        ctx.subscribe(&old, Self::on_buffer_event);
+        ctx.subscribe(&new, Self::on_buffer_event);
+
+        let task = ctx
+            .background_executor()
+            .spawn(compute_hunks(old.as_ref(ctx).text(), new.as_ref(ctx).text()));
+
+        ctx.spawn(task, |me, hunks, ctx| me.hunks_computed(0, hunks, ctx)).detach();
+
+        Self {
+            old,
+            new,
+
+            old_title,
+            new_title,
+
+            hunks: Vec::new(),
+            update_epoch: 0
+        }
+    }
+
+    pub fn old_buffer(&self) -> &ModelHandle<Buffer> {
+        &self.old
+    }
+
+    pub fn new_buffer(&self) -> &ModelHandle<Buffer> {
+        &self.new
+    }
+
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    fn on_buffer_event(&mut self, event: &buffer::Event, ctx: &mut ModelContext<Self>) {
+        if let buffer::Event::Edited(_) = event {
+            self.update_epoch += 1;
+
+            let epoch = self.update_epoch;
+
+            ctx.spawn(Timer::after(UPDATE_DEBOUNCE), move |me, _, ctx| me.update(epoch, ctx))
+                .detach();
+        }
+    }
+
+    fn update(&mut self, epoch: usize, ctx: &mut ModelContext<Self>) {
+        if epoch != self.update_epoch {
+            return;
+        }
+
+        let task = ctx
+            .background_executor()
+            .spawn(compute_hunks(self.old.as_ref(ctx).text(), self.new.as_ref(ctx).text()));
+
+        ctx.spawn(task, move |me, hunks, ctx| me.hunks_computed(epoch, hunks, ctx))
+            .detach();
+    }
+
+    fn hunks_computed(&mut self, epoch: usize, hunks: Vec<DiffHunk>, ctx: &mut ModelContext<Self>) {
+        // os buffers mudaram enquanto o diff era calculado, e um novo cálculo já foi agendado
+        if epoch == self.update_epoch && hunks != self.hunks {
+            self.hunks = hunks;
+
+            ctx.notify();
+        }
+    }
+}
+
+async fn compute_hunks(old_text: String, new_text: String) -> Vec<DiffHunk> {
+    diff_lines(&old_text, &new_text)
+}
+
+impl Entity for Diff {
+    type Event = ();
+}
+
+impl Item for Diff {
+    type View = DiffView;
+
+    fn build_view(
+        handle: ModelHandle<Self>,
+        settings: watch::Receiver<Settings>,
+        ctx: &mut ViewContext<Self::View>
+    ) -> Self::View {
+        DiffView::new(handle, settings, ctx)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffMode {
+    SideBySide,
+    Inline
+}
+
+pub struct DiffView {
+    diff: ModelHandle<Diff>,
+    settings: watch::Receiver<Settings>,
+
+    old_view: ViewHandle<BufferView>,
+    new_view: ViewHandle<BufferView>,
+
+    mode: DiffMode
+}
+
+pub enum Event {
+    Activate
+}
+
+impl DiffView {
+    pub fn new(
+        diff: ModelHandle<Diff>,
+        settings: watch::Receiver<Settings>,
+        ctx: &mut ViewContext<Self>
+    ) -> Self {
+        settings.notify_view_on_change(ctx);
+
+        ctx.observe(&diff, Self::on_diff_changed);
+
+        let old = diff.as_ref(ctx).old.clone();
+        let new = diff.as_ref(ctx).new.clone();
+
+        let old_view = ctx.add_view(|ctx| BufferView::for_buffer(old, settings.clone(), ctx));
+        let new_view = ctx.add_view(|ctx| BufferView::for_buffer(new, settings.clone(), ctx));
+
+        ctx.subscribe_to_view(&old_view, Self::on_old_view_event);
+        ctx.subscribe_to_view(&new_view, Self::on_new_view_event);
+
+        Self {
+            diff,
+            settings,
+
+            old_view,
+            new_view,
+
+            mode: DiffMode::SideBySide
+        }
+    }
+
+    pub fn mode(&self) -> DiffMode {
+        self.mode
+    }
+
+    fn toggle_mode(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.mode = match self.mode {
+            DiffMode::SideBySide => DiffMode::Inline,
+            DiffMode::Inline => DiffMode::SideBySide
+        };
+
+        self.update_blocks(ctx);
+
+        ctx.focus(&self.new_view);
+        ctx.notify();
+    }
+
+    fn on_diff_changed(&mut self, _: ModelHandle<Diff>, ctx: &mut ViewContext<Self>) {
+        self.update_blocks(ctx);
+
+        ctx.notify();
+    }
+
+    fn update_blocks(&mut self, ctx: &mut ViewContext<Self>) {
+        let diff = self.diff.as_ref(ctx);
+
+        match self.mode {
+            DiffMode::SideBySide => {
+                let (old_fillers, new_fillers) = filler_blocks(&diff.hunks);
+
+                self.old_view.update(ctx, |view, ctx| view.set_inline_blocks(old_fillers, ctx));
+                self.new_view.update(ctx, |view, ctx| view.set_inline_blocks(new_fillers, ctx));
+            }
+
+            DiffMode::Inline => {
+                let blocks = removed_blocks(&diff.hunks, diff.old.as_ref(ctx));
+
+                self.old_view.update(ctx, |view, ctx| view.set_inline_blocks(Vec::new(), ctx));
+                self.new_view.update(ctx, |view, ctx| view.set_inline_blocks(blocks, ctx));
+            }
+        }
+    }
+
+    fn on_old_view_event(
+        &mut self,
+
+        view: ViewHandle<BufferView>,
+        event: &buffer_view::Event,
+        ctx: &mut ViewContext<Self>
+    ) {
+        match event {
+            buffer_view::Event::Scrolled => {
+                let position = view.as_ref(ctx).scroll_position();
+
+                self.new_view.update(ctx, |view, ctx| view.set_scroll_position(position, ctx));
+            }
+
+            buffer_view::Event::Activate => ctx.emit(Event::Activate),
+
+            _ => {}
+        }
+    }
+
+    fn on_new_view_event(
+        &mut self,
+
+        view: ViewHandle<BufferView>,
+        event: &buffer_view::Event,
+        ctx: &mut ViewContext<Self>
+    ) {
+        match event {
+            buffer_view::Event::Scrolled if self.mode == DiffMode::SideBySide => {
+                let position = view.as_ref(ctx).scroll_position();
+
+                self.old_view.update(ctx, |view, ctx| view.set_scroll_position(position, ctx));
+            }
+
+            buffer_view::Event::Activate => ctx.emit(Event::Activate),
+
+            _ => {}
+        }
+    }
+
+    /// compara o buffer ativo com o arquivo salvo no disco
+    fn diff_with_saved(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        let view = match active_buffer_view(workspace_view, ctx.app()) {
+            Some(view) => view,
+
+            None => return
+        };
+
+        let buffer = view.as_ref(ctx).buffer().clone();
+
+        let history = match buffer.as_ref(ctx).file() {
+            Some(file) => file.load_history(ctx.app()),
+
+            None => {
+                log::error!("o buffer ativo não possui um arquivo salvo");
+
+                return;
+            }
+        };
+
+        let title = view.as_ref(ctx).title(ctx.app());
+
+        let _ = ctx.spawn(history, move |workspace_view, history, ctx| match history {
+            Ok(history) => {
+                let old = ctx.add_model(|_| Buffer::new(0, history.base_text));
+
+                open_diff(workspace_view, old, format!("{} (salvo)", title), buffer, title, ctx);
+            }
+
+            Err(error) => log::error!("erro ao ler o arquivo salvo: {}", error)
+        });
+    }
+
+    /// compara o buffer ativo com o seu conteúdo no `HEAD` do repositório
+    fn diff_with_head(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        let view = match active_buffer_view(workspace_view, ctx.app()) {
+            Some(view) => view,
+
+            None => return
+        };
+
+        let buffer = view.as_ref(ctx).buffer().clone();
+        let title = view.as_ref(ctx).title(ctx.app());
+
+        let head_text = match buffer.as_ref(ctx).head_text() {
+            Some(head_text) => head_text.to_string(),
+
+            None => {
+                log::error!("o arquivo do buffer ativo não está versionado");
+
+                return;
+            }
+        };
+
+        let old = ctx.add_model(|_| Buffer::new(0, head_text));
+
+        open_diff(workspace_view, old, format!("{} (HEAD)", title), buffer, title, ctx);
+    }
+
+    /// compara o buffer à esquerda do ativo com o buffer ativo
+    fn diff_with_prev_item(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        let prev_view = workspace_view
+            .active_pane()
+            .as_ref(ctx)
+            .prev_item()
+            .and_then(|item| item.to_any().downcast::<BufferView>());
+
+        let (old_view, new_view) = match (prev_view, active_buffer_view(workspace_view, ctx.app())) {
+            (Some(old_view), Some(new_view)) => (old_view, new_view),
+
+            _ => {
+                log::error!("é preciso ter dois buffers lado a lado no painel ativo para compará-los");
+
+                return;
+            }
+        };
+
+        let old = old_view.as_ref(ctx).buffer().clone();
+        let old_title = old_view.as_ref(ctx).title(ctx.app());
+
+        let new = new_view.as_ref(ctx).buffer().clone();
+        let new_title = new_view.as_ref(ctx).title(ctx.app());
+
+        open_diff(workspace_view, old, old_title, new, new_title, ctx);
+    }
+}
+
+fn active_buffer_view(workspace_view: &WorkspaceView, app: &AppContext) -> Option<ViewHandle<BufferView>> {
+    workspace_view
+        .active_pane()
+        .as_ref(app)
+        .active_item()
+        .and_then(|item| item.to_any().downcast::<BufferView>())
+}
+
+fn open_diff(
+    workspace_view: &mut WorkspaceView,
+
+    old: ModelHandle<Buffer>,
+    old_title: String,
+
+    new: ModelHandle<Buffer>,
+    new_title: String,
+
+    ctx: &mut ViewContext<WorkspaceView>
+) {
+    let diff = ctx.add_model(|ctx| Diff::new(old, old_title, new, new_title, ctx));
+    let item = diff.add_view(ctx.window_id(), workspace_view.settings.clone(), ctx.app_mut());
+
+    workspace_view.add_item(item, ctx);
+}
+
+/// linhas de preenchimento do lado antigo e do novo, que deixam cada trecho com a mesma altura
+/// nos dois lados
+fn filler_blocks(hunks: &[DiffHunk]) -> (Vec<InlineBlock>, Vec<InlineBlock>) {
+    let mut old_fillers = Vec::new();
+    let mut new_fillers = Vec::new();
+
+    for hunk in hunks {
+        let old_len = hunk.head_rows.end - hunk.head_rows.start;
+        let new_len = hunk.rows.end - hunk.rows.start;
+
+        let (fillers, row, count) = if new_len > old_len {
+            (&mut old_fillers, hunk.head_rows.end, new_len - old_len)
+        } else if old_len > new_len {
+            (&mut new_fillers, hunk.rows.end, old_len - new_len)
+        } else {
+            continue;
+        };
+
+        fillers.push(InlineBlock {
+            row,
+            kind: InlineBlockKind::Filler,
+            lines: vec![String::new(); count as usize]
+        });
+    }
+
+    (old_fillers, new_fillers)
+}
+
+/// linhas removidas de cada trecho, exibidas acima das linhas que as substituíram
+fn removed_blocks(hunks: &[DiffHunk], old: &Buffer) -> Vec<InlineBlock> {
+    hunks
+        .iter()
+        .filter(|hunk| hunk.head_rows.start < hunk.head_rows.end)
+        .map(|hunk| InlineBlock {
+            row: hunk.rows.start,
+            kind: InlineBlockKind::Removed,
+
+            lines: hunk
+                .head_rows
+                .clone()
+                .map(|row| old.line(row).unwrap_or_default())
+                .collect()
+        }).collect()
+}
+
+impl Entity for DiffView {
+    type Event = Event;
+}
+
+impl View for DiffView {
+    fn ui_name() -> &'static str {
+        "DiffView"
+    }
+
+    fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        match self.mode {
+            DiffMode::SideBySide => {
+                let theme = smol::block_on(self.settings.read()).theme.clone();
+
+                Flex::row()
+                    .with_child(
+                        Expanded::new(
+                            1.0,
+
+                            Container::new(ChildView::new(self.old_view.id()).boxed())
+                                .with_border(Border::right(1.0, theme.ui.border))
+                                .boxed()
+                        ).boxed()
+                    )
+
+                    .with_child(Expanded::new(1.0, ChildView::new(self.new_view.id()).boxed()).boxed())
+                    .boxed()
+            }
+
+            DiffMode::Inline => ChildView::new(self.new_view.id()).boxed()
+        }
+    }
+
+    fn on_focus(&mut self, ctx: &mut ViewContext<Self>) {
+        ctx.focus(&self.new_view);
+    }
+}
+
+impl ItemView for DiffView {
+    fn is_activate_event(event: &Self::Event) -> bool {
+        match event {
+            Event::Activate => true
+        }
+    }
+
+    fn title(&self, app: &AppContext) -> String {
+        let diff = self.diff.as_ref(app);
+
+        format!("{} ↔ {}", diff.old_title, diff.new_title)
+    }
+
+    fn entry_id(&self, _: &AppContext) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::settings;
+    use gpui::FontCache;
+
+    fn hunk(rows: std::ops::Range<u32>, head_rows: std::ops::Range<u32>) -> DiffHunk {
+        DiffHunk { rows, head_rows }
+    }
+
+    #[test]
+    fn test_alignment() {
+        // o lado novo ganha duas linhas no começo e perde uma no fim
+        let hunks = vec![hunk(1..4, 1..2), hunk(6..6, 4..5)];
+        let (old_fillers, new_fillers) = filler_blocks(&hunks);
+
+        assert_eq!(
+            old_fillers.iter().map(|block| (block.row, block.lines.len())).collect::<Vec<_>>(),
+            vec![(2, 2)]
+        );
+
+        assert_eq!(
+            new_fillers.iter().map(|block| (block.row, block.lines.len())).collect::<Vec<_>>(),
+            vec![(6, 1)]
+        );
+
+        App::test((), |mut app| async move {
+            let old = app.add_model(|_| Buffer::new(0, "a\nb\nc\nd\ne\nf"));
+            let new = app.add_model(|_| Buffer::new(0, "a\nx\ny\nz\nc\nd\nf"));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+
+            let (_, old_view) = app.add_window(|ctx| BufferView::for_buffer(old, settings.clone(), ctx));
+            let (_, new_view) = app.add_window(|ctx| BufferView::for_buffer(new, settings, ctx));
+
+            old_view.update(&mut app, |view, ctx| view.set_inline_blocks(old_fillers, ctx));
+            new_view.update(&mut app, |view, ctx| view.set_inline_blocks(new_fillers, ctx));
+
+            old_view.read(&app, |old_view, app| {
+                let new_view = new_view.as_ref(app);
+
+                // as linhas correspondentes ocupam a mesma linha visual nos dois lados
+                for (old_row, new_row) in vec![(0, 0), (2, 4), (3, 5), (5, 6)] {
+                    assert_eq!(old_view.visual_row(old_row, app), new_view.visual_row(new_row, app));
+                }
+
+                assert_eq!(old_view.visual_row_count(app), 8);
+                assert_eq!(new_view.visual_row_count(app), 8);
+
+                // uma linha dentro do preenchimento leva à linha logo abaixo dele
+                assert_eq!(old_view.display_row_for_visual_row(3, app), 2);
+                assert_eq!(new_view.display_row_for_visual_row(6, app), 6);
+            });
+        });
+    }
+
+    #[test]
+    fn test_diff_updates() {
+        App::test((), |mut app| async move {
+            let old = app.add_model(|_| Buffer::new(0, "a\nb\nc"));
+            let new = app.add_model(|_| Buffer::new(0, "a\nc"));
+
+            let diff = app.add_model(|ctx| {
+                Diff::new(old.clone(), "old".into(), new.clone(), "new".into(), ctx)
+            });
+
+            app.finish_pending_tasks().await;
+
+            diff.read(&app, |diff, _| assert_eq!(diff.hunks(), &[hunk(1..1, 1..2)]));
+
+            new.update(&mut app, |buffer, ctx| buffer.edit(Some(3..3), "\nd", Some(ctx)).unwrap());
+
+            app.finish_pending_tasks().await;
+
+            diff.read(&app, |diff, _| {
+                assert_eq!(diff.hunks(), &[hunk(1..1, 1..2), hunk(2..3, 3..3)]);
+
+                let (old_fillers, new_fillers) = filler_blocks(diff.hunks());
+
+                assert_eq!(old_fillers.len(), 1);
+                assert_eq!(new_fillers.len(), 1);
+            });
+
+            diff.read(&app, |diff, app| {
+                let blocks = removed_blocks(diff.hunks(), diff.old_buffer().as_ref(app));
+
+                assert_eq!(blocks.len(), 1);
+                assert_eq!(blocks[0].row, 1);
+                assert_eq!(blocks[0].lines, vec!["b".to_string()]);
+            });
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use std::{cmp, ops::Range};
+
+// conflitos de merge deixados pelo git no texto:
+//
+// <<<<<<< HEAD
+// nossa versão
+// ||||||| base        (apenas com `merge.conflictStyle = diff3`)
+// versão comum
+// =======
+// versão deles
+// >>>>>>> outro-branch
+
+/// região em conflito, com o trecho de cada lado sem as linhas de marcação
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Conflict<T> {
+    pub range: Range<T>,
+    pub ours: Range<T>,
+    pub theirs: Range<T>
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Both
+}
+
+/// conflitos completos do texto, em ordem, como intervalos de offsets em caracteres
+pub fn find_conflicts(text: &str) -> Vec<Conflict<usize>> {
+    let mut conflicts = Vec::new();
+
+    let text_len = text.chars().count();
+
+    let mut start = None;
+    let mut ours_start = 0;
+    let mut ours_end = None;
+    let mut theirs_start = None;
+
+    let mut line_start = 0;
+
+    for line in text.split('\n') {
+        let line_end = line_start + line.chars().count();
+        let next_line_start = cmp::min(line_end + 1, text_len);
+
+        // arquivos com quebras de linha `\r\n` trazem o `\r` no fim de cada marcação
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        if is_marker(line, '<') {
+            // um novo início descarta um conflito incompleto
+            start = Some(line_start);
+            ours_start = next_line_start;
+            ours_end = None;
+            theirs_start = None;
+        } else if start.is_some() && theirs_start.is_none() && is_marker(line, '|') {
+            ours_end.get_or_insert(line_start);
+        } else if start.is_some() && theirs_start.is_none() && line == "=======" {
+            ours_end.get_or_insert(line_start);
+            theirs_start = Some(next_line_start);
+        } else if let (Some(range_start), Some(theirs_range_start)) = (start, theirs_start) {
+            if is_marker(line, '>') {
+                conflicts.push(Conflict {
+                    range: range_start..next_line_start,
+                    ours: ours_start..ours_end.unwrap(),
+                    theirs: theirs_range_start..line_start
+                });
+
+                start = None;
+                ours_end = None;
+                theirs_start = None;
+            }
+        }
+
+        line_start = line_end + 1;
+    }
+
+    conflicts
+}
+
+/// linhas como `<<<<<<< HEAD`: sete marcadores seguidos de um espaço ou do fim da linha
+fn is_marker(line: &str, marker: char) -> bool {
+    let mut chars = line.chars();
+
+    chars.by_ref().take(7).filter(|c| *c == marker).count() == 7
+        && chars.next().map_or(true, |c| c == ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_conflicts() {
+        let text = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nb\n";
+        let conflicts = find_conflicts(text);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(&text[conflicts[0].range.clone()], "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n");
+        assert_eq!(&text[conflicts[0].ours.clone()], "ours\n");
+        assert_eq!(&text[conflicts[0].theirs.clone()], "theirs\n");
+
+        // estilo diff3, um lado vazio e o último marcador sem quebra de linha no fim
+        let text = "<<<<<<< HEAD\n||||||| base\nbase\n=======\ntheirs\n>>>>>>> branch";
+        let conflicts = find_conflicts(text);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(&text[conflicts[0].range.clone()], text);
+        assert_eq!(&text[conflicts[0].ours.clone()], "");
+        assert_eq!(&text[conflicts[0].theirs.clone()], "theirs\n");
+
+        // conflitos incompletos ou marcadores no meio da linha são ignorados
+        assert!(find_conflicts("<<<<<<< HEAD\nours\n>>>>>>> branch\n").is_empty());
+        assert!(find_conflicts("x <<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n").is_empty());
+        assert!(find_conflicts("<<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n").is_empty());
+
+        // quebras de linha `\r\n`
+        let text = "a\r\n<<<<<<<\r\nours\r\n=======\r\ntheirs\r\n>>>>>>> branch\r\nb\r\n";
+        let conflicts = find_conflicts(text);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(&text[conflicts[0].range.clone()], "<<<<<<<\r\nours\r\n=======\r\ntheirs\r\n>>>>>>> branch\r\n");
+        assert_eq!(&text[conflicts[0].ours.clone()], "ours\r\n");
+        assert_eq!(&text[conflicts[0].theirs.clone()], "theirs\r\n");
+    }
+}
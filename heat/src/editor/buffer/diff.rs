@@ -0,0 +1,213 @@
+use std::{cmp, ops::Range};
+
+// diff por linhas entre o texto do `HEAD` e o texto atual do buffer
+//
+// as linhas em comum no começo e no fim são descartadas antes de rodar o algoritmo de Myers,
+// então uma edição localizada custa apenas o tamanho do trecho alterado, e não o do arquivo
+//
+// o algoritmo guarda, para cada passo, as diagonais que ele alcança, o que ocupa memória
+// quadrática no número de linhas diferentes; acima de `MAX_EDIT_DISTANCE`, o trecho do meio
+// inteiro é tratado como uma única substituição
+
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffHunkKind {
+    Added,
+    Modified,
+    Deleted
+}
+
+/// trecho alterado, com as linhas que ocupa no buffer e as linhas que ocupava no `HEAD`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiffHunk {
+    pub rows: Range<u32>,
+    pub head_rows: Range<u32>
+}
+
+impl DiffHunk {
+    pub fn kind(&self) -> DiffHunkKind {
+        if self.head_rows.start == self.head_rows.end {
+            DiffHunkKind::Added
+        } else if self.rows.start == self.rows.end {
+            DiffHunkKind::Deleted
+        } else {
+            DiffHunkKind::Modified
+        }
+    }
+}
+
+/// trechos diferentes entre `old` e `new`, em ordem, com as linhas separadas por `\n`
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffHunk> {
+    let old_lines = old.split('\n').collect::<Vec<_>>();
+    let new_lines = new.split('\n').collect::<Vec<_>>();
+
+    let prefix_len = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let suffix_len = old_lines[prefix_len..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix_len..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let old_middle = &old_lines[prefix_len..old_lines.len() - suffix_len];
+    let new_middle = &new_lines[prefix_len..new_lines.len() - suffix_len];
+
+    let mut hunks = Vec::new();
+
+    let mut old_row = 0;
+    let mut new_row = 0;
+
+    let matches = matching_lines(old_middle, new_middle)
+        .into_iter()
+        .chain(Some((old_middle.len(), new_middle.len())));
+
+    for (old_ix, new_ix) in matches {
+        if old_ix > old_row || new_ix > new_row {
+            hunks.push(DiffHunk {
+                rows: (prefix_len + new_row) as u32..(prefix_len + new_ix) as u32,
+                head_rows: (prefix_len + old_row) as u32..(prefix_len + old_ix) as u32
+            });
+        }
+
+        old_row = old_ix + 1;
+        new_row = new_ix + 1;
+    }
+
+    hunks
+}
+
+/// pares de linhas iguais de uma subsequência comum máxima entre `old` e `new`, ou nenhum par
+/// quando elas diferem em mais de `MAX_EDIT_DISTANCE` linhas
+fn matching_lines(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let old_len = old.len() as isize;
+    let new_len = new.len() as isize;
+
+    let max = (old_len + new_len) as usize;
+    let offset = max as isize;
+
+    // `furthest[k]` guarda o maior `x` alcançado na diagonal `k = x - y`, e `trace[d]` o trecho
+    // de `furthest` que o passo `d` lê, das diagonais `-(d - 1)` a `d - 1`, para reconstruir o
+    // caminho de trás para frente
+    let mut furthest = vec![0_isize; 2 * max + 2];
+    let mut trace = Vec::new();
+    let mut found = false;
+
+    'search: for d in 0..=cmp::min(max, MAX_EDIT_DISTANCE) as isize {
+        trace.push(if d == 0 {
+            Vec::new()
+        } else {
+            furthest[(offset - d + 1) as usize..=(offset + d - 1) as usize].to_vec()
+        });
+
+        for k in (-d..=d).step_by(2) {
+            let ix = (k + offset) as usize;
+
+            let mut x = if k == -d || (k != d && furthest[ix - 1] < furthest[ix + 1]) {
+                furthest[ix + 1]
+            } else {
+                furthest[ix - 1] + 1
+            };
+
+            let mut y = x - k;
+
+            while x < old_len && y < new_len && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            furthest[ix] = x;
+
+            if x >= old_len && y >= new_len {
+                found = true;
+
+                break 'search;
+            }
+        }
+    }
+
+    // o caminho até aqui seria caro demais de completar, então tudo vira um único trecho
+    if !found {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+
+    let mut x = old_len;
+    let mut y = new_len;
+
+    for (d, previous) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let previous = |k: isize| previous[(k + d - 1) as usize];
+
+            let prev_k = if k == -d || (k != d && previous(k - 1) < previous(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+
+            (previous(prev_k), previous(prev_k) - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+
+            matches.push((x as usize, y as usize));
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    matches.reverse();
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let hunk = |rows: Range<u32>, head_rows: Range<u32>| DiffHunk { rows, head_rows };
+
+        assert_eq!(diff_lines("a\nb\nc", "a\nb\nc"), vec![]);
+
+        assert_eq!(diff_lines("a\nb\nc", "a\nx\nb\nc"), vec![hunk(1..2, 1..1)]);
+        assert_eq!(diff_lines("a\nb\nc", "a\nc"), vec![hunk(1..1, 1..2)]);
+        assert_eq!(diff_lines("a\nb\nc", "a\nB\nc"), vec![hunk(1..2, 1..2)]);
+
+        assert_eq!(
+            diff_lines("a\nb\nc\nd\ne", "x\na\nc\nD\ne\ny"),
+            vec![hunk(0..1, 0..0), hunk(2..2, 1..2), hunk(3..4, 3..4), hunk(5..6, 5..5)]
+        );
+
+        assert_eq!(diff_lines("a\nb", "a"), vec![hunk(1..1, 1..2)]);
+        assert_eq!(diff_lines("", "a\nb"), vec![hunk(0..2, 0..1)]);
+
+        // textos diferentes demais viram um único trecho
+        let old = (0..1500).map(|ix| ix.to_string()).collect::<Vec<_>>().join("\n");
+
+        let new = (0..1500)
+            .map(|ix| if ix % 2 == 0 { ix.to_string() } else { "x".to_string() })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(diff_lines(&old, &new), vec![hunk(1..1500, 1..1500)]);
+
+        assert_eq!(hunk(1..2, 1..1).kind(), DiffHunkKind::Added);
+        assert_eq!(hunk(1..1, 1..2).kind(), DiffHunkKind::Deleted);
+        assert_eq!(hunk(1..2, 1..2).kind(), DiffHunkKind::Modified);
+    }
+}
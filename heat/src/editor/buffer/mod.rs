@@ -1,23 +1,29 @@
 mod anchor;
+mod conflict;
+mod diff;
 mod point;
 mod text;
 
 pub use anchor::*;
+pub use conflict::{Conflict, ConflictResolution};
+pub use diff::{diff_lines, DiffHunk, DiffHunkKind};
 pub use point::*;
 pub use text::*;
 
 use crate::{
     operation_queue::{self, OperationQueue},
+    settings::EditorSettings,
     sum_tree::{self, Cursor, FilterCursor, SeekBias, SumTree},
     time::{self, ReplicaId},
     util::RandomCharIter,
-    worktree::FileHandle
+    worktree::{self, FileHandle, GitStatus}
 };
 
 use anyhow::{anyhow, Result};
 use gpui::{AppContext, Entity, ModelContext};
 use lazy_static::lazy_static;
 use rand::prelude::*;
+use smol::Timer;
 
 use std::{
     cmp::{self, Ordering},
@@ -27,17 +33,27 @@ use std::{
     ops::{AddAssign, Range},
     path::PathBuf,
     str,
-    sync::Arc
+    sync::Arc,
+    time::Duration
 };
 
 pub type SelectionSetId = time::Lamport;
 pub type SelectionsVersion = usize;
 
+const DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+const CONFLICT_SCAN_DEBOUNCE: Duration = Duration::from_millis(250);
+
 pub struct Buffer {
     file: Option<FileHandle>,
+    head_text: Option<String>,
+    diff_hunks: Vec<AnchoredDiffHunk>,
+    diff_epoch: usize,
+    conflicts: Vec<Conflict<Anchor>>,
+    conflict_epoch: usize,
     fragments: SumTree<Fragment>,
     insertion_splits: HashMap<time::Local, SumTree<InsertionSplit>>,
     pub version: time::Global,
+    saved_version: time::Global,
     last_edit: time::Local,
     selections: HashMap<SelectionSetId, Vec<Selection>>,
     pub selections_last_update: SelectionsVersion,
@@ -48,6 +64,20 @@ pub struct Buffer {
     lamport_clock: time::Lamport
 }
 
+/// trecho do diff com o `HEAD` preso ao texto do buffer, para acompanhar as edições feitas antes
+/// que o diff seja recalculado
+#[derive(Clone)]
+struct AnchoredDiffHunk {
+    start: Anchor,
+    end: Anchor,
+
+    // o fim do trecho está além da última linha, o que não pode ser expresso por uma âncora
+    reaches_end: bool,
+    is_deletion: bool,
+
+    head_rows: Range<u32>
+}
+
 #[derive(Clone)]
 pub struct History {
     pub base_text: String
@@ -208,11 +238,17 @@ impl Buffer {
             });
         }
 
-        Self {
+        let mut buffer = Self {
             file,
+            head_text: None,
+            diff_hunks: Vec::new(),
+            diff_epoch: 0,
+            conflicts: Vec::new(),
+            conflict_epoch: 0,
             fragments,
             insertion_splits,
             version: time::Global::new(),
+            saved_version: time::Global::new(),
             last_edit: time::Local::default(),
 
             selections: HashMap::default(),
@@ -224,19 +260,390 @@ impl Buffer {
 
             local_clock: time::Local::new(replica_id),
             lamport_clock: time::Lamport::new(replica_id)
+        };
+
+        // arquivos abertos no meio de um merge já mostram os conflitos, sem esperar por uma edição
+        match buffer.anchor_conflicts(conflict::find_conflicts(&buffer.text())) {
+            Ok(conflicts) => buffer.conflicts = conflicts,
+            Err(error) => log::error!("erro ao ancorar os conflitos de merge: {}", error)
         }
+
+        buffer
     }
 
     pub fn path(&self, app: &AppContext) -> Option<PathBuf> {
         self.file.as_ref().map(|file| file.path(app))
     }
 
+    pub fn abs_path(&self, app: &AppContext) -> Option<PathBuf> {
+        self.file.as_ref().map(|file| file.abs_path(app))
+    }
+
+    pub fn file(&self) -> Option<&FileHandle> {
+        self.file.as_ref()
+    }
+
     pub fn entry_id(&self) -> Option<(usize, usize)> {
         self.file.as_ref().map(|file| file.entry_id())
     }
 
     pub fn is_modified(&self) -> bool {
-        self.version != time::Global::new()
+        self.version != self.saved_version
+    }
+
+    /// grava o buffer no seu arquivo, aplicando antes as opções de gravação de `settings` ao texto
+    /// para que o buffer continue igual ao que está no disco
+    pub fn save(&mut self, settings: &EditorSettings, ctx: &mut ModelContext<Self>) -> Result<()> {
+        let file = self.file.clone().ok_or_else(|| anyhow!("o buffer não tem um arquivo"))?;
+        let line_ending = settings.end_of_line.as_str();
+
+        if settings.trim_trailing_whitespace {
+            let ranges = trailing_whitespace(&self.text());
+
+            if !ranges.is_empty() {
+                self.edit(ranges, "", Some(ctx))?;
+            }
+        }
+
+        let ranges = foreign_line_breaks(&self.text(), line_ending);
+
+        if !ranges.is_empty() {
+            self.edit(ranges, line_ending, Some(ctx))?;
+        }
+
+        if settings.insert_final_newline {
+            let text = self.text();
+
+            if !text.is_empty() && !text.ends_with(&['\n', '\r'][..]) {
+                let len = self.len();
+
+                self.edit(Some(len..len), line_ending, Some(ctx))?;
+            }
+        }
+
+        let version = self.version.clone();
+        let task = file.save(self.text(), ctx.app());
+
+        ctx.spawn(task, move |me, result, ctx| match result {
+            Ok(()) => {
+                me.saved_version = version;
+
+                ctx.notify();
+            }
+
+            Err(error) => log::error!("erro ao gravar o buffer: {}", error)
+        })
+        .detach();
+
+        Ok(())
+    }
+
+    pub fn git_status(&self, app: &AppContext) -> Option<GitStatus> {
+        self.file.as_ref().and_then(|file| file.git_status(app))
+    }
+
+    /// conteúdo do arquivo no `HEAD` do repositório, para comparar com o texto atual
+    pub fn head_text(&self) -> Option<&str> {
+        self.head_text.as_deref()
+    }
+
+    pub fn set_head_text(&mut self, head_text: Option<String>, ctx: &mut ModelContext<Self>) {
+        if self.head_text != head_text {
+            self.head_text = head_text;
+
+            ctx.emit(Event::HeadChanged);
+            ctx.notify();
+
+            self.schedule_diff(ctx);
+        }
+    }
+
+    /// trechos alterados em relação ao `HEAD`, em ordem
+    pub fn diff_hunks(&self) -> Vec<DiffHunk> {
+        let max_row = self.max_point().row;
+
+        self.diff_hunks
+            .iter()
+            .filter_map(|hunk| {
+                let end = if hunk.reaches_end {
+                    max_row + 1
+                } else {
+                    hunk.end.to_point(self).ok()?.row
+                };
+
+                let start = if hunk.is_deletion {
+                    end
+                } else {
+                    cmp::min(hunk.start.to_point(self).ok()?.row, end)
+                };
+
+                Some(DiffHunk {
+                    rows: start..end,
+                    head_rows: hunk.head_rows.clone()
+                })
+            }).collect()
+    }
+
+    /// linhas que o trecho ocupava no `HEAD`
+    pub fn head_text_for_hunk(&self, hunk: &DiffHunk) -> Option<String> {
+        let head_lines = self.head_lines(hunk)?;
+
+        Some(head_lines.join("\n"))
+    }
+
+    /// substitui as linhas do trecho pelas que ele ocupava no `HEAD`
+    pub fn revert_diff_hunk(
+        &mut self,
+
+        hunk: &DiffHunk,
+
+        ctx: Option<&mut ModelContext<Self>>
+    ) -> Result<Vec<Operation>> {
+        let head_lines = self
+            .head_lines(hunk)
+            .ok_or_else(|| anyhow!("o trecho não corresponde ao texto do HEAD"))?;
+
+        let range;
+        let text: String;
+
+        if hunk.rows.end > self.max_point().row {
+            // a última linha não termina com `\n`, então o trecho começa no fim da linha anterior
+            if hunk.rows.start > 0 {
+                let row = hunk.rows.start - 1;
+
+                range = Point::new(row, self.line_len(row)?).to_offset(self)?..self.len();
+                text = head_lines.iter().map(|line| format!("\n{}", line)).collect();
+            } else {
+                range = 0..self.len();
+                text = head_lines.join("\n");
+            }
+        } else {
+            range = Point::new(hunk.rows.start, 0).to_offset(self)?..Point::new(hunk.rows.end, 0).to_offset(self)?;
+            text = head_lines.iter().map(|line| format!("{}\n", line)).collect();
+        }
+
+        self.edit(Some(range), text, ctx)
+    }
+
+    fn head_lines(&self, hunk: &DiffHunk) -> Option<Vec<&str>> {
+        let head_lines = self.head_text.as_ref()?.split('\n').collect::<Vec<_>>();
+
+        head_lines
+            .get(hunk.head_rows.start as usize..hunk.head_rows.end as usize)
+            .map(|lines| lines.to_vec())
+    }
+
+    /// recalcula o diff com o `HEAD` em segundo plano quando as edições param por um instante
+    fn schedule_diff(&mut self, ctx: &mut ModelContext<Self>) {
+        if self.head_text.is_none() && self.diff_hunks.is_empty() {
+            return;
+        }
+
+        self.diff_epoch += 1;
+
+        let epoch = self.diff_epoch;
+
+        ctx.spawn(Timer::after(DIFF_DEBOUNCE), move |me, _, ctx| me.compute_diff(epoch, ctx))
+            .detach();
+    }
+
+    fn compute_diff(&mut self, epoch: usize, ctx: &mut ModelContext<Self>) {
+        if epoch != self.diff_epoch {
+            return;
+        }
+
+        let head_text = match self.head_text.clone() {
+            Some(head_text) => head_text,
+
+            None => {
+                self.diff_hunks.clear();
+
+                ctx.notify();
+
+                return;
+            }
+        };
+
+        let text = self.text();
+        let version = self.version.clone();
+
+        let task = ctx
+            .background_executor()
+            .spawn(async move { diff::diff_lines(&head_text, &text) });
+
+        ctx.spawn(task, move |me, hunks, ctx| me.diff_computed(epoch, version, hunks, ctx))
+            .detach();
+    }
+
+    fn diff_computed(
+        &mut self,
+
+        epoch: usize,
+        version: time::Global,
+        hunks: Vec<DiffHunk>,
+
+        ctx: &mut ModelContext<Self>
+    ) {
+        // o texto mudou enquanto o diff era calculado, e um novo cálculo já foi agendado
+        if epoch != self.diff_epoch || version != self.version {
+            return;
+        }
+
+        let max_row = self.max_point().row;
+
+        let anchor = |row: u32| {
+            if row > max_row {
+                Ok(Anchor::End)
+            } else {
+                self.anchor_before(Point::new(row, 0))
+            }
+        };
+
+        let hunks = hunks
+            .into_iter()
+            .map(|hunk| {
+                Ok(AnchoredDiffHunk {
+                    start: anchor(hunk.rows.start)?,
+                    end: anchor(hunk.rows.end)?,
+
+                    reaches_end: hunk.rows.end > max_row,
+                    is_deletion: hunk.rows.start == hunk.rows.end,
+
+                    head_rows: hunk.head_rows
+                })
+            }).collect::<Result<Vec<_>>>();
+
+        match hunks {
+            Ok(hunks) => {
+                self.diff_hunks = hunks;
+
+                ctx.notify();
+            }
+
+            Err(error) => log::error!("erro ao ancorar o diff com o HEAD: {}", error)
+        }
+    }
+
+    /// conflitos de merge do texto, em ordem, presos ao texto para acompanhar as edições
+    pub fn conflicts(&self) -> &[Conflict<Anchor>] {
+        &self.conflicts
+    }
+
+    /// substitui o conflito pelo lado escolhido, removendo as linhas de marcação
+    pub fn resolve_conflict(
+        &mut self,
+
+        ix: usize,
+        resolution: ConflictResolution,
+
+        ctx: Option<&mut ModelContext<Self>>
+    ) -> Result<Vec<Operation>> {
+        let conflict = self
+            .conflicts
+            .get(ix)
+            .cloned()
+            .ok_or_else(|| anyhow!("conflito {} não existe", ix))?;
+
+        let ours = self.text_for_range(conflict.ours.clone())?;
+        let theirs = self.text_for_range(conflict.theirs.clone())?;
+
+        let text = match resolution {
+            ConflictResolution::Ours => ours,
+            ConflictResolution::Theirs => theirs,
+            ConflictResolution::Both => ours + &theirs
+        };
+
+        let range = conflict.range.start.to_offset(self)?..conflict.range.end.to_offset(self)?;
+
+        let operations = self.edit(Some(range), text, ctx)?;
+
+        // só esquece o conflito depois que a edição deu certo
+        self.conflicts.remove(ix);
+
+        Ok(operations)
+    }
+
+    /// as bordas do conflito não avançam sobre o texto digitado em volta dele, enquanto cada lado
+    /// cresce com o texto digitado nas suas bordas
+    fn anchor_conflicts(&self, conflicts: Vec<Conflict<usize>>) -> Result<Vec<Conflict<Anchor>>> {
+        conflicts
+            .into_iter()
+            .map(|conflict| {
+                Ok(Conflict {
+                    range: self.anchor_after(conflict.range.start)?..self.anchor_before(conflict.range.end)?,
+                    ours: self.anchor_before(conflict.ours.start)?..self.anchor_after(conflict.ours.end)?,
+                    theirs: self.anchor_before(conflict.theirs.start)?..self.anchor_after(conflict.theirs.end)?
+                })
+            }).collect()
+    }
+
+    /// procura os conflitos de novo em segundo plano quando as edições param por um instante, o que
+    /// descarta os conflitos cujas marcações foram apagadas
+    fn schedule_conflict_scan(&mut self, ctx: &mut ModelContext<Self>) {
+        if self.file.is_none() && self.conflicts.is_empty() {
+            return;
+        }
+
+        self.conflict_epoch += 1;
+
+        let epoch = self.conflict_epoch;
+
+        ctx.spawn(Timer::after(CONFLICT_SCAN_DEBOUNCE), move |me, _, ctx| me.scan_conflicts(epoch, ctx))
+            .detach();
+    }
+
+    fn scan_conflicts(&mut self, epoch: usize, ctx: &mut ModelContext<Self>) {
+        if epoch != self.conflict_epoch {
+            return;
+        }
+
+        let text = self.text();
+        let version = self.version.clone();
+
+        let task = ctx
+            .background_executor()
+            .spawn(async move { conflict::find_conflicts(&text) });
+
+        ctx.spawn(task, move |me, conflicts, ctx| me.conflicts_scanned(epoch, version, conflicts, ctx))
+            .detach();
+    }
+
+    fn conflicts_scanned(
+        &mut self,
+
+        epoch: usize,
+        version: time::Global,
+        conflicts: Vec<Conflict<usize>>,
+
+        ctx: &mut ModelContext<Self>
+    ) {
+        if epoch != self.conflict_epoch || version != self.version {
+            return;
+        }
+
+        match self.anchor_conflicts(conflicts) {
+            Ok(conflicts) => {
+                self.conflicts = conflicts;
+
+                ctx.notify();
+            }
+
+            Err(error) => log::error!("erro ao ancorar os conflitos de merge: {}", error)
+        }
+    }
+
+    /// recarrega o texto do `HEAD` sempre que o status do git da árvore muda ou que algo muda em
+    /// `.git`, o que cobre commits, checkouts e mudanças no índice
+    pub fn observe_head(&self, ctx: &mut ModelContext<Self>) {
+        if let Some(file) = self.file.clone() {
+            ctx.subscribe(file.worktree(), move |_, event, ctx| {
+                if let worktree::Event::GitStatusChanged = event {
+                    let task = file.load_head_text(ctx.app());
+
+                    ctx.spawn(task, Self::set_head_text).detach();
+                }
+            });
+        }
     }
 
     pub fn text_summary(&self) -> TextSummary {
@@ -432,6 +839,9 @@ impl Buffer {
                 if !changes.is_empty() {
                     ctx.emit(Event::Edited(changes))
                 }
+
+                self.schedule_diff(ctx);
+                self.schedule_conflict_scan(ctx);
             }
 
             if let Operation::Edit {
@@ -687,6 +1097,9 @@ impl Buffer {
             if !changes.is_empty() {
                 ctx.emit(Event::Edited(changes));
             }
+
+            self.schedule_diff(ctx);
+            self.schedule_conflict_scan(ctx);
         }
 
         Ok(())
@@ -1546,9 +1959,15 @@ impl Clone for Buffer {
     fn clone(&self) -> Self {
         Self {
             file: self.file.clone(),
+            head_text: self.head_text.clone(),
+            diff_hunks: self.diff_hunks.clone(),
+            diff_epoch: self.diff_epoch,
+            conflicts: self.conflicts.clone(),
+            conflict_epoch: self.conflict_epoch,
             fragments: self.fragments.clone(),
             insertion_splits: self.insertion_splits.clone(),
             version: self.version.clone(),
+            saved_version: self.saved_version.clone(),
             last_edit: self.last_edit.clone(),
             
             selections: self.selections.clone(),
@@ -1565,9 +1984,68 @@ impl Clone for Buffer {
     }
 }
 
+/// intervalos, em caracteres, dos espaços e tabs no fim de cada linha
+fn trailing_whitespace(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut run_start = None;
+    let mut len = 0;
+
+    for (offset, char) in text.chars().enumerate() {
+        match char {
+            ' ' | '\t' => {
+                run_start.get_or_insert(offset);
+            }
+
+            '\n' | '\r' => {
+                if let Some(start) = run_start.take() {
+                    ranges.push(start..offset);
+                }
+            }
+
+            _ => run_start = None
+        }
+
+        len = offset + 1;
+    }
+
+    if let Some(start) = run_start {
+        ranges.push(start..len);
+    }
+
+    ranges
+}
+
+/// intervalos, em caracteres, das quebras de linha diferentes de `line_ending`
+fn foreign_line_breaks(text: &str, line_ending: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut chars = text.chars().enumerate().peekable();
+
+    while let Some((offset, char)) = chars.next() {
+        let line_break = match char {
+            '\r' if chars.peek().map(|(_, next)| *next) == Some('\n') => {
+                chars.next();
+
+                "\r\n"
+            }
+
+            '\r' => "\r",
+            '\n' => "\n",
+
+            _ => continue
+        };
+
+        if line_break != line_ending {
+            ranges.push(offset..offset + line_break.chars().count());
+        }
+    }
+
+    ranges
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Event {
-    Edited(Vec<Edit>)
+    Edited(Vec<Edit>),
+    HeadChanged
 }
 
 impl Entity for Buffer {
@@ -2114,7 +2592,8 @@ impl ToPoint for usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::BTreeMap;
+    use crate::settings::{IndentStyle, LineEnding};
+    use std::{collections::BTreeMap, fs};
 
     #[test]
     fn test_edit() -> Result<()> {
@@ -2733,6 +3212,122 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_diff_hunks() {
+        use gpui::App;
+
+        App::test((), |mut app| async move {
+            let buffer = app.add_model(|_| Buffer::new(0, "a\nB\nc\nd\nx"));
+
+            buffer.update(&mut app, |buffer, ctx| {
+                buffer.set_head_text(Some("a\nb\nc\nd".into()), ctx)
+            });
+
+            app.finish_pending_tasks().await;
+
+            let hunks = buffer.read(&app, |buffer, _| buffer.diff_hunks());
+
+            assert_eq!(
+                hunks,
+
+                vec![
+                    DiffHunk { rows: 1..2, head_rows: 1..2 },
+                    DiffHunk { rows: 4..5, head_rows: 4..4 }
+                ]
+            );
+
+            // os trechos acompanham as edições até que o diff seja recalculado
+            buffer.update(&mut app, |buffer, ctx| buffer.edit(Some(0..0), "z\n", Some(ctx)).unwrap());
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(buffer.diff_hunks()[0].rows, 2..3);
+                assert_eq!(buffer.head_text_for_hunk(&buffer.diff_hunks()[0]), Some("b".into()));
+            });
+
+            app.finish_pending_tasks().await;
+
+            buffer.update(&mut app, |buffer, ctx| {
+                for hunk in buffer.diff_hunks().into_iter().rev() {
+                    buffer.revert_diff_hunk(&hunk, Some(ctx)).unwrap();
+                }
+            });
+
+            buffer.read(&app, |buffer, _| assert_eq!(buffer.text(), "a\nb\nc\nd"));
+
+            app.finish_pending_tasks().await;
+
+            buffer.read(&app, |buffer, _| assert!(buffer.diff_hunks().is_empty()));
+        });
+    }
+
+    #[test]
+    fn test_conflicts() {
+        use gpui::App;
+
+        App::test((), |mut app| async move {
+            let text = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nb\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> branch\n";
+            let buffer = app.add_model(|_| Buffer::new(0, text));
+
+            let conflict_text = |buffer: &Buffer, ix: usize| {
+                let conflict = &buffer.conflicts()[ix];
+
+                (
+                    buffer.text_for_range(conflict.ours.clone()).unwrap(),
+                    buffer.text_for_range(conflict.theirs.clone()).unwrap()
+                )
+            };
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(buffer.conflicts().len(), 2);
+                assert_eq!(conflict_text(buffer, 0), ("ours\n".into(), "theirs\n".into()));
+            });
+
+            // cada lado acompanha o texto digitado nas suas bordas
+            buffer.update(&mut app, |buffer, ctx| {
+                let offset = buffer.conflicts()[0].ours.start.to_offset(buffer).unwrap();
+
+                buffer.edit(Some(offset..offset), "new ", Some(ctx)).unwrap();
+            });
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(conflict_text(buffer, 0), ("new ours\n".into(), "theirs\n".into()));
+            });
+
+            buffer.update(&mut app, |buffer, ctx| {
+                buffer.resolve_conflict(1, ConflictResolution::Both, Some(ctx)).unwrap();
+            });
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(buffer.text(), "a\n<<<<<<< HEAD\nnew ours\n=======\ntheirs\n>>>>>>> branch\nb\nx\ny\n");
+                assert_eq!(buffer.conflicts().len(), 1);
+            });
+
+            // conflitos novos aparecem e os que perderam uma marcação somem depois da nova busca
+            buffer.update(&mut app, |buffer, ctx| {
+                buffer.edit(Some(0..0), "<<<<<<< HEAD\n=======\n>>>>>>> branch\n", Some(ctx)).unwrap();
+            });
+
+            app.finish_pending_tasks().await;
+
+            buffer.read(&app, |buffer, _| assert_eq!(buffer.conflicts().len(), 2));
+
+            buffer.update(&mut app, |buffer, ctx| buffer.edit(Some(0..13), "", Some(ctx)).unwrap());
+
+            app.finish_pending_tasks().await;
+
+            buffer.update(&mut app, |buffer, ctx| {
+                assert_eq!(buffer.conflicts().len(), 1);
+
+                buffer.resolve_conflict(0, ConflictResolution::Theirs, Some(ctx)).unwrap();
+            });
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(buffer.text(), "=======\n>>>>>>> branch\na\ntheirs\nb\nx\ny\n");
+                assert!(buffer.conflicts().is_empty());
+            });
+        });
+    }
+
     #[test]
     fn test_is_modified() -> Result<()> {
         let mut buffer = Buffer::new(0, "abc");
@@ -2745,6 +3340,98 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_save_trims_trailing_whitespace() {
+        let settings = EditorSettings {
+            trim_trailing_whitespace: true,
+
+            ..editor_settings()
+        };
+
+        assert_eq!(saved_text("a  \nb\t\n  \nc ", settings), "a\nb\n\nc");
+    }
+
+    #[test]
+    fn test_save_inserts_final_newline() {
+        let settings = EditorSettings {
+            insert_final_newline: true,
+
+            ..editor_settings()
+        };
+
+        assert_eq!(saved_text("a\nb", settings.clone()), "a\nb\n");
+        assert_eq!(saved_text("a\nb\n", settings.clone()), "a\nb\n");
+        assert_eq!(saved_text("", settings), "");
+    }
+
+    #[test]
+    fn test_save_converts_line_endings() {
+        let settings = EditorSettings {
+            end_of_line: LineEnding::CrLf,
+
+            ..editor_settings()
+        };
+
+        assert_eq!(saved_text("a\r\nb\rc\nd", settings.clone()), "a\r\nb\r\nc\r\nd");
+
+        let settings = EditorSettings {
+            insert_final_newline: true,
+
+            ..settings
+        };
+
+        assert_eq!(saved_text("a\nb", settings), "a\r\nb\r\n");
+    }
+
+    fn editor_settings() -> EditorSettings {
+        EditorSettings {
+            indent_style: IndentStyle::Space,
+            indent_size: 4,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            end_of_line: LineEnding::Lf
+        }
+    }
+
+    /// abre um arquivo com `text`, grava o buffer com `settings` e devolve o que ficou no disco
+    fn saved_text(text: &str, settings: EditorSettings) -> String {
+        use crate::{
+            test::temp_tree,
+            worktree::{Worktree, WorktreeHandle}
+        };
+
+        use gpui::App;
+        use serde_json::json;
+
+        let dir = temp_tree(json!({ "file.txt": text }));
+        let path = dir.path().join("file.txt");
+
+        App::test((), |mut app| async move {
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), dir.path(), Some(ctx)));
+
+            app.finish_pending_tasks().await;
+
+            let (file, history) = tree.read(&app, |worktree, app| {
+                let entry_id = worktree.entry_for_path(&path).unwrap();
+
+                (tree.file(entry_id, app).unwrap(), worktree.load_history(entry_id))
+            });
+
+            let history = history.await.unwrap();
+            let buffer = app.add_model(|_| Buffer::from_history(0, file, history));
+
+            buffer.update(&mut app, |buffer, ctx| buffer.save(&settings, ctx)).unwrap();
+
+            app.finish_pending_tasks().await;
+
+            buffer.read(&app, |buffer, _| {
+                assert!(!buffer.is_modified());
+
+                fs::read_to_string(&path).unwrap()
+            })
+        })
+    }
+
     #[test]
     fn test_random_concurrent_edits() {
         use crate::test::Network;
@@ -1,6 +1,11 @@
-use super::{BufferView, DisplayPoint, SelectAction};
+use super::{
+    BufferView, ConflictAction, ConflictRegion, DiffHunkKind, DisplayPoint, InlineBlockKind,
+    SelectAction
+};
 
 use gpui::{
+    color::ColorU,
+
     geometry::{
         rect::RectF,
         vector::{vec2f, Vector2F}
@@ -12,10 +17,7 @@ use gpui::{
     MutableAppContext, PaintContext, Scene, SizeConstraint, ViewHandle
 };
 
-use std::{
-    cmp::{self},
-    sync::Arc
-};
+use std::{ops::Range, sync::Arc};
 
 pub struct BufferElement {
     view: ViewHandle<BufferView>,
@@ -37,7 +39,10 @@ impl BufferElement {
         &self,
 
         position: Vector2F,
+        alt: bool,
         cmd: bool,
+        shift: bool,
+        click_count: usize,
 
         ctx: &mut EventContext,
         app: &AppContext
@@ -47,9 +52,36 @@ impl BufferElement {
 
         if paint.text_rect.contains_point(position) {
             let view = self.view.as_ref(app);
+
+            if let Some(action) = paint.conflict_action_for_position(view, layout, position, ctx.font_cache, app) {
+                ctx.dispatch_action("buffer:resolve_conflict", (action.conflict_ix, action.resolution));
+
+                return true;
+            }
+
+            if alt {
+                let position = paint.unclamped_point_for_position(view, layout, position, ctx.font_cache, app);
+
+                ctx.dispatch_action("buffer:select", SelectAction::BeginColumnar { position });
+
+                return true;
+            }
+
             let position = paint.point_for_position(view, layout, position, ctx.font_cache, app);
 
-            ctx.dispatch_action("buffer:select", SelectAction::Begin { position, add: cmd });
+            if shift {
+                ctx.dispatch_action("buffer:select", SelectAction::Extend { position });
+            } else {
+                ctx.dispatch_action(
+                    "buffer:select",
+
+                    SelectAction::Begin {
+                        position,
+                        add: cmd,
+                        click_count
+                    }
+                );
+            }
 
             true
         } else {
@@ -107,11 +139,18 @@ impl BufferElement {
                 ))
             }
 
+            // seleções em coluna seguem a coluna visual além do fim das linhas curtas
+            let position = if view.is_selecting_columns() {
+                paint.unclamped_point_for_position(view, layout, position, ctx.font_cache, app)
+            } else {
+                paint.point_for_position(view, layout, position, ctx.font_cache, app)
+            };
+
             ctx.dispatch_action(
                 "buffer:select",
 
                 SelectAction::Update {
-                    position: paint.point_for_position(view, layout, position, ctx.font_cache, app),
+                    position,
 
                     scroll_position: (view.scroll_position() + scroll_delta).clamp(
                         Vector2F::zero(),
@@ -201,6 +240,7 @@ impl BufferElement {
     fn paint_gutter(&mut self, rect: RectF, ctx: &mut PaintContext, app: &AppContext) {
         // if let Some(layout) = self.layout.as_ref() {
         //     let view = self.view.as_ref(app);
+        //     let theme = view.theme();
 
         //     let scene = &mut ctx.scene;
         //     let font_cache = &ctx.font_cache;
@@ -210,7 +250,7 @@ impl BufferElement {
 
         //     scene.save();
         //     scene.translate(rect.origin());
-        //     scene.set_fill_style(FillStyle::Color(ColorU::white()));
+        //     scene.set_fill_style(FillStyle::Color(theme.ui.gutter_background));
 
         //     let rect = RectF::new(Vector2F::zero(), rect.size());
         //     let mut rect_path = Path2D::new();
@@ -220,6 +260,24 @@ impl BufferElement {
         //     scene.clip_path(rect_path, FillRule::EvenOdd);
         //     scene.fill_rect(rect);
 
+        //     // marcadores do diff com o `HEAD`: uma barra ao longo das linhas adicionadas ou
+        //     // modificadas e um traço entre as linhas onde houve uma remoção
+        //     let start_row = view.scroll_position().y() as u32;
+        //     let marker_width = 0.2 * line_height;
+
+        //     for (rows, kind) in &layout.diff_markers {
+        //         let top = rows.start as f32 * line_height - scroll_top;
+
+        //         let height = match kind {
+        //             DiffHunkKind::Deleted => marker_width,
+
+        //             _ => (rows.end - rows.start) as f32 * line_height
+        //         };
+
+        //         scene.set_fill_style(FillStyle::Color(theme.ui.diff_hunk(*kind)));
+        //         scene.fill_rect(RectF::new(vec2f(0.0, top), vec2f(marker_width, height)));
+        //     }
+
         //     for (ix, line) in layout.line_number_layouts.iter().enumerate() {
         //         let line_origin = vec2f(
         //             rect.width() - line.width - layout.gutter_padding,
@@ -229,7 +287,7 @@ impl BufferElement {
         //         line.paint(
         //             line_origin,
         //             rect,
-        //             &[(0..line.len, ColorU::black())],
+        //             &[(0..line.len, theme.ui.line_number)],
         //             scene,
         //             font_cache
         //         );
@@ -243,10 +301,25 @@ impl BufferElement {
         // if let Some(layout) = self.layout.as_ref() {
         //     let scene = &mut ctx.scene;
         //     let font_cache = &ctx.font_cache;
+        //     let theme = self.view.as_ref(app).theme();
+
+        //     // conflitos de merge: cada lado com a sua cor de fundo, por baixo das seleções
+        //     for (rows, region) in &layout.conflict_regions {
+        //         let background = match region {
+        //             ConflictRegion::Marker => theme.ui.conflict_marker_background,
+        //             ConflictRegion::Ours => theme.ui.conflict_ours_background,
+        //             ConflictRegion::Theirs => theme.ui.conflict_theirs_background
+        //         };
+        //         scene.set_fill_style(FillStyle::Color(background));
+        //         scene.fill_rect(RectF::new(
+        //             vec2f(0.0, rows.start as f32 * line_height - scroll_top),
+        //             vec2f(rect.width(), (rows.end - rows.start) as f32 * line_height)
+        //         ));
+        //     }
 
         //     scene.save();
         //     scene.translate(rect.origin());
-        //     scene.set_fill_style(FillStyle::Color(ColorU::white()));
+        //     scene.set_fill_style(FillStyle::Color(theme.ui.editor_background));
 
         //     let rect = RectF::new(Vector2F::zero(), rect.size());
         //     let mut rect_path = Path2D::new();
@@ -290,6 +363,7 @@ impl BufferElement {
 
         //             let selection = Selection {
         //                 line_height,
+        //                 color: theme.ui.selection,
 
         //                 start_y: row_range.start as f32 * line_height - scroll_top,
 
@@ -336,7 +410,8 @@ impl BufferElement {
 
         //                     y: selection.end.row() as f32 * line_height - scroll_top,
 
-        //                     line_height
+        //                     line_height,
+        //                     color: theme.ui.cursor
         //                 });
         //             }
         //         }
@@ -345,7 +420,7 @@ impl BufferElement {
         //     scene.restore();
 
         //     // desenhar glifos
-        //     scene.set_fill_style(FillStyle::Color(ColorU::black()));
+        //     scene.set_fill_style(FillStyle::Color(theme.ui.editor_text));
 
         //     for (ix, line) in layout.line_layouts.iter().enumerate() {
         //         let row = start_row + ix as u32;
@@ -355,10 +430,53 @@ impl BufferElement {
         //             row as f32 * line_height - scroll_top
         //         );
 
+        //         // cores do realce de sintaxe, que acompanham o tema atual
+        //         let default_colors = [(0..line.len, theme.ui.editor_text)];
+        //         let colors = layout.line_colors.get(ix).map_or(&default_colors[..], Vec::as_slice);
+
         //         line.paint(
         //             line_origin,
         //             rect,
-        //             &[(0..line.len, ColorU::black())],
+        //             colors,
+        //             scene,
+        //             font_cache
+        //         );
+        //     }
+
+        //     // blocos somente leitura numa faixa acima da sua linha: o preenchimento que alinha os
+        //     // lados de um diff e as linhas removidas, como o texto do `HEAD` do trecho expandido
+        //     for (row, kind, lines) in &layout.inline_blocks {
+        //         let top = *row as f32 * line_height - scroll_top;
+        //         let band = RectF::new(
+        //             vec2f(0.0, top),
+        //             vec2f(rect.width(), lines.len() as f32 * line_height)
+        //         );
+
+        //         let background = match kind {
+        //             InlineBlockKind::Filler => theme.ui.gutter_background,
+        //             InlineBlockKind::Removed => theme.ui.head_text_background
+        //         };
+
+        //         scene.set_fill_style(FillStyle::Color(background));
+        //         scene.fill_rect(band);
+
+        //         for (ix, line) in lines.iter().enumerate() {
+        //             line.paint(
+        //                 vec2f(-scroll_left - descent, top + ix as f32 * line_height),
+        //                 rect,
+        //                 &[(0..line.len, theme.ui.editor_text)],
+        //                 scene,
+        //                 font_cache
+        //             );
+        //         }
+        //     }
+
+        //     // botões de aceitar cada lado, ao lado da marcação de início do conflito
+        //     for action in &layout.conflict_actions {
+        //         action.label.paint(
+        //             vec2f(action.x.start - scroll_left, action.row as f32 * line_height - scroll_top),
+        //             rect,
+        //             &[(0..action.label.len, theme.ui.conflict_action_text)],
         //             scene,
         //             font_cache
         //         );
@@ -387,7 +505,7 @@ impl Element for BufferElement {
         if size.y().is_infinite() {
             let view = self.view.as_ref(app);
 
-            size.set_y((view.max_point(app).row() + 1) as f32 * view.line_height(ctx.font_cache));
+            size.set_y(view.visual_row_count(app) as f32 * view.line_height(ctx.font_cache));
         }
 
         if size.x().is_infinite() {
@@ -437,9 +555,38 @@ impl Element for BufferElement {
             Vec::new()
         };
 
-        let start_row = view.scroll_position().y() as u32;
+        // a rolagem conta as linhas dos blocos, então as linhas de exibição visíveis saem das visuais
         let scroll_top = view.scroll_position().y() * line_height;
-        let end_row = ((scroll_top + size.y()) / line_height).ceil() as u32 + 1; // adicionar 1 para garantir que as seleções saiam da tela
+        let start_row = view.display_row_for_visual_row(view.scroll_position().y() as u32, app);
+        let end_row = view.display_row_for_visual_row(((scroll_top + size.y()) / line_height).ceil() as u32, app) + 1; // adicionar 1 para garantir que as seleções saiam da tela
+
+        let diff_markers = if view.is_gutter_visible() {
+            view.diff_markers(start_row..end_row, app)
+        } else {
+            Vec::new()
+        };
+
+        let inline_blocks = match view.layout_inline_blocks(font_cache, layout_cache, app) {
+            Err(error) => {
+                log::error!("erro ao traçar os blocos do buffer: {}", error);
+
+                Vec::new()
+            }
+
+            Ok(layouts) => layouts
+        };
+
+        let conflict_regions = view.conflict_regions(start_row..end_row, app);
+
+        let conflict_actions = match view.layout_conflict_actions(start_row..end_row, font_cache, layout_cache, app) {
+            Err(error) => {
+                log::error!("erro ao traçar as ações dos conflitos: {}", error);
+
+                Vec::new()
+            }
+
+            Ok(actions) => actions
+        };
 
         let mut max_visible_line_width = 0.0;
 
@@ -461,13 +608,29 @@ impl Element for BufferElement {
             }
         };
 
+        let line_colors = match view.line_colors(start_row..end_row, app) {
+            Err(error) => {
+                log::error!("erro ao realçar linhas: {}", error);
+
+                Vec::new()
+            }
+
+            Ok(colors) => colors
+        };
+
         self.layout = Some(LayoutState {
             size,
             gutter_size,
             gutter_padding,
             text_size,
+            start_row,
             line_layouts,
+            line_colors,
             line_number_layouts,
+            diff_markers,
+            inline_blocks,
+            conflict_regions,
+            conflict_actions,
             max_visible_line_width,
             autoscroll_horizontally,
         });
@@ -488,7 +651,7 @@ impl Element for BufferElement {
 
         if layout.autoscroll_horizontally {
             view.autoscroll_horizontally(
-                view.scroll_position().y() as u32,
+                layout.start_row,
 
                 layout.text_size.x(),
                 layout.scroll_width(view, ctx.font_cache, ctx.text_layout_cache, app.downgrade()),
@@ -529,7 +692,15 @@ impl Element for BufferElement {
 
     fn dispatch_event(&self, event: &Event, ctx: &mut EventContext, app: &AppContext) -> bool {
         match event {
-            Event::LeftMouseDown { position, cmd } => self.mouse_down(*position, *cmd, ctx, app),
+            Event::LeftMouseDown {
+                position,
+                alt,
+                cmd,
+                shift,
+                click_count,
+                ..
+            } => self.mouse_down(*position, *alt, *cmd, *shift, *click_count, ctx, app),
+
             Event::LeftMouseUp { position } => self.mouse_up(*position, ctx, app),
             Event::LeftMouseDragged { position } => self.mouse_dragged(*position, ctx, app),
 
@@ -539,7 +710,9 @@ impl Element for BufferElement {
                 precise
             } => self.scroll(*position, *delta, *precise, ctx, app),
 
-            Event::KeyDown { chars, .. } => self.key_down(chars, ctx, app)
+            Event::KeyDown { chars, .. } => self.key_down(chars, ctx, app),
+
+            Event::ModifiersChanged { .. } | Event::MouseMoved { .. } => false
         }
     }
 
@@ -556,9 +729,19 @@ struct LayoutState {
 
     text_size: Vector2F,
 
+    // linha de exibição da primeira linha traçada
+    start_row: u32,
+
     line_layouts: Vec<Arc<text_layout::Line>>,
+    line_colors: Vec<Vec<(Range<usize>, ColorU)>>,
     line_number_layouts: Vec<Arc<text_layout::Line>>,
 
+    diff_markers: Vec<(Range<u32>, DiffHunkKind)>,
+    inline_blocks: Vec<(u32, InlineBlockKind, Vec<Arc<text_layout::Line>>)>,
+
+    conflict_regions: Vec<(Range<u32>, ConflictRegion)>,
+    conflict_actions: Vec<ConflictAction>,
+
     max_visible_line_width: f32,
     autoscroll_horizontally: bool
 }
@@ -596,7 +779,7 @@ impl LayoutState {
         vec2f(
             ((self.scroll_width(view, font_cache, layout_cache, app) - self.text_size.x()) / view.em_width(font_cache))
             .max(0.0),
-            view.max_point(app).row().saturating_sub(1) as f32
+            view.visual_row_count(app).saturating_sub(2) as f32
         )
     }
 }
@@ -620,10 +803,10 @@ impl PaintState {
 
         let y = position.y().max(0.0).min(layout.size.y());
 
-        let row = ((y / view.line_height(font_cache)) + scroll_position.y()) as u32;
-        let row = cmp::min(row, view.max_point(app).row());
+        let visual_row = ((y / view.line_height(font_cache)) + scroll_position.y()) as u32;
+        let row = view.display_row_for_visual_row(visual_row, app);
 
-        let line = &layout.line_layouts[(row - scroll_position.y() as u32) as usize];
+        let line = &layout.line_layouts[(row - layout.start_row) as usize];
 
         let x = position.x() + (scroll_position.x() * view.em_width(font_cache));
 
@@ -637,18 +820,69 @@ impl PaintState {
 
         DisplayPoint::new(row, column)
     }
+
+    fn unclamped_point_for_position(
+        &self,
+        view: &BufferView,
+        layout: &LayoutState,
+        position: Vector2F,
+        font_cache: &FontCache,
+        app: &AppContext
+    ) -> DisplayPoint {
+        let mut point = self.point_for_position(view, layout, position, font_cache, app);
+
+        let scroll_position = view.scroll_position();
+        let em_width = view.em_width(font_cache);
+
+        let line = &layout.line_layouts[(point.row() - layout.start_row) as usize];
+        let x = position.x() - self.text_rect.origin_x() + scroll_position.x() * em_width;
+
+        if x > line.width {
+            *point.column_mut() += ((x - line.width) / em_width).round() as u32;
+        }
+
+        point
+    }
+
+    fn conflict_action_for_position<'a>(
+        &self,
+        view: &BufferView,
+        layout: &'a LayoutState,
+        position: Vector2F,
+        font_cache: &FontCache,
+        app: &AppContext
+    ) -> Option<&'a ConflictAction> {
+        let scroll_position = view.scroll_position();
+        let position = position - self.text_rect.origin();
+
+        let visual_row = ((position.y() / view.line_height(font_cache)) + scroll_position.y()) as u32;
+        let row = view.display_row_for_visual_row(visual_row, app);
+
+        // as linhas de um bloco não pertencem à linha abaixo dele
+        if view.visual_row(row, app) != visual_row {
+            return None;
+        }
+
+        let x = position.x() + scroll_position.x() * view.em_width(font_cache);
+
+        layout
+            .conflict_actions
+            .iter()
+            .find(|action| action.row == row && action.x.start <= x && x < action.x.end)
+    }
 }
 
 struct Cursor {
     x: f32,
     y: f32,
 
-    line_height: f32
+    line_height: f32,
+    color: ColorU
 }
 
 impl Cursor {
     fn paint(&self, scene: &mut Scene) {
-        // scene.set_fill_style(FillStyle::Color(ColorU::black()));
+        // scene.set_fill_style(FillStyle::Color(self.color));
         //
         // scene.fill_rect(RectF::new(
         //     vec2f(self.x, self.y),
@@ -662,7 +896,8 @@ struct Selection {
     start_y: f32,
 
     line_height: f32,
-    lines: Vec<SelectionLine>
+    lines: Vec<SelectionLine>,
+    color: ColorU
 }
 
 #[derive(Debug)]
@@ -756,9 +991,7 @@ impl Selection {
         // rounded_corner(&mut path, corner, corner_radius, Up, Right);
         // path.close_path();
         //
-        // scene.set_fill_style(FillStyle::Color(
-        //     ColorF::new(0.639, 0.839, 1.0, 1.0).to_u8()
-        // ));
+        // scene.set_fill_style(FillStyle::Color(self.color));
         //
         // scene.fill_path(path, FillRule::Winding);
     }
@@ -1,14 +1,26 @@
 use super::{
-    buffer, movement, Anchor, Bias, Buffer, BufferElement, DisplayMap, DisplayPoint, Point,
-    ToOffset, ToPoint
+    buffer, movement, syntax, Anchor, Bias, Buffer, BufferElement, ConflictResolution, DiffHunk,
+    DiffHunkKind, DisplayMap, DisplayPoint, Point, ToOffset, ToPoint
 };
 
-use crate::{settings::Settings, watch, workspace};
+use crate::{
+    editor_config,
+    settings::{self, EditorSettings, EditorSettingsOverrides, Settings},
+    theme::{Theme, TokenStyle},
+    util, watch,
+    workspace::{self, session::ItemState, NavigationPosition},
+    worktree::{self, GitStatus, Worktree}
+};
 use anyhow::Result;
 use easy_parallel::Parallel;
 
 use gpui::{
-    fonts::{FontCache, Properties as FontProperties},
+    color::ColorU,
+    fonts::{
+        FamilyId, FontCache, FontId, Properties as FontProperties, Style as FontStyle,
+        Weight as FontWeight
+    },
+
     keymap::Binding,
     text_layout, App, AppContext, Element, Entity, ModelHandle, View, ViewContext, WeakViewHandle
 };
@@ -33,6 +45,7 @@ pub fn init(app: &mut App) {
     app.add_bindings(vec![
         Binding::new("backspace", "buffer:backspace", Some("BufferView")),
         Binding::new("enter", "buffer:newline", Some("BufferView")),
+        Binding::new("tab", "buffer:indent", Some("BufferView")),
         Binding::new("up", "buffer:move_up", Some("BufferView")),
         Binding::new("down", "buffer:move_down", Some("BufferView")),
         Binding::new("left", "buffer:move_left", Some("BufferView")),
@@ -41,17 +54,32 @@ pub fn init(app: &mut App) {
         Binding::new("shift-down", "buffer:select_down", Some("BufferView")),
         Binding::new("shift-left", "buffer:select_left", Some("BufferView")),
         Binding::new("shift-right", "buffer:select_right", Some("BufferView")),
+        Binding::new("alt-shift-up", "buffer:column_select_up", Some("BufferView")),
+        Binding::new("alt-shift-down", "buffer:column_select_down", Some("BufferView")),
+        Binding::new("alt-shift-left", "buffer:column_select_left", Some("BufferView")),
+        Binding::new("alt-shift-right", "buffer:column_select_right", Some("BufferView")),
         Binding::new("pageup", "buffer:page_up", Some("BufferView")),
         Binding::new("pagedown", "buffer:page_down", Some("BufferView")),
         Binding::new("alt-cmd-[", "buffer:fold", Some("BufferView")),
         Binding::new("alt-cmd-]", "buffer:unfold", Some("BufferView")),
-        Binding::new("alt-cmd-f", "buffer:fold_selected_ranges", Some("BufferView"))
+        Binding::new("alt-cmd-f", "buffer:fold_selected_ranges", Some("BufferView")),
+        Binding::new("alt-cmd-down", "buffer:next_diff_hunk", Some("BufferView")),
+        Binding::new("alt-cmd-up", "buffer:prev_diff_hunk", Some("BufferView")),
+        Binding::new("alt-cmd-d", "buffer:toggle_diff_hunk", Some("BufferView")),
+        Binding::new("alt-cmd-z", "buffer:revert_diff_hunk", Some("BufferView")),
+        Binding::new("ctrl-cmd-down", "buffer:next_conflict", Some("BufferView")),
+        Binding::new("ctrl-cmd-up", "buffer:prev_conflict", Some("BufferView")),
+        Binding::new("cmd-k o", "buffer:accept_ours", Some("BufferView")),
+        Binding::new("cmd-k t", "buffer:accept_theirs", Some("BufferView")),
+        Binding::new("cmd-k b", "buffer:accept_both", Some("BufferView")),
+        Binding::new("cmd-s", "buffer:save", Some("BufferView"))
     ]);
 
     app.add_action("buffer:scroll", BufferView::scroll);
     app.add_action("buffer:select", BufferView::select);
     app.add_action("buffer:insert", BufferView::insert);
     app.add_action("buffer:newline", BufferView::newline);
+    app.add_action("buffer:indent", BufferView::indent);
     app.add_action("buffer:backspace", BufferView::backspace);
     app.add_action("buffer:move_up", BufferView::move_up);
     app.add_action("buffer:move_down", BufferView::move_down);
@@ -61,17 +89,80 @@ pub fn init(app: &mut App) {
     app.add_action("buffer:select_down", BufferView::select_down);
     app.add_action("buffer:select_left", BufferView::select_left);
     app.add_action("buffer:select_right", BufferView::select_right);
+    app.add_action("buffer:column_select_up", BufferView::column_select_up);
+    app.add_action("buffer:column_select_down", BufferView::column_select_down);
+    app.add_action("buffer:column_select_left", BufferView::column_select_left);
+    app.add_action("buffer:column_select_right", BufferView::column_select_right);
     app.add_action("buffer:page_up", BufferView::page_up);
     app.add_action("buffer:page_down", BufferView::page_down);
     app.add_action("buffer:fold", BufferView::fold);
     app.add_action("buffer:unfold", BufferView::unfold);
     app.add_action("buffer:fold_selected_ranges", BufferView::fold_selected_ranges);
+    app.add_action("buffer:next_diff_hunk", BufferView::next_diff_hunk);
+    app.add_action("buffer:prev_diff_hunk", BufferView::prev_diff_hunk);
+    app.add_action("buffer:toggle_diff_hunk", BufferView::toggle_diff_hunk);
+    app.add_action("buffer:revert_diff_hunk", BufferView::revert_diff_hunk);
+    app.add_action("buffer:next_conflict", BufferView::next_conflict);
+    app.add_action("buffer:prev_conflict", BufferView::prev_conflict);
+    app.add_action("buffer:accept_ours", BufferView::accept_ours);
+    app.add_action("buffer:accept_theirs", BufferView::accept_theirs);
+    app.add_action("buffer:accept_both", BufferView::accept_both);
+    app.add_action("buffer:resolve_conflict", BufferView::resolve_conflict);
+    app.add_action("buffer:save", BufferView::save);
+}
+
+/// linhas somente leitura exibidas acima de uma linha do buffer, sem fazer parte do seu texto
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlineBlock {
+    pub row: u32,
+    pub kind: InlineBlockKind,
+    pub lines: Vec<String>
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InlineBlockKind {
+    // linhas vazias que alinham o buffer a outro exibido ao lado
+    Filler,
+
+    // linhas que existiam no texto comparado e foram removidas
+    Removed
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictRegion {
+    // linhas `<<<<<<<`, `|||||||`, `=======` e `>>>>>>>`, além da versão comum do diff3
+    Marker,
+
+    Ours,
+    Theirs
+}
+
+/// botão clicável exibido ao lado da marcação de início de um conflito
+pub struct ConflictAction {
+    pub row: u32,
+
+    // posição horizontal a partir do início do texto, sem contar a rolagem
+    pub x: Range<f32>,
+
+    pub conflict_ix: usize,
+    pub resolution: ConflictResolution,
+
+    pub label: Arc<text_layout::Line>
 }
 
 pub enum SelectAction {
     Begin {
         position: DisplayPoint,
-        add: bool
+        add: bool,
+        click_count: usize
+    },
+
+    Extend {
+        position: DisplayPoint
+    },
+
+    BeginColumnar {
+        position: DisplayPoint
     },
 
     Update {
@@ -99,10 +190,19 @@ pub struct BufferView {
     buffer: ModelHandle<Buffer>,
     display_map: ModelHandle<DisplayMap>,
     selections: Vec<Selection>,
-    pending_selection: Option<Selection>,
+    pending_selection: Option<PendingSelection>,
+    columnar_selection: Option<ColumnarSelection>,
+    selecting_columns: bool,
+    next_selection_id: usize,
     scroll_position: Mutex<Vector2F>,
     autoscroll_requested: Mutex<bool>,
     settings: watch::Receiver<Settings>,
+    editor_config: EditorSettingsOverrides,
+    editor_settings: EditorSettings,
+
+    // linhas do `HEAD` do trecho cujo texto antigo é exibido abaixo dele
+    expanded_diff_hunk: Option<Range<u32>>,
+    inline_blocks: Vec<InlineBlock>,
 
     focused: bool,
     cursors_visible: bool,
@@ -126,18 +226,27 @@ impl BufferView {
         settings: watch::Receiver<Settings>,
         ctx: &mut ViewContext<Self>
     ) -> Self {
-        settings.notify_view_on_change(ctx);
+        ctx.spawn_stream(settings.clone(), Self::on_settings_changed, |_, _| {})
+            .detach();
 
         ctx.observe(&buffer, Self::on_buffer_changed);
         ctx.subscribe_to_model(&buffer, Self::on_buffer_event);
 
-        let display_map = ctx.add_model(|ctx| {
-            DisplayMap::new(
-                buffer.clone(),
-                smol::block_on(settings.read()).tab_size,
+        let editor_config = EditorSettingsOverrides::default();
 
-                ctx
-            )
+        let editor_settings = smol::block_on(settings.read())
+            .editor_settings(buffer.as_ref(ctx).path(ctx).as_deref(), &editor_config);
+
+        // os arquivos `.editorconfig` são lidos em segundo plano e relidos quando algum deles
+        // muda na árvore
+        Self::load_editor_config(&buffer, ctx);
+
+        if let Some(file) = buffer.as_ref(ctx).file() {
+            ctx.subscribe_to_model(file.worktree(), Self::on_worktree_event);
+        }
+
+        let display_map = ctx.add_model(|ctx| {
+            DisplayMap::new(buffer.clone(), editor_settings.indent_size, ctx)
         });
 
         ctx.observe(&display_map, Self::on_display_map_changed);
@@ -150,6 +259,8 @@ impl BufferView {
             display_map,
 
             selections: vec![Selection {
+                id: 0,
+
                 start: buffer_ref.anchor_before(0).unwrap(),
                 end: buffer_ref.anchor_before(0).unwrap(),
 
@@ -158,10 +269,18 @@ impl BufferView {
             }],
 
             pending_selection: None,
+            columnar_selection: None,
+            selecting_columns: false,
+            next_selection_id: 1,
             scroll_position: Mutex::new(Vector2F::zero()),
             autoscroll_requested: Mutex::new(false),
 
             settings,
+            editor_config,
+            editor_settings,
+
+            expanded_diff_hunk: None,
+            inline_blocks: Vec::new(),
 
             focused: false,
             cursors_visible: false,
@@ -180,7 +299,14 @@ impl BufferView {
     }
 
     fn scroll(&mut self, scroll_position: &Vector2F, ctx: &mut ViewContext<Self>) {
-        *self.scroll_position.lock() = *scroll_position;
+        self.set_scroll_position(*scroll_position, ctx);
+
+        ctx.emit(Event::Scrolled);
+    }
+
+    /// rola sem emitir `Event::Scrolled`, para acompanhar a rolagem de outra visão
+    pub fn set_scroll_position(&mut self, scroll_position: Vector2F, ctx: &mut ViewContext<Self>) {
+        *self.scroll_position.lock() = scroll_position;
 
         ctx.notify();
     }
@@ -207,7 +333,7 @@ impl BufferView {
         let mut scroll_position = self.scroll_position.lock();
         let scroll_top = scroll_position.y();
 
-        scroll_position.set_y(scroll_top.min(self.max_point(app).row().saturating_sub(1) as f32));
+        scroll_position.set_y(scroll_top.min(self.visual_row_count(app).saturating_sub(2) as f32));
 
         let mut autoscroll_requested = self.autoscroll_requested.lock();
 
@@ -220,23 +346,29 @@ impl BufferView {
         let map = self.display_map.as_ref(app);
         let visible_lines = viewport_height / line_height;
 
-        let first_cursor_top = self
-            .selections
-            .first()
-            .unwrap()
-            .head()
-            .to_display_point(map, app)
-            .unwrap()
-            .row() as f32;
+        let first_cursor_top = self.visual_row(
+            self.selections
+                .first()
+                .unwrap()
+                .head()
+                .to_display_point(map, app)
+                .unwrap()
+                .row(),
 
-        let last_cursor_bottom = self
-            .selections
-            .last()
-            .unwrap()
-            .head()
-            .to_display_point(map, app)
-            .unwrap()
-            .row() as f32
+            app
+        ) as f32;
+
+        let last_cursor_bottom = self.visual_row(
+            self.selections
+                .last()
+                .unwrap()
+                .head()
+                .to_display_point(map, app)
+                .unwrap()
+                .row(),
+
+            app
+        ) as f32
             + 1.0;
 
         let margin = ((visible_lines - (last_cursor_bottom - first_cursor_top)) / 2.0)
@@ -311,7 +443,17 @@ impl BufferView {
 
     fn select(&mut self, arg: &SelectAction, ctx: &mut ViewContext<Self>) {
         match arg {
-            SelectAction::Begin { position, add } => self.begin_selection(*position, *add, ctx),
+            SelectAction::Begin {
+                position,
+                add,
+                click_count
+            } => self.begin_selection(*position, *add, *click_count, ctx),
+
+            SelectAction::Extend { position } => self.extend_selection(*position, ctx),
+
+            SelectAction::BeginColumnar { position } => {
+                self.begin_columnar_selection(*position, ctx)
+            }
 
             SelectAction::Update {
                 position,
@@ -322,21 +464,44 @@ impl BufferView {
         }
     }
 
-    fn begin_selection(&mut self, position: DisplayPoint, add: bool, ctx: &mut ViewContext<Self>) {
+    fn begin_selection(
+        &mut self,
+
+        position: DisplayPoint,
+        add: bool,
+        click_count: usize,
+        ctx: &mut ViewContext<Self>
+    ) {
         if !self.focused {
             ctx.focus_self();
             ctx.emit(Event::Activate);
         }
 
-        let display_map = self.display_map.as_ref(ctx);
+        let app = ctx.app();
+        let map = self.display_map.as_ref(app);
 
-        let cursor = display_map
-            .anchor_before(position, Bias::Left, ctx.app())
-            .unwrap();
+        let (range, mode) = match click_count {
+            0 | 1 => (position..position, SelectMode::Character),
+
+            2 => {
+                let range = movement::surrounding_word(map, position, app).unwrap();
+
+                (range.clone(), SelectMode::Word(range))
+            }
+
+            _ => {
+                let range = movement::surrounding_line(map, position, app).unwrap();
+
+                (range.clone(), SelectMode::Line(range))
+            }
+        };
 
         let selection = Selection {
-            start: cursor.clone(),
-            end: cursor,
+            id: util::post_inc(&mut self.next_selection_id),
+
+            start: map.anchor_before(range.start, Bias::Left, app).unwrap(),
+            end: map.anchor_before(range.end, Bias::Left, app).unwrap(),
+
             reversed: false,
             goal_column: None
         };
@@ -345,11 +510,65 @@ impl BufferView {
             self.selections.clear();
         }
 
-        self.pending_selection = Some(selection);
+        self.columnar_selection = None;
+        self.selecting_columns = false;
+        self.pending_selection = Some(PendingSelection { selection, mode });
+
+        ctx.notify();
+    }
+
+    fn extend_selection(&mut self, position: DisplayPoint, ctx: &mut ViewContext<Self>) {
+        if !self.focused {
+            ctx.focus_self();
+            ctx.emit(Event::Activate);
+        }
+
+        let newest_ix = self
+            .selections
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, selection)| selection.id)
+            .map(|(ix, _)| ix);
+
+        let mut selection = if let Some(pending) = self.pending_selection.take() {
+            pending.selection
+        } else if let Some(ix) = newest_ix {
+            self.selections.remove(ix)
+        } else {
+            log::error!("extend_selection enviado sem nenhuma seleção");
+
+            return;
+        };
+
+        let app = ctx.app();
+
+        let buffer = self.buffer.as_ref(app);
+        let map = self.display_map.as_ref(app);
+
+        selection.set_head(buffer, map.anchor_before(position, Bias::Left, app).unwrap());
+        selection.goal_column = None;
+
+        self.columnar_selection = None;
+        self.selecting_columns = false;
+        self.pending_selection = Some(PendingSelection {
+            selection,
+            mode: SelectMode::Character
+        });
 
         ctx.notify();
     }
 
+    fn begin_columnar_selection(&mut self, position: DisplayPoint, ctx: &mut ViewContext<Self>) {
+        if !self.focused {
+            ctx.focus_self();
+            ctx.emit(Event::Activate);
+        }
+
+        self.pending_selection = None;
+        self.select_columns(position, position, ctx);
+        self.selecting_columns = true;
+    }
+
     fn update_selection(
         &mut self,
 
@@ -357,13 +576,55 @@ impl BufferView {
         scroll_position: Vector2F,
         ctx: &mut ViewContext<Self>
     ) {
-        let buffer = self.buffer.as_ref(ctx);
-        let map = self.display_map.as_ref(ctx);
+        if self.selecting_columns {
+            if let Some(ColumnarSelection { tail, .. }) = self.columnar_selection {
+                self.select_columns(tail, position, ctx);
+            }
+
+            *self.scroll_position.lock() = scroll_position;
+
+            ctx.notify();
+
+            return;
+        }
+
+        let app = ctx.app();
 
-        let cursor = map.anchor_before(position, Bias::Left, ctx.app()).unwrap();
+        let buffer = self.buffer.as_ref(app);
+        let map = self.display_map.as_ref(app);
+
+        if let Some(PendingSelection { selection, mode }) = self.pending_selection.as_mut() {
+            let original = match mode {
+                SelectMode::Character => None,
+
+                SelectMode::Word(original) => Some((
+                    original.clone(),
+                    movement::surrounding_word(map, position, app).unwrap()
+                )),
+
+                SelectMode::Line(original) => Some((
+                    original.clone(),
+                    movement::surrounding_line(map, position, app).unwrap()
+                ))
+            };
+
+            if let Some((original, head_range)) = original {
+                // mantém a granularidade original, estendendo a seleção até a
+                // palavra ou linha inteira que contém a posição atual
+                let (start, end, reversed) = if head_range.start < original.start {
+                    (head_range.start, original.end, true)
+                } else {
+                    (original.start, cmp::max(head_range.end, original.end), false)
+                };
 
-        if let Some(selection) = self.pending_selection.as_mut() {
-            selection.set_head(buffer, cursor);
+                selection.start = map.anchor_before(start, Bias::Left, app).unwrap();
+                selection.end = map.anchor_before(end, Bias::Left, app).unwrap();
+                selection.reversed = reversed;
+            } else {
+                let cursor = map.anchor_before(position, Bias::Left, app).unwrap();
+
+                selection.set_head(buffer, cursor);
+            }
         } else {
             log::error!("update_selection enviado sem seleção pendente");
 
@@ -376,7 +637,11 @@ impl BufferView {
     }
 
     fn end_selection(&mut self, ctx: &mut ViewContext<Self>) {
-        if let Some(selection) = self.pending_selection.take() {
+        if self.selecting_columns {
+            self.selecting_columns = false;
+
+            ctx.notify();
+        } else if let Some(PendingSelection { selection, .. }) = self.pending_selection.take() {
             let ix = self.selection_insertion_index(&selection.start, ctx.app());
 
             self.selections.insert(ix, selection);
@@ -389,7 +654,138 @@ impl BufferView {
     }
 
     pub fn is_selecting(&self) -> bool {
-        self.pending_selection.is_some()
+        self.pending_selection.is_some() || self.selecting_columns
+    }
+
+    pub fn is_selecting_columns(&self) -> bool {
+        self.selecting_columns
+    }
+
+    fn select_columns(&mut self, tail: DisplayPoint, head: DisplayPoint, ctx: &mut ViewContext<Self>) {
+        {
+            let app = ctx.app();
+            let map = self.display_map.as_ref(app);
+
+            let start_column = cmp::min(tail.column(), head.column());
+            let end_column = cmp::max(tail.column(), head.column());
+            let reversed = head.column() < tail.column();
+
+            let rows = cmp::min(tail.row(), head.row())..=cmp::max(tail.row(), head.row());
+            let mut selections = Vec::new();
+
+            for row in rows {
+                let line_len = map.line_len(row, app).unwrap();
+
+                // linhas mais curtas que a coluna inicial recebem um cursor vazio no final
+                let start = DisplayPoint::new(row, cmp::min(start_column, line_len));
+                let end = DisplayPoint::new(row, cmp::min(end_column, line_len));
+
+                // colunas dentro de uma tabulação se expandem para cobri-la inteira
+                let end_bias = if start == end { Bias::Left } else { Bias::Right };
+
+                selections.push(Selection {
+                    id: util::post_inc(&mut self.next_selection_id),
+
+                    start: map.anchor_before(start, Bias::Left, app).unwrap(),
+                    end: map.anchor_before(end, end_bias, app).unwrap(),
+
+                    reversed: reversed && start != end,
+                    goal_column: Some(head.column())
+                });
+            }
+
+            self.set_selections(selections);
+        }
+
+        self.columnar_selection = Some(ColumnarSelection { tail, head });
+        self.pause_cursor_blinking(ctx);
+
+        ctx.notify();
+    }
+
+    fn columnar_selection_bounds(&self, app: &AppContext) -> (DisplayPoint, DisplayPoint) {
+        if let Some(ColumnarSelection { tail, head }) = self.columnar_selection {
+            return (tail, head);
+        }
+
+        let map = self.display_map.as_ref(app);
+
+        let selection = self
+            .selections
+            .iter()
+            .max_by_key(|selection| selection.id)
+            .unwrap();
+
+        let tail = selection.tail().to_display_point(map, app).unwrap();
+        let mut head = selection.head().to_display_point(map, app).unwrap();
+
+        if let Some(goal_column) = selection.goal_column {
+            *head.column_mut() = goal_column;
+        }
+
+        (tail, head)
+    }
+
+    pub fn column_select_up(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.single_line {
+            ctx.propagate_action();
+        } else {
+            let (tail, mut head) = self.columnar_selection_bounds(ctx.app());
+
+            if head.row() > 0 {
+                *head.row_mut() -= 1;
+            }
+
+            self.select_columns(tail, head, ctx);
+
+            *self.autoscroll_requested.lock() = true;
+        }
+    }
+
+    pub fn column_select_down(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.single_line {
+            ctx.propagate_action();
+        } else {
+            let (tail, mut head) = self.columnar_selection_bounds(ctx.app());
+
+            if head.row() < self.max_point(ctx.app()).row() {
+                *head.row_mut() += 1;
+            }
+
+            self.select_columns(tail, head, ctx);
+
+            *self.autoscroll_requested.lock() = true;
+        }
+    }
+
+    pub fn column_select_left(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let (tail, mut head) = self.columnar_selection_bounds(ctx.app());
+
+        *head.column_mut() = head.column().saturating_sub(1);
+
+        self.select_columns(tail, head, ctx);
+
+        *self.autoscroll_requested.lock() = true;
+    }
+
+    pub fn column_select_right(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let (tail, mut head) = self.columnar_selection_bounds(ctx.app());
+
+        let app = ctx.app();
+        let map = self.display_map.as_ref(app);
+
+        let max_column = (cmp::min(tail.row(), head.row())..=cmp::max(tail.row(), head.row()))
+            .map(|row| map.line_len(row, app).unwrap())
+            .max()
+            .unwrap_or(0);
+
+        if head.column() < max_column {
+            *head.column_mut() += 1;
+        }
+
+        self.select_columns(tail, head, ctx);
+
+        *self.autoscroll_requested.lock() = true;
     }
 
     #[cfg(test)]
@@ -404,6 +800,8 @@ impl BufferView {
 
         for range in ranges {
             selections.push(Selection {
+                id: util::post_inc(&mut self.next_selection_id),
+
                 start: map.anchor_after(range.start, Bias::Left, ctx.app())?,
                 end: map.anchor_before(range.end, Bias::Left, ctx.app())?,
 
@@ -414,7 +812,7 @@ impl BufferView {
 
         selections.sort_unstable_by(|a, b| a.start.cmp(&b.start, buffer).unwrap());
 
-        self.selections = selections;
+        self.set_selections(selections);
         self.merge_selections(ctx.app());
 
         ctx.notify();
@@ -445,10 +843,13 @@ impl BufferView {
 
         let mut delta = 0_isize;
 
-        self.selections = offset_ranges
+        let ids = self.selections.iter().map(|s| s.id).collect::<SmallVec<[usize; 32]>>();
+
+        let selections = offset_ranges
             .into_iter()
+            .zip(ids)
 
-            .map(|range| {
+            .map(|(range, id)| {
                 let start = range.start as isize;
                 let end = range.end as isize;
 
@@ -459,6 +860,8 @@ impl BufferView {
                 delta += char_count - deleted_count;
 
                 Selection {
+                    id,
+
                     start: anchor.clone(),
                     end: anchor,
                     reversed: false,
@@ -466,6 +869,7 @@ impl BufferView {
                 }
             }).collect();
 
+        self.set_selections(selections);
         self.pause_cursor_blinking(ctx);
 
         *self.autoscroll_requested.lock() = true;
@@ -479,6 +883,14 @@ impl BufferView {
         }
     }
 
+    fn indent(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.single_line {
+            ctx.propagate_action();
+        } else {
+            self.insert(&self.editor_settings.indent_text(), ctx);
+        }
+    }
+
     pub fn backspace(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
         self.select_left(&(), ctx);
         self.insert(&String::new(), ctx);
@@ -684,7 +1096,15 @@ impl BufferView {
         }
     }
 
+    /// troca todas as seleções, descartando a seleção em colunas que elas substituem
+    fn set_selections(&mut self, selections: Vec<Selection>) {
+        self.selections = selections;
+        self.columnar_selection = None;
+    }
+
     pub fn changed_selections(&mut self, ctx: &mut ViewContext<Self>) {
+        self.columnar_selection = None;
+
         self.merge_selections(ctx.app());
         self.pause_cursor_blinking(ctx);
 
@@ -693,6 +1113,26 @@ impl BufferView {
         ctx.notify();
     }
 
+    fn current_position(&self) -> NavigationPosition {
+        NavigationPosition {
+            selections: self
+                .selections
+                .iter()
+                .map(|selection| selection.tail().clone()..selection.head().clone())
+                .collect(),
+
+            scroll_position: self.scroll_position()
+        }
+    }
+
+    /// emite `Event::Navigated` com a posição atual antes de um salto explícito. os demais
+    /// movimentos do cursor, mesmo os longos, não entram no histórico de navegação
+    fn record_navigation(&self, ctx: &mut ViewContext<Self>) {
+        if !self.single_line {
+            ctx.emit(Event::Navigated(self.current_position()));
+        }
+    }
+
     fn merge_selections(&mut self, ctx: &AppContext) {
         let buffer = self.buffer.as_ref(ctx);
 
@@ -755,8 +1195,8 @@ impl BufferView {
         let start = map.anchor_before(range.start, Bias::Left, app).unwrap();
         let start_index = self.selection_insertion_index(&start, app);
 
-        let pending_selection = self.pending_selection.as_ref().and_then(|s| {
-            let selection_range = s.display_range(map, app);
+        let pending_selection = self.pending_selection.as_ref().and_then(|pending| {
+            let selection_range = pending.selection.display_range(map, app);
 
             if selection_range.start <= range.end || selection_range.end <= range.end {
                 Some(selection_range)
@@ -952,37 +1392,525 @@ impl BufferView {
         });
     }
 
-    pub fn line(&self, display_row: u32, app: &AppContext) -> Result<String> {
-        self.display_map.as_ref(app).line(display_row, app)
-    }
+    pub fn next_diff_hunk(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.as_ref(ctx);
+        let hunks = buffer.diff_hunks();
 
-    pub fn line_len(&self, display_row: u32, app: &AppContext) -> Result<u32> {
-        self.display_map.as_ref(app).line_len(display_row, app)
-    }
+        if let Some(row) = self.cursor_row(ctx.app()) {
+            let hunk = hunks
+                .iter()
+                .find(|hunk| hunk.rows.start > row)
+                .or_else(|| hunks.first());
 
-    pub fn rightmost_point(&self, app: &AppContext) -> DisplayPoint {
-        self.display_map.as_ref(app).rightmost_point()
+            if let Some(hunk) = hunk {
+                self.move_cursor_to_row(hunk.rows.start, ctx);
+            }
+        }
     }
 
-    pub fn max_point(&self, app: &AppContext) -> DisplayPoint {
-        self.display_map.as_ref(app).max_point(app)
-    }
+    pub fn prev_diff_hunk(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.as_ref(ctx);
+        let hunks = buffer.diff_hunks();
 
-    pub fn text(&self, app: &AppContext) -> String {
-        self.display_map.as_ref(app).text(app)
-    }
+        if let Some(row) = self.cursor_row(ctx.app()) {
+            let hunk = hunks
+                .iter()
+                .rev()
+                .find(|hunk| hunk.rows.start < row)
+                .or_else(|| hunks.last());
 
-    pub fn font_size(&self) -> f32 {
-        smol::block_on(self.settings.read()).buffer_font_size
+            if let Some(hunk) = hunk {
+                self.move_cursor_to_row(hunk.rows.start, ctx);
+            }
+        }
     }
 
-    pub fn font_ascent(&self, font_cache: &FontCache) -> f32 {
-        let settings = smol::block_on(self.settings.read());
-
-        let font_id = font_cache.default_font(settings.buffer_font_family);
-        let ascent = font_cache.metric(font_id, |m| m.ascent);
+    /// mostra ou esconde o texto do `HEAD` do trecho sob o cursor
+    pub fn toggle_diff_hunk(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(hunk) = self.diff_hunk_at_cursor(ctx.app()) {
+            if self.expanded_diff_hunk.as_ref() == Some(&hunk.head_rows) {
+                self.expanded_diff_hunk = None;
+            } else {
+                self.expanded_diff_hunk = Some(hunk.head_rows);
+            }
 
-        font_cache.scale_metric(ascent, font_id, settings.buffer_font_size)
+            ctx.notify();
+        }
+    }
+
+    pub fn revert_diff_hunk(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(hunk) = self.diff_hunk_at_cursor(ctx.app()) {
+            self.buffer.update(ctx, |buffer, ctx| {
+                if let Err(error) = buffer.revert_diff_hunk(&hunk, Some(ctx)) {
+                    log::error!("erro ao reverter o trecho: {}", error);
+                }
+            });
+
+            self.expanded_diff_hunk = None;
+            self.changed_selections(ctx);
+        }
+    }
+
+    pub fn next_conflict(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let rows = self.conflict_start_rows(ctx.app());
+
+        if let Some(row) = self.cursor_row(ctx.app()) {
+            let next_row = rows.iter().find(|start| **start > row).or_else(|| rows.first());
+
+            if let Some(next_row) = next_row {
+                self.move_cursor_to_row(*next_row, ctx);
+            }
+        }
+    }
+
+    pub fn prev_conflict(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let rows = self.conflict_start_rows(ctx.app());
+
+        if let Some(row) = self.cursor_row(ctx.app()) {
+            let prev_row = rows.iter().rev().find(|start| **start < row).or_else(|| rows.last());
+
+            if let Some(prev_row) = prev_row {
+                self.move_cursor_to_row(*prev_row, ctx);
+            }
+        }
+    }
+
+    pub fn accept_ours(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Ours, ctx);
+    }
+
+    pub fn accept_theirs(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Theirs, ctx);
+    }
+
+    pub fn accept_both(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.resolve_conflict_at_cursor(ConflictResolution::Both, ctx);
+    }
+
+    fn resolve_conflict_at_cursor(&mut self, resolution: ConflictResolution, ctx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.conflict_at_cursor(ctx.app()) {
+            self.resolve_conflict(&(ix, resolution), ctx);
+        }
+    }
+
+    /// disparada pelos botões exibidos ao lado de cada conflito
+    pub fn resolve_conflict(
+        &mut self,
+
+        &(ix, resolution): &(usize, ConflictResolution),
+
+        ctx: &mut ViewContext<Self>
+    ) {
+        self.buffer.update(ctx, |buffer, ctx| {
+            if let Err(error) = buffer.resolve_conflict(ix, resolution, Some(ctx)) {
+                log::error!("erro ao resolver o conflito: {}", error);
+            }
+        });
+
+        self.changed_selections(ctx);
+    }
+
+    fn conflict_start_rows(&self, app: &AppContext) -> Vec<u32> {
+        let buffer = self.buffer.as_ref(app);
+
+        buffer
+            .conflicts()
+            .iter()
+            .filter_map(|conflict| Some(conflict.range.start.to_point(buffer).ok()?.row))
+            .collect()
+    }
+
+    fn conflict_at_cursor(&self, app: &AppContext) -> Option<usize> {
+        let buffer = self.buffer.as_ref(app);
+        let cursor = self.selections.last()?.head().to_offset(buffer).ok()?;
+
+        buffer.conflicts().iter().position(|conflict| {
+            let start = conflict.range.start.to_offset(buffer);
+            let end = conflict.range.end.to_offset(buffer);
+
+            match (start, end) {
+                (Ok(start), Ok(end)) => start <= cursor && cursor <= end,
+
+                _ => false
+            }
+        })
+    }
+
+    fn cursor_row(&self, app: &AppContext) -> Option<u32> {
+        let buffer = self.buffer.as_ref(app);
+
+        Some(self.selections.last()?.head().to_point(buffer).ok()?.row)
+    }
+
+    /// salta para o início da linha, como ao ir ao próximo trecho do diff ou conflito
+    fn move_cursor_to_row(&mut self, row: u32, ctx: &mut ViewContext<Self>) {
+        self.record_navigation(ctx);
+
+        let buffer = self.buffer.as_ref(ctx);
+        let row = cmp::min(row, buffer.max_point().row);
+        let cursor = buffer.anchor_before(Point::new(row, 0)).unwrap();
+
+        let id = util::post_inc(&mut self.next_selection_id);
+
+        self.set_selections(vec![Selection {
+            id,
+
+            start: cursor.clone(),
+            end: cursor,
+
+            reversed: false,
+            goal_column: None
+        }]);
+
+        self.changed_selections(ctx);
+    }
+
+    /// trecho que contém a linha do cursor, ou a remoção logo abaixo dela
+    fn diff_hunk_at_cursor(&self, app: &AppContext) -> Option<DiffHunk> {
+        let row = self.cursor_row(app)?;
+
+        self.buffer
+            .as_ref(app)
+            .diff_hunks()
+            .into_iter()
+            .find(|hunk| match hunk.kind() {
+                DiffHunkKind::Deleted => hunk.rows.start == row || hunk.rows.start == row + 1,
+
+                _ => hunk.rows.contains(&row)
+            })
+    }
+
+    fn display_row_for_buffer_row(&self, row: u32, app: &AppContext) -> u32 {
+        let map = self.display_map.as_ref(app);
+
+        if row > self.buffer.as_ref(app).max_point().row {
+            map.max_point(app).row() + 1
+        } else {
+            Point::new(row, 0).to_display_point(map, app).unwrap().row()
+        }
+    }
+
+    /// marcadores do diff com o `HEAD` que aparecem nas linhas de exibição dadas
+    pub fn diff_markers(&self, rows: Range<u32>, app: &AppContext) -> Vec<(Range<u32>, DiffHunkKind)> {
+        self.buffer
+            .as_ref(app)
+            .diff_hunks()
+            .into_iter()
+            .filter_map(|hunk| {
+                let start = self.display_row_for_buffer_row(hunk.rows.start, app);
+
+                let end = if hunk.rows.start == hunk.rows.end {
+                    start
+                } else {
+                    self.display_row_for_buffer_row(hunk.rows.end - 1, app) + 1
+                };
+
+                if start <= rows.end && end >= rows.start {
+                    Some((start..end, hunk.kind()))
+                } else {
+                    None
+                }
+            }).collect()
+    }
+
+    /// regiões dos conflitos de merge que aparecem nas linhas de exibição dadas
+    pub fn conflict_regions(&self, rows: Range<u32>, app: &AppContext) -> Vec<(Range<u32>, ConflictRegion)> {
+        let buffer = self.buffer.as_ref(app);
+        let max_row = buffer.max_point().row;
+
+        let mut regions = Vec::new();
+
+        for conflict in buffer.conflicts() {
+            let row = |anchor: &Anchor| anchor.to_point(buffer).map(|point| point.row);
+
+            let (start, ours_start, ours_end, theirs_start, theirs_end) = match (
+                row(&conflict.range.start),
+                row(&conflict.ours.start),
+                row(&conflict.ours.end),
+                row(&conflict.theirs.start),
+                row(&conflict.theirs.end)
+            ) {
+                (Ok(a), Ok(b), Ok(c), Ok(d), Ok(e)) => (a, b, c, d, e),
+
+                _ => continue
+            };
+
+            let buffer_regions = [
+                (start..ours_start, ConflictRegion::Marker),
+                (ours_start..ours_end, ConflictRegion::Ours),
+                (ours_end..theirs_start, ConflictRegion::Marker),
+                (theirs_start..theirs_end, ConflictRegion::Theirs),
+                (theirs_end..cmp::min(theirs_end + 1, max_row + 1), ConflictRegion::Marker)
+            ];
+
+            for (buffer_rows, region) in buffer_regions.iter().cloned() {
+                if buffer_rows.start >= buffer_rows.end {
+                    continue;
+                }
+
+                let display_rows = self.display_row_for_buffer_row(buffer_rows.start, app)
+                    ..self.display_row_for_buffer_row(buffer_rows.end - 1, app) + 1;
+
+                if display_rows.start < rows.end && display_rows.end > rows.start {
+                    regions.push((display_rows, region));
+                }
+            }
+        }
+
+        regions
+    }
+
+    /// botões de aceitar cada lado, posicionados depois do texto da marcação de início de cada
+    /// conflito visível
+    pub fn layout_conflict_actions(
+        &self,
+
+        rows: Range<u32>,
+
+        font_cache: &FontCache,
+        layout_cache: &TextLayoutCache,
+
+        app: &AppContext
+    ) -> Result<Vec<ConflictAction>> {
+        let buffer = self.buffer.as_ref(app);
+
+        if buffer.conflicts().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // `layout_line` e `em_width` também leem as configurações
+        let (font_size, font_id) = {
+            let settings = smol::block_on(self.settings.read());
+
+            (
+                settings.buffer_font_size,
+                font_cache.select_font(settings.buffer_font_family, &FontProperties::new())?
+            )
+        };
+
+        let em_width = self.em_width(font_cache);
+
+        let mut actions = Vec::new();
+
+        for (conflict_ix, conflict) in buffer.conflicts().iter().enumerate() {
+            let row = self.display_row_for_buffer_row(conflict.range.start.to_point(buffer)?.row, app);
+
+            if !rows.contains(&row) {
+                continue;
+            }
+
+            let mut x = self.layout_line(row, font_cache, layout_cache, app)?.width + 2.0 * em_width;
+
+            let buttons = [
+                ("aceitar atual", ConflictResolution::Ours),
+                ("aceitar recebida", ConflictResolution::Theirs),
+                ("aceitar ambas", ConflictResolution::Both)
+            ];
+
+            for (text, resolution) in buttons.iter() {
+                let label = layout_cache.layout_str(
+                    text,
+                    font_size,
+                    &[(0..text.chars().count(), font_id)],
+                    font_cache
+                );
+
+                let width = label.width;
+
+                actions.push(ConflictAction {
+                    row,
+                    x: x..x + width,
+
+                    conflict_ix,
+                    resolution: *resolution,
+
+                    label
+                });
+
+                x += width + em_width;
+            }
+        }
+
+        Ok(actions)
+    }
+
+    pub fn set_inline_blocks(&mut self, blocks: Vec<InlineBlock>, ctx: &mut ViewContext<Self>) {
+        self.inline_blocks = blocks;
+
+        ctx.notify();
+    }
+
+    /// blocos a exibir, incluindo o texto do `HEAD` do trecho expandido, em ordem de linha e cada
+    /// um com a linha de exibição acima da qual aparece
+    fn display_blocks(&self, app: &AppContext) -> Vec<InlineBlock> {
+        let buffer = self.buffer.as_ref(app);
+
+        let expanded_diff_hunk = self.expanded_diff_hunk.as_ref().and_then(|head_rows| {
+            let hunk = buffer.diff_hunks().into_iter().find(|hunk| &hunk.head_rows == head_rows)?;
+
+            Some(InlineBlock {
+                row: hunk.rows.end,
+                kind: InlineBlockKind::Removed,
+                lines: buffer.head_text_for_hunk(&hunk)?.split('\n').map(Into::into).collect()
+            })
+        });
+
+        let mut blocks = self
+            .inline_blocks
+            .iter()
+            .chain(expanded_diff_hunk.as_ref())
+            .map(|block| InlineBlock {
+                row: self.display_row_for_buffer_row(block.row, app),
+                kind: block.kind,
+                lines: block.lines.clone()
+            }).collect::<Vec<_>>();
+
+        blocks.sort_by_key(|block| block.row);
+        blocks
+    }
+
+    /// linha visual de uma linha de exibição, contando as linhas dos blocos exibidos acima dela.
+    /// a rolagem vertical é medida em linhas visuais
+    pub fn visual_row(&self, display_row: u32, app: &AppContext) -> u32 {
+        let block_len = self
+            .display_blocks(app)
+            .iter()
+            .take_while(|block| block.row <= display_row)
+            .map(|block| block.lines.len() as u32)
+            .sum::<u32>();
+
+        display_row + block_len
+    }
+
+    /// inversa de `visual_row`, levando uma linha no meio de um bloco à linha abaixo dele, ou à
+    /// última linha quando o bloco fica no fim do buffer
+    pub fn display_row_for_visual_row(&self, visual_row: u32, app: &AppContext) -> u32 {
+        let max_row = self.max_point(app).row();
+        let mut block_len = 0;
+
+        for block in self.display_blocks(app) {
+            let start = block.row + block_len;
+
+            if visual_row < start {
+                break;
+            }
+
+            if visual_row < start + block.lines.len() as u32 {
+                return cmp::min(block.row, max_row);
+            }
+
+            block_len += block.lines.len() as u32;
+        }
+
+        cmp::min(visual_row - block_len, max_row)
+    }
+
+    /// quantidade de linhas visuais, com as linhas dos blocos
+    pub fn visual_row_count(&self, app: &AppContext) -> u32 {
+        let block_len = self
+            .display_blocks(app)
+            .iter()
+            .map(|block| block.lines.len() as u32)
+            .sum::<u32>();
+
+        self.max_point(app).row() + 1 + block_len
+    }
+
+    /// blocos a exibir, cada um com a linha visual em que começa
+    pub fn layout_inline_blocks(
+        &self,
+
+        font_cache: &FontCache,
+        layout_cache: &TextLayoutCache,
+
+        app: &AppContext
+    ) -> Result<Vec<(u32, InlineBlockKind, Vec<Arc<text_layout::Line>>)>> {
+        let blocks = self.display_blocks(app);
+
+        if blocks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let settings = smol::block_on(self.settings.read());
+        let font_id = font_cache.select_font(settings.buffer_font_family, &FontProperties::new())?;
+
+        let mut block_len = 0;
+
+        Ok(blocks
+            .into_iter()
+            .map(|block| {
+                let lines = block
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        layout_cache.layout_str(
+                            line,
+                            settings.buffer_font_size,
+                            &[(0..line.chars().count(), font_id)],
+                            font_cache
+                        )
+                    }).collect::<Vec<_>>();
+
+                let start = block.row + block_len;
+
+                block_len += lines.len() as u32;
+
+                (start, block.kind, lines)
+            }).collect())
+    }
+
+    pub fn line(&self, display_row: u32, app: &AppContext) -> Result<String> {
+        self.display_map.as_ref(app).line(display_row, app)
+    }
+
+    pub fn line_len(&self, display_row: u32, app: &AppContext) -> Result<u32> {
+        self.display_map.as_ref(app).line_len(display_row, app)
+    }
+
+    pub fn rightmost_point(&self, app: &AppContext) -> DisplayPoint {
+        self.display_map.as_ref(app).rightmost_point()
+    }
+
+    pub fn max_point(&self, app: &AppContext) -> DisplayPoint {
+        self.display_map.as_ref(app).max_point(app)
+    }
+
+    pub fn text(&self, app: &AppContext) -> String {
+        self.display_map.as_ref(app).text(app)
+    }
+
+    /// substitui todo o texto, deixando o cursor no final
+    pub fn set_text(&mut self, text: &str, ctx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.as_ref(ctx);
+
+        let id = util::post_inc(&mut self.next_selection_id);
+
+        self.set_selections(vec![Selection {
+            id,
+
+            start: buffer.anchor_before(0).unwrap(),
+            end: buffer.anchor_before(buffer.len()).unwrap(),
+
+            reversed: false,
+            goal_column: None
+        }]);
+
+        self.insert(&text.into(), ctx);
+
+        ctx.notify();
+    }
+
+    pub fn font_size(&self) -> f32 {
+        smol::block_on(self.settings.read()).buffer_font_size
+    }
+
+    pub fn font_ascent(&self, font_cache: &FontCache) -> f32 {
+        let settings = smol::block_on(self.settings.read());
+
+        let font_id = font_cache.default_font(settings.buffer_font_family);
+        let ascent = font_cache.metric(font_id, |m| m.ascent);
+
+        font_cache.scale_metric(ascent, font_id, settings.buffer_font_size)
     }
 
     pub fn font_descent(&self, font_cache: &FontCache) -> f32 {
@@ -1056,7 +1984,7 @@ impl BufferView {
         let font_size = settings.buffer_font_size;
         let font_id = font_cache.select_font(settings.buffer_font_family, &FontProperties::new())?;
 
-        let start_row = self.scroll_position().y() as usize;
+        let start_row = self.display_row_for_visual_row(self.scroll_position().y() as u32, app) as usize;
 
         let end_row = cmp::min(
             self.max_point(app).row() as usize,
@@ -1121,9 +2049,12 @@ impl BufferView {
 
         let settings = smol::block_on(self.settings.read());
 
-        let font_id = font_cache.select_font(settings.buffer_font_family, &FontProperties::new())?;
+        let fonts = select_fonts(font_cache, settings.buffer_font_family)?;
         let font_size = settings.buffer_font_size;
 
+        let theme = settings.theme.clone();
+        let language = self.language(app);
+
         let cpus = num_cpus::get();
         let chunk_size = (rows.len() + cpus - 1) / cpus;
 
@@ -1141,7 +2072,6 @@ impl BufferView {
                     let mut row = chunk_start;
 
                     let mut line = String::new();
-                    let mut line_len = 0;
 
                     let chars = display_map
                         .chars_at(DisplayPoint::new(chunk_start as u32, 0), app)
@@ -1149,15 +2079,16 @@ impl BufferView {
 
                     for char in chars.chain(Some('\n')) {
                         if char == '\n' {
+                            let styles = syntax::styles(&line, language, &theme);
+
                             chunk[(row - chunk_start) as usize] = layout_cache.layout_str(
                                 &line,
                                 font_size,
-                                &[(0..line_len, font_id)],
+                                &font_runs(&styles, &fonts),
                                 font_cache,
                             );
 
                             line.clear();
-                            line_len = 0;
 
                             row += 1;
 
@@ -1165,8 +2096,6 @@ impl BufferView {
                                 break;
                             }
                         } else {
-                            line_len += 1;
-
                             line.push(char);
                         }
                     }
@@ -1187,18 +2116,46 @@ impl BufferView {
         app: &AppContext
     ) -> Result<Arc<text_layout::Line>> {
         let settings = smol::block_on(self.settings.read());
-        let font_id = font_cache.select_font(settings.buffer_font_family, &FontProperties::new())?;
+        let fonts = select_fonts(font_cache, settings.buffer_font_family)?;
 
         let line = self.line(row, app)?;
+        let styles = syntax::styles(&line, self.language(app), &settings.theme);
 
         Ok(layout_cache.layout_str(
             &line,
             settings.buffer_font_size,
-            &[(0..self.line_len(row, app)? as usize, font_id)],
+            &font_runs(&styles, &fonts),
             font_cache
         ))
     }
 
+    /// cores do texto das linhas de exibição em `rows`, segundo o realce de sintaxe do tema atual
+    pub fn line_colors(&self, mut rows: Range<u32>, app: &AppContext) -> Result<Vec<Vec<(Range<usize>, ColorU)>>> {
+        rows.end = cmp::min(rows.end, self.max_point(app).row() + 1);
+
+        let theme = self.theme();
+        let language = self.language(app);
+
+        rows.map(|row| {
+            let line = self.line(row, app)?;
+
+            Ok(syntax::styles(&line, language, &theme)
+                .into_iter()
+                .map(|(range, style)| (range, style.color))
+                .collect())
+        })
+        .collect()
+    }
+
+    /// linguagem do arquivo do buffer, que escolhe as regras do realce de sintaxe
+    fn language(&self, app: &AppContext) -> Option<&'static str> {
+        self.buffer
+            .as_ref(app)
+            .path(app)
+            .as_deref()
+            .and_then(settings::language_name)
+    }
+
     fn next_blink_epoch(&mut self) -> usize {
         self.blink_epoch += 1;
 
@@ -1255,11 +2212,95 @@ impl BufferView {
         self.cursors_visible
     }
 
-    fn on_buffer_changed(&mut self, _: ModelHandle<Buffer>, ctx: &mut ViewContext<Self>) {
-        ctx.notify();
+    pub fn theme(&self) -> Arc<Theme> {
+        smol::block_on(self.settings.read()).theme.clone()
     }
 
-    fn on_display_map_changed(&mut self, _: ModelHandle<DisplayMap>, ctx: &mut ViewContext<Self>) {
+    /// configurações efetivas do editor para este buffer
+    pub fn editor_settings(&self) -> &EditorSettings {
+        &self.editor_settings
+    }
+
+    pub fn save(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let settings = self.editor_settings.clone();
+
+        self.buffer.update(ctx, |buffer, ctx| {
+            if let Err(error) = buffer.save(&settings, ctx) {
+                log::error!("erro ao gravar o buffer: {}", error);
+            }
+        });
+    }
+
+    fn on_settings_changed(&mut self, _: (), ctx: &mut ViewContext<Self>) {
+        self.update_editor_settings(ctx);
+
+        ctx.notify();
+    }
+
+    fn load_editor_config(buffer: &ModelHandle<Buffer>, ctx: &mut ViewContext<Self>) {
+        if let Some(abs_path) = buffer.as_ref(ctx).abs_path(ctx) {
+            let task = ctx
+                .background_executor()
+                .spawn(async move { editor_config::load(&abs_path) });
+
+            ctx.spawn(task, Self::on_editor_config_loaded).detach();
+        }
+    }
+
+    fn on_worktree_event(
+        &mut self,
+
+        worktree: ModelHandle<Worktree>,
+        event: &worktree::Event,
+        ctx: &mut ViewContext<Self>
+    ) {
+        let changed = match event {
+            // o caminho antigo de uma entrada movida não é conhecido, então sempre relê
+            worktree::Event::Moved(_) => true,
+
+            worktree::Event::Created(entry_id)
+            | worktree::Event::Modified(entry_id)
+            | worktree::Event::Removed(entry_id) => worktree
+                .as_ref(ctx)
+                .entry_path(*entry_id)
+                .map_or(false, |path| editor_config::is_editor_config(&path)),
+
+            worktree::Event::GitStatusChanged => false
+        };
+
+        if changed {
+            Self::load_editor_config(&self.buffer, ctx);
+        }
+    }
+
+    fn on_editor_config_loaded(
+        &mut self,
+
+        editor_config: EditorSettingsOverrides,
+        ctx: &mut ViewContext<Self>
+    ) {
+        self.editor_config = editor_config;
+        self.update_editor_settings(ctx);
+    }
+
+    fn update_editor_settings(&mut self, ctx: &mut ViewContext<Self>) {
+        let path = self.buffer.as_ref(ctx).path(ctx);
+
+        let editor_settings = smol::block_on(self.settings.read())
+            .editor_settings(path.as_deref(), &self.editor_config);
+
+        let indent_size = editor_settings.indent_size;
+
+        self.display_map.update(ctx, |map, ctx| map.set_tab_size(indent_size, ctx));
+
+        self.editor_settings = editor_settings;
+    }
+
+    fn on_buffer_changed(&mut self, _: ModelHandle<Buffer>, ctx: &mut ViewContext<Self>) {
+        ctx.notify();
+    }
+
+    fn on_display_map_changed(&mut self, _: ModelHandle<DisplayMap>, ctx: &mut ViewContext<Self>) {
         ctx.notify();
     }
 
@@ -1271,12 +2312,15 @@ impl BufferView {
         ctx: &mut ViewContext<Self>
     ) {
         match event {
-            buffer::Event::Edited(_) => ctx.emit(Event::Edited)
+            buffer::Event::Edited(_) => ctx.emit(Event::Edited),
+            buffer::Event::HeadChanged => ctx.notify()
         }
     }
 }
 
 struct Selection {
+    id: usize,
+
     start: Anchor,
     end: Anchor,
 
@@ -1284,10 +2328,32 @@ struct Selection {
     goal_column: Option<u32>
 }
 
+struct PendingSelection {
+    selection: Selection,
+    mode: SelectMode
+}
+
+#[derive(Clone, Copy)]
+struct ColumnarSelection {
+    tail: DisplayPoint,
+    head: DisplayPoint
+}
+
+enum SelectMode {
+    Character,
+
+    Word(Range<DisplayPoint>),
+    Line(Range<DisplayPoint>)
+}
+
 pub enum Event {
     Activate,
     Edited,
-    Blurred
+    Blurred,
+    Scrolled,
+
+    // o cursor saltou para longe da posição anterior
+    Navigated(NavigationPosition)
 }
 
 impl Entity for BufferView {
@@ -1327,6 +2393,14 @@ impl workspace::ItemView for BufferView {
         }
     }
 
+    fn is_edit_event(event: &Self::Event) -> bool {
+        match event {
+            Event::Edited => true,
+
+            _ => false
+        }
+    }
+
     fn title(&self, app: &AppContext) -> std::string::String {
         if let Some(path) = self.buffer.as_ref(app).path(app) {
             path.file_name()
@@ -1342,6 +2416,10 @@ impl workspace::ItemView for BufferView {
         self.buffer.as_ref(app).entry_id()
     }
 
+    fn git_status(&self, app: &AppContext) -> Option<GitStatus> {
+        self.buffer.as_ref(app).git_status(app)
+    }
+
     fn clone_on_split(&self, ctx: &mut ViewContext<Self>) -> Option<Self>
     where
         Self: Sized
@@ -1352,6 +2430,121 @@ impl workspace::ItemView for BufferView {
 
         Some(clone)
     }
+
+    fn state(&self, app: &AppContext) -> Option<ItemState> {
+        let buffer = self.buffer.as_ref(app);
+
+        Some(ItemState {
+            path: buffer.abs_path(app)?,
+            selections: self.selections.iter().map(|selection| selection.range(buffer)).collect(),
+            folds: self.display_map.as_ref(app).folds(app),
+            scroll_position: self.scroll_position()
+        })
+    }
+
+    fn restore_state(&mut self, state: &ItemState, ctx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.as_ref(ctx);
+
+        // o arquivo pode ter mudado desde que a sessão foi salva
+        let max_point = buffer.max_point();
+
+        let clip = |point: Point| {
+            let row = cmp::min(point.row, max_point.row);
+
+            Point::new(row, cmp::min(point.column, buffer.line_len(row).unwrap()))
+        };
+
+        let folds = state
+            .folds
+            .iter()
+            .map(|fold| clip(fold.start)..clip(fold.end))
+            .filter(|fold| fold.start < fold.end)
+            .collect::<Vec<_>>();
+
+        let mut selections = Vec::new();
+
+        for range in &state.selections {
+            let (tail, head) = (clip(range.start), clip(range.end));
+            let reversed = head < tail;
+
+            selections.push(Selection {
+                id: util::post_inc(&mut self.next_selection_id),
+
+                start: buffer.anchor_before(cmp::min(tail, head)).unwrap(),
+                end: buffer.anchor_before(cmp::max(tail, head)).unwrap(),
+
+                reversed,
+                goal_column: None
+            });
+        }
+
+        if !selections.is_empty() {
+            self.set_selections(selections);
+        }
+
+        if !folds.is_empty() {
+            self.display_map.update(ctx, |map, ctx| {
+                if let Err(error) = map.fold(folds, ctx) {
+                    log::error!("erro ao restaurar dobras: {}", error);
+                }
+            });
+        }
+
+        *self.scroll_position.lock() = state.scroll_position;
+
+        ctx.notify();
+    }
+
+    fn navigation_position(&self, _: &AppContext) -> Option<NavigationPosition> {
+        Some(self.current_position())
+    }
+
+    fn navigation_event(event: &Self::Event) -> Option<&NavigationPosition> {
+        match event {
+            Event::Navigated(position) => Some(position),
+
+            _ => None
+        }
+    }
+
+    fn navigate(&mut self, position: &NavigationPosition, ctx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.as_ref(ctx);
+        let mut selections = Vec::new();
+
+        for range in &position.selections {
+            let (tail, head) = match (range.start.to_point(buffer), range.end.to_point(buffer)) {
+                (Ok(tail), Ok(head)) => (tail, head),
+
+                _ => continue
+            };
+
+            let reversed = head < tail;
+
+            let (start, end) = if reversed {
+                (range.end.clone(), range.start.clone())
+            } else {
+                (range.start.clone(), range.end.clone())
+            };
+
+            selections.push(Selection {
+                id: util::post_inc(&mut self.next_selection_id),
+
+                start,
+                end,
+
+                reversed,
+                goal_column: None
+            });
+        }
+
+        // as âncoras mantêm a ordem entre si mesmo depois de edições
+        if !selections.is_empty() {
+            self.set_selections(selections);
+            self.merge_selections(ctx.app());
+        }
+
+        self.set_scroll_position(position.scroll_position, ctx);
+    }
 }
 
 impl Selection {
@@ -1414,6 +2607,25 @@ impl Selection {
     }
 }
 
+/// fontes da família do buffer indexadas por `[negrito][itálico]`
+fn select_fonts(font_cache: &FontCache, family_id: FamilyId) -> Result<[[FontId; 2]; 2]> {
+    let select = |weight, style| {
+        font_cache.select_font(family_id, &FontProperties::new().weight(weight).style(style))
+    };
+
+    Ok([
+        [select(FontWeight::NORMAL, FontStyle::Normal)?, select(FontWeight::NORMAL, FontStyle::Italic)?],
+        [select(FontWeight::BOLD, FontStyle::Normal)?, select(FontWeight::BOLD, FontStyle::Italic)?]
+    ])
+}
+
+fn font_runs(styles: &[(Range<usize>, TokenStyle)], fonts: &[[FontId; 2]; 2]) -> Vec<(Range<usize>, FontId)> {
+    styles
+        .iter()
+        .map(|(range, style)| (range.clone(), fonts[style.bold as usize][style.italic as usize]))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1433,7 +2645,7 @@ mod tests {
             let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer, settings, ctx));
 
             buffer_view.update(&mut app, |view, ctx| {
-                view.begin_selection(DisplayPoint::new(2, 2), false, ctx);
+                view.begin_selection(DisplayPoint::new(2, 2), false, 1, ctx);
             });
 
             buffer_view.read(&app, |view, app| {
@@ -1499,7 +2711,7 @@ mod tests {
             });
 
             buffer_view.update(&mut app, |view, ctx| {
-                view.begin_selection(DisplayPoint::new(3, 3), true, ctx);
+                view.begin_selection(DisplayPoint::new(3, 3), true, 1, ctx);
                 view.update_selection(DisplayPoint::new(0, 0), Vector2F::zero(), ctx);
             });
 
@@ -1536,6 +2748,287 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_selection_with_mouse_granularity() {
+        App::test((), |mut app| async move {
+            let buffer = app.add_model(|_| Buffer::new(0, "use std::io;\nfn main() {}\nlet x = 1;\n"));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+
+            let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer, settings, ctx));
+
+            // clique duplo seleciona a palavra inteira
+            buffer_view.update(&mut app, |view, ctx| {
+                view.begin_selection(DisplayPoint::new(0, 5), false, 2, ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [DisplayPoint::new(0, 4)..DisplayPoint::new(0, 7)]
+                );
+            });
+
+            // arrastar mantém a granularidade de palavra nas duas direções
+            buffer_view.update(&mut app, |view, ctx| {
+                view.update_selection(DisplayPoint::new(1, 4), Vector2F::zero(), ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [DisplayPoint::new(0, 4)..DisplayPoint::new(1, 7)]
+                );
+            });
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.update_selection(DisplayPoint::new(0, 1), Vector2F::zero(), ctx);
+                view.end_selection(ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [DisplayPoint::new(0, 7)..DisplayPoint::new(0, 0)]
+                );
+            });
+
+            // clique triplo seleciona a linha inteira
+            buffer_view.update(&mut app, |view, ctx| {
+                view.begin_selection(DisplayPoint::new(1, 2), false, 3, ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [DisplayPoint::new(1, 0)..DisplayPoint::new(2, 0)]
+                );
+            });
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.update_selection(DisplayPoint::new(2, 3), Vector2F::zero(), ctx);
+                view.end_selection(ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [DisplayPoint::new(1, 0)..DisplayPoint::new(3, 0)]
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_extend_selection_with_shift_click() {
+        App::test((), |mut app| async move {
+            let buffer = app.add_model(|_| Buffer::new(0, "aaaaaa\nbbbbbb\ncccccc\ndddddd\n"));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+
+            let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer, settings, ctx));
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.begin_selection(DisplayPoint::new(0, 1), false, 1, ctx);
+                view.end_selection(ctx);
+
+                view.begin_selection(DisplayPoint::new(2, 2), true, 1, ctx);
+                view.end_selection(ctx);
+
+                // estende apenas a seleção mais recente
+                view.extend_selection(DisplayPoint::new(3, 4), ctx);
+                view.end_selection(ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [
+                        DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
+                        DisplayPoint::new(2, 2)..DisplayPoint::new(3, 4)
+                    ]
+                );
+            });
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.extend_selection(DisplayPoint::new(1, 0), ctx);
+                view.end_selection(ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [
+                        DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
+                        DisplayPoint::new(2, 2)..DisplayPoint::new(1, 0)
+                    ]
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_columnar_selection() {
+        App::test((), |mut app| async move {
+            let buffer = app.add_model(|_| Buffer::new(0, "abcdef\nab\n\tabcd\nabcdef"));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+
+            let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer.clone(), settings, ctx));
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.begin_columnar_selection(DisplayPoint::new(0, 1), ctx);
+                view.update_selection(DisplayPoint::new(3, 3), Vector2F::zero(), ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [
+                        DisplayPoint::new(0, 1)..DisplayPoint::new(0, 3),
+                        DisplayPoint::new(1, 1)..DisplayPoint::new(1, 2),
+                        DisplayPoint::new(2, 0)..DisplayPoint::new(2, 4),
+                        DisplayPoint::new(3, 1)..DisplayPoint::new(3, 3)
+                    ]
+                );
+            });
+
+            buffer_view.update(&mut app, |view, ctx| {
+                view.end_selection(ctx);
+                view.insert(&"x".into(), ctx);
+            });
+
+            buffer.read(&app, |buffer, _| {
+                assert_eq!(buffer.text(), "axdef\nax\nxabcd\naxdef");
+            });
+
+            // seleção em coluna pelo teclado a partir de um cursor
+            buffer_view.update(&mut app, |view, ctx| {
+                view.select_ranges(vec![DisplayPoint::new(0, 0)..DisplayPoint::new(0, 0)], ctx).unwrap();
+
+                view.column_select_down(&(), ctx);
+                view.column_select_right(&(), ctx);
+                view.column_select_right(&(), ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(
+                    view.selections(app),
+
+                    [
+                        DisplayPoint::new(0, 0)..DisplayPoint::new(0, 2),
+                        DisplayPoint::new(1, 0)..DisplayPoint::new(1, 2)
+                    ]
+                );
+            });
+
+            // seleções trocadas por outro caminho não retomam a seleção em coluna anterior
+            buffer_view.update(&mut app, |view, ctx| {
+                view.select_ranges(vec![DisplayPoint::new(3, 1)..DisplayPoint::new(3, 1)], ctx).unwrap();
+
+                view.column_select_right(&(), ctx);
+            });
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(view.selections(app), [DisplayPoint::new(3, 1)..DisplayPoint::new(3, 2)]);
+            });
+        });
+    }
+
+    #[test]
+    fn test_indent_with_settings_changes() {
+        App::test((), |mut app| async move {
+            let buffer = app.add_model(|_| Buffer::new(0, "a"));
+
+            let (mut settings_tx, settings) = settings::channel(&FontCache::new()).unwrap();
+
+            let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer.clone(), settings, ctx));
+
+            buffer_view.update(&mut app, |view, ctx| view.indent(&(), ctx));
+
+            buffer.read(&app, |buffer, _| assert_eq!(buffer.text(), "    a"));
+
+            settings_tx
+                .update(|settings| {
+                    settings.editor.indent_style = settings::IndentStyle::Tab;
+                    settings.editor.indent_size = 2;
+                }).await;
+
+            // descartar o sender encerra o stream depois que a atualização é entregue
+            drop(settings_tx);
+
+            app.finish_pending_tasks().await;
+
+            buffer_view.update(&mut app, |view, ctx| view.indent(&(), ctx));
+
+            buffer.read(&app, |buffer, _| assert_eq!(buffer.text(), "    \ta"));
+
+            buffer_view.read(&app, |view, app| {
+                assert_eq!(view.editor_settings().indent_size, 2);
+                assert_eq!(view.text(app), "      a");
+            });
+        });
+    }
+
+    #[test]
+    fn test_line_colors_follow_theme() {
+        use crate::{
+            test::temp_tree,
+            worktree::WorktreeHandle
+        };
+
+        use serde_json::json;
+
+        let dir = temp_tree(json!({ "main.rs": "fn main() {}" }));
+
+        App::test((), |mut app| async move {
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), dir.path(), Some(ctx)));
+
+            app.finish_pending_tasks().await;
+
+            let (file, history) = tree.read(&app, |worktree, app| {
+                let entry_id = worktree.entry_for_path(&dir.path().join("main.rs")).unwrap();
+
+                (tree.file(entry_id, app).unwrap(), worktree.load_history(entry_id))
+            });
+
+            let history = history.await.unwrap();
+            let buffer = app.add_model(|_| Buffer::from_history(0, file, history));
+
+            let (mut settings_tx, settings) = settings::channel(&FontCache::new()).unwrap();
+            let (_, buffer_view) = app.add_window(|ctx| BufferView::for_buffer(buffer, settings, ctx));
+
+            let keyword_color = |theme: &Theme| theme.token_style("keyword").unwrap().color;
+
+            buffer_view.read(&app, |view, app| {
+                let colors = view.line_colors(0..1, app).unwrap();
+
+                assert_eq!(colors[0][0], (0..2, keyword_color(&view.theme())));
+                assert_eq!(colors[0][1], (2..3, view.theme().ui.editor_text));
+                assert_eq!(colors[0][2].0, 3..7);
+            });
+
+            settings_tx
+                .update(|settings| settings.theme = Arc::new(Theme::dark()))
+                .await;
+
+            buffer_view.read(&app, |view, app| {
+                let colors = view.line_colors(0..1, app).unwrap();
+
+                assert_eq!(colors[0][0], (0..2, keyword_color(&Theme::dark())));
+                assert_eq!(colors[0][2].1, Theme::dark().token_style("function").unwrap().color);
+            });
+        });
+    }
+
     #[test]
     fn test_layout_line_numbers() -> Result<()> {
         use gpui::{fonts::FontCache, text_layout::TextLayoutCache};
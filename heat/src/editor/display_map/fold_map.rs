@@ -1,5 +1,6 @@
 use super::{
-    buffer, Anchor, AnchorRangeExt, Buffer, DisplayPoint, Edit, Point, TextSummary, ToOffset
+    buffer, Anchor, AnchorRangeExt, Buffer, DisplayPoint, Edit, Point, TextSummary, ToOffset,
+    ToPoint
 };
 
 use crate::{
@@ -175,6 +176,16 @@ impl FoldMap {
         Ok(())
     }
 
+    /// intervalos dobrados, em ordem, como pontos do buffer
+    pub fn folds(&self, app: &AppContext) -> Vec<Range<Point>> {
+        let buffer = self.buffer.as_ref(app);
+
+        self.folds
+            .iter()
+            .map(|fold| fold.start.to_point(buffer).unwrap()..fold.end.to_point(buffer).unwrap())
+            .collect()
+    }
+
     pub fn is_line_folded(&self, display_row: u32) -> bool {
         let mut cursor = self.transforms.cursor::<DisplayPoint, DisplayPoint>();
         
@@ -35,6 +35,14 @@ impl DisplayMap {
         }
     }
 
+    pub fn set_tab_size(&mut self, tab_size: usize, ctx: &mut ModelContext<Self>) {
+        if tab_size != self.tab_size {
+            self.tab_size = tab_size;
+
+            ctx.notify();
+        }
+    }
+
     pub fn fold<T: ToOffset>(
         &mut self,
 
@@ -61,6 +69,10 @@ impl DisplayMap {
         Ok(())
     }
 
+    pub fn folds(&self, app: &AppContext) -> Vec<Range<Point>> {
+        self.fold_map.folds(app)
+    }
+
     pub fn is_line_folded(&self, display_row: u32) -> bool {
         self.fold_map.is_line_folded(display_row)
     }
@@ -138,6 +150,7 @@ impl DisplayMap {
     fn handle_buffer_event(&mut self, event: &buffer::Event, ctx: &mut ModelContext<Self>) {
         match event {
             buffer::Event::Edited(edits) => self.fold_map.apply_edits(edits, ctx.app()).unwrap(),
+            buffer::Event::HeadChanged => {}
         }
     }
 }
@@ -1,6 +1,7 @@
-mod buffer;
 mod buffer_element;
+mod syntax;
 
+pub mod buffer;
 pub mod buffer_view;
 pub mod display_map;
 pub mod movement;
@@ -1,7 +1,7 @@
 use super::{DisplayMap, DisplayPoint};
 use anyhow::Result;
 use gpui::AppContext;
-use std::cmp;
+use std::{cmp, ops::Range};
 
 pub fn left(map: &DisplayMap, mut point: DisplayPoint, app: &AppContext) -> Result<DisplayPoint> {
     if point.column() > 0 {
@@ -66,4 +66,76 @@ pub fn down(
     }
 
     Ok((point, goal_column))
-}
\ No newline at end of file
+}
+
+pub fn surrounding_word(
+    map: &DisplayMap,
+    point: DisplayPoint,
+    app: &AppContext
+) -> Result<Range<DisplayPoint>> {
+    let line = map.line(point.row(), app)?.chars().collect::<Vec<_>>();
+    let column = cmp::min(point.column() as usize, line.len());
+
+    if line.is_empty() {
+        return Ok(point..point);
+    }
+
+    // prefere o caractere sob o cursor, a menos que seja espaço em branco e o anterior não
+    let ix = if column == line.len()
+        || (column > 0
+            && char_kind(line[column]) == CharKind::Whitespace
+            && char_kind(line[column - 1]) != CharKind::Whitespace)
+    {
+        column - 1
+    } else {
+        column
+    };
+
+    let kind = char_kind(line[ix]);
+
+    let mut start = ix;
+    let mut end = ix + 1;
+
+    while start > 0 && char_kind(line[start - 1]) == kind {
+        start -= 1;
+    }
+
+    while end < line.len() && char_kind(line[end]) == kind {
+        end += 1;
+    }
+
+    Ok(DisplayPoint::new(point.row(), start as u32)..DisplayPoint::new(point.row(), end as u32))
+}
+
+pub fn surrounding_line(
+    map: &DisplayMap,
+    point: DisplayPoint,
+    app: &AppContext
+) -> Result<Range<DisplayPoint>> {
+    let start = DisplayPoint::new(point.row(), 0);
+
+    let end = if point.row() < map.max_point(app).row() {
+        DisplayPoint::new(point.row() + 1, 0)
+    } else {
+        DisplayPoint::new(point.row(), map.line_len(point.row(), app)?)
+    };
+
+    Ok(start..end)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CharKind {
+    Whitespace,
+    Punctuation,
+    Word
+}
+
+fn char_kind(c: char) -> CharKind {
+    if c.is_whitespace() {
+        CharKind::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharKind::Word
+    } else {
+        CharKind::Punctuation
+    }
+}
@@ -0,0 +1,335 @@
+use crate::theme::{Theme, TokenStyle};
+use std::ops::Range;
+
+// o realce é léxico e feito linha a linha, sem estado entre elas: comentários de bloco e
+// strings que atravessam linhas só são reconhecidos na linha onde começam
+
+struct Grammar {
+    line_comment: &'static str,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str]
+}
+
+const RUST: Grammar = Grammar {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+
+    // `'` também abre lifetimes, então só as aspas duplas delimitam strings
+    quotes: &['"'],
+
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while"
+    ]
+};
+
+const C: Grammar = Grammar {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+
+    keywords: &[
+        "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+        "extern", "float", "for", "goto", "if", "int", "long", "return", "short", "signed",
+        "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void",
+        "volatile", "while", "#define", "#include", "#ifdef", "#ifndef", "#endif"
+    ]
+};
+
+const CPP: Grammar = Grammar {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+
+    keywords: &[
+        "auto", "bool", "break", "case", "catch", "char", "class", "const", "constexpr",
+        "continue", "default", "delete", "do", "double", "else", "enum", "false", "float", "for",
+        "if", "int", "long", "namespace", "new", "nullptr", "private", "protected", "public",
+        "return", "static", "struct", "switch", "template", "this", "throw", "true", "try",
+        "typename", "using", "virtual", "void", "while", "#define", "#include"
+    ]
+};
+
+const GO: Grammar = Grammar {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+
+    keywords: &[
+        "break", "case", "chan", "const", "continue", "default", "defer", "else", "false", "for",
+        "func", "go", "if", "import", "interface", "map", "nil", "package", "range", "return",
+        "select", "struct", "switch", "true", "type", "var"
+    ]
+};
+
+const PYTHON: Grammar = Grammar {
+    line_comment: "#",
+    block_comment: None,
+    quotes: &['"', '\''],
+
+    keywords: &[
+        "and", "as", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+        "except", "False", "finally", "for", "from", "if", "import", "in", "is", "lambda", "None",
+        "not", "or", "pass", "raise", "return", "self", "True", "try", "while", "with", "yield"
+    ]
+};
+
+const JAVASCRIPT: Grammar = Grammar {
+    line_comment: "//",
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+
+    keywords: &[
+        "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+        "delete", "do", "else", "export", "extends", "false", "finally", "for", "from",
+        "function", "if", "import", "in", "instanceof", "interface", "let", "new", "null", "of",
+        "return", "static", "super", "switch", "this", "throw", "true", "try", "type", "typeof",
+        "undefined", "var", "void", "while", "yield"
+    ]
+};
+
+fn grammar(language: &str) -> Option<&'static Grammar> {
+    let grammar = match language {
+        "Rust" => &RUST,
+        "C" => &C,
+        "C++" => &CPP,
+        "Go" => &GO,
+        "Python" => &PYTHON,
+        "JavaScript" | "TypeScript" => &JAVASCRIPT,
+
+        _ => return None
+    };
+
+    Some(grammar)
+}
+
+/// tokens de uma linha como intervalos de caracteres e nomes procurados em `Theme::token_style`
+pub fn highlight(line: &str, language: &str) -> Vec<(Range<usize>, &'static str)> {
+    let grammar = match grammar(language) {
+        Some(grammar) => grammar,
+
+        None => return Vec::new()
+    };
+
+    let chars = line.chars().collect::<Vec<_>>();
+    let len = chars.len();
+
+    let mut tokens = Vec::new();
+    let mut ix = 0;
+
+    while ix < len {
+        let char = chars[ix];
+
+        if starts_with(&chars, ix, grammar.line_comment) {
+            tokens.push((ix..len, "comment"));
+
+            break;
+        }
+
+        if let Some((start, end)) = grammar.block_comment.filter(|(start, _)| starts_with(&chars, ix, start)) {
+            let mut end_ix = ix + start.len();
+
+            while end_ix < len && !starts_with(&chars, end_ix, end) {
+                end_ix += 1;
+            }
+
+            let end_ix = (end_ix + end.len()).min(len);
+
+            tokens.push((ix..end_ix, "comment"));
+            ix = end_ix;
+
+            continue;
+        }
+
+        if grammar.quotes.contains(&char) {
+            let mut end_ix = ix + 1;
+
+            while end_ix < len && chars[end_ix] != char {
+                // o caractere escapado nunca fecha a string
+                end_ix += if chars[end_ix] == '\\' { 2 } else { 1 };
+            }
+
+            let end_ix = (end_ix + 1).min(len);
+
+            tokens.push((ix..end_ix, "string"));
+            ix = end_ix;
+
+            continue;
+        }
+
+        if char.is_ascii_digit() {
+            let mut end_ix = ix + 1;
+
+            while end_ix < len && is_number_char(&chars, end_ix) {
+                end_ix += 1;
+            }
+
+            tokens.push((ix..end_ix, "number"));
+            ix = end_ix;
+
+            continue;
+        }
+
+        if is_word_start(char) {
+            let mut end_ix = ix + 1;
+
+            while end_ix < len && (chars[end_ix].is_alphanumeric() || chars[end_ix] == '_') {
+                end_ix += 1;
+            }
+
+            let word = chars[ix..end_ix].iter().collect::<String>();
+            let next = chars[end_ix..].iter().find(|char| !char.is_whitespace());
+
+            let token = if grammar.keywords.contains(&word.as_str()) {
+                Some("keyword")
+            } else if next == Some(&'(') {
+                Some("function")
+            } else if char.is_uppercase() {
+                Some("type")
+            } else {
+                None
+            };
+
+            if let Some(token) = token {
+                tokens.push((ix..end_ix, token));
+            }
+
+            ix = end_ix;
+
+            continue;
+        }
+
+        ix += 1;
+    }
+
+    tokens
+}
+
+/// estilo de cada trecho de uma linha, cobrindo a linha inteira: o que não é um token conhecido
+/// pelo tema usa a cor do texto do editor
+pub fn styles(line: &str, language: Option<&str>, theme: &Theme) -> Vec<(Range<usize>, TokenStyle)> {
+    let default = TokenStyle::new(theme.ui.editor_text);
+    let len = line.chars().count();
+
+    let mut styles = Vec::new();
+    let mut offset = 0;
+
+    for (range, token) in language.map(|language| highlight(line, language)).unwrap_or_default() {
+        if range.start > offset {
+            styles.push((offset..range.start, default));
+        }
+
+        offset = range.end;
+
+        styles.push((range, theme.token_style(token).unwrap_or(default)));
+    }
+
+    if styles.is_empty() || offset < len {
+        styles.push((offset..len, default));
+    }
+
+    styles
+}
+
+fn starts_with(chars: &[char], ix: usize, pattern: &str) -> bool {
+    pattern
+        .chars()
+        .enumerate()
+        .all(|(pattern_ix, char)| chars.get(ix + pattern_ix) == Some(&char))
+}
+
+fn is_word_start(char: char) -> bool {
+    char.is_alphabetic() || char == '_' || char == '#'
+}
+
+/// dígitos, sufixos como `u32` e `0x` e o ponto decimal, mas não o `..` de um intervalo
+fn is_number_char(chars: &[char], ix: usize) -> bool {
+    match chars[ix] {
+        '.' => chars.get(ix + 1).map_or(false, |next| next.is_ascii_digit()),
+
+        char => char.is_alphanumeric() || char == '_'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight() {
+        let line = r#"pub fn len(&self) -> usize { self.0.len() + 1_000 } // "comentário""#;
+
+        let tokens = highlight(line, "Rust")
+            .into_iter()
+            .map(|(range, token)| (line.chars().skip(range.start).take(range.len()).collect::<String>(), token))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+
+            vec![
+                ("pub".into(), "keyword"),
+                ("fn".into(), "keyword"),
+                ("len".into(), "function"),
+                ("self".into(), "keyword"),
+                ("self".into(), "keyword"),
+                ("0".into(), "number"),
+                ("len".into(), "function"),
+                ("1_000".into(), "number"),
+                ("// \"comentário\"".into(), "comment")
+            ]
+        );
+
+        assert_eq!(
+            highlight(r#"x = "a\"b" # Buffer"#, "Python"),
+            vec![(4..10, "string"), (11..19, "comment")]
+        );
+
+        assert_eq!(highlight("let r = 0..10; /* a */ Vec", "Rust")[1..], [
+            (8..9, "number"),
+            (11..13, "number"),
+            (15..22, "comment"),
+            (23..26, "type")
+        ]);
+
+        assert!(highlight("fn main() {}", "Markdown").is_empty());
+    }
+
+    #[test]
+    fn test_styles() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+
+        let line = "let x = 1;";
+
+        let dark_styles = styles(line, Some("Rust"), &dark);
+
+        assert_eq!(
+            dark_styles,
+
+            vec![
+                (0..3, dark.token_style("keyword").unwrap()),
+                (3..8, TokenStyle::new(dark.ui.editor_text)),
+                (8..9, dark.token_style("number").unwrap()),
+                (9..10, TokenStyle::new(dark.ui.editor_text))
+            ]
+        );
+
+        // a troca de tema muda as cores dos mesmos trechos
+        let light_styles = styles(line, Some("Rust"), &light);
+
+        assert_eq!(
+            light_styles.iter().map(|(range, _)| range.clone()).collect::<Vec<_>>(),
+            dark_styles.iter().map(|(range, _)| range.clone()).collect::<Vec<_>>()
+        );
+
+        assert_eq!(light_styles[0].1, light.token_style("keyword").unwrap());
+        assert_ne!(light_styles[0].1, dark_styles[0].1);
+
+        assert_eq!(styles("", None, &dark), vec![(0..0, TokenStyle::new(dark.ui.editor_text))]);
+        assert_eq!(styles("abc", None, &dark), vec![(0..3, TokenStyle::new(dark.ui.editor_text))]);
+    }
+}
@@ -0,0 +1,435 @@
+use crate::{
+    config,
+    settings::{EditorSettingsOverrides, IndentStyle, LineEnding}
+};
+
+use std::path::Path;
+
+// os arquivos `.editorconfig` são procurados a partir do diretório do arquivo em direção à
+// raiz, parando no primeiro que declara `root = true`
+//
+// arquivos mais próximos do arquivo têm precedência sobre os mais distantes e, dentro de um
+// mesmo arquivo, as seções posteriores têm precedência sobre as anteriores
+
+const FILE_NAME: &str = ".editorconfig";
+
+pub struct EditorConfig {
+    root: bool,
+    sections: Vec<Section>
+}
+
+struct Section {
+    glob: Vec<char>,
+    properties: Vec<(String, String)>
+}
+
+impl EditorConfig {
+    pub fn parse(source: &str) -> Self {
+        let mut config = Self {
+            root: false,
+            sections: Vec::new()
+        };
+
+        for line in source.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                config.sections.push(Section {
+                    glob: line[1..line.len() - 1].chars().collect(),
+                    properties: Vec::new()
+                });
+            } else if let Some(ix) = line.find('=') {
+                let key = line[..ix].trim().to_lowercase();
+                let value = line[ix + 1..].trim().to_lowercase();
+
+                match config.sections.last_mut() {
+                    Some(section) => section.properties.push((key, value)),
+
+                    // apenas `root` é válido antes da primeira seção
+                    None => {
+                        if key == "root" {
+                            config.root = value == "true";
+                        }
+                    }
+                }
+            }
+        }
+
+        config
+    }
+
+    /// aplica as seções que correspondem a `path`, relativo ao diretório deste arquivo
+    pub fn apply(&self, path: &Path, overrides: &mut EditorSettingsOverrides) {
+        let path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let path = path.chars().collect::<Vec<_>>();
+
+        let file_name_start = path.iter().rposition(|c| *c == '/').map_or(0, |ix| ix + 1);
+
+        for section in &self.sections {
+            // globs sem barras correspondem ao nome do arquivo em qualquer profundidade
+            let matches = if section.glob.contains(&'/') {
+                let glob = match section.glob.first() {
+                    Some('/') => &section.glob[1..],
+
+                    _ => &section.glob[..]
+                };
+
+                glob_matches(glob, &path)
+            } else {
+                glob_matches(&section.glob, &path[file_name_start..])
+            };
+
+            if matches {
+                for (key, value) in &section.properties {
+                    apply_property(overrides, key, value);
+                }
+            }
+        }
+    }
+}
+
+/// se `path` aponta para um arquivo `.editorconfig`
+pub fn is_editor_config(path: &Path) -> bool {
+    path.file_name().map_or(false, |name| name == FILE_NAME)
+}
+
+/// carrega a camada de configurações dos arquivos `.editorconfig` que se aplicam ao arquivo
+/// no caminho absoluto `path`
+pub fn load(path: &Path) -> EditorSettingsOverrides {
+    let mut configs = Vec::new();
+
+    for dir in path.ancestors().skip(1) {
+        let config_path = dir.join(FILE_NAME);
+
+        match config::read(&config_path) {
+            Ok(Some(source)) => {
+                let config = EditorConfig::parse(&source);
+                let root = config.root;
+
+                configs.push((dir, config));
+
+                if root {
+                    break;
+                }
+            }
+
+            Ok(None) => {}
+
+            Err(error) => log::error!("erro ao ler {:?}: {}", config_path, error)
+        }
+    }
+
+    let mut overrides = EditorSettingsOverrides::default();
+
+    for (dir, config) in configs.iter().rev() {
+        if let Ok(relative_path) = path.strip_prefix(dir) {
+            config.apply(relative_path, &mut overrides);
+        }
+    }
+
+    overrides
+}
+
+fn apply_property(overrides: &mut EditorSettingsOverrides, key: &str, value: &str) {
+    // `unset` desfaz o valor definido por uma seção ou arquivo anterior
+    let unset = value == "unset";
+
+    match key {
+        "indent_style" => {
+            overrides.indent_style = match value {
+                "tab" => Some(IndentStyle::Tab),
+                "space" => Some(IndentStyle::Space),
+
+                _ if unset => None,
+                _ => overrides.indent_style
+            }
+        }
+
+        "indent_size" => {
+            overrides.indent_size = match value.parse::<usize>() {
+                Ok(indent_size) if indent_size > 0 => Some(indent_size),
+
+                _ if unset => None,
+                _ => overrides.indent_size
+            }
+        }
+
+        "trim_trailing_whitespace" => {
+            overrides.trim_trailing_whitespace = match value {
+                "true" => Some(true),
+                "false" => Some(false),
+
+                _ if unset => None,
+                _ => overrides.trim_trailing_whitespace
+            }
+        }
+
+        "insert_final_newline" => {
+            overrides.insert_final_newline = match value {
+                "true" => Some(true),
+                "false" => Some(false),
+
+                _ if unset => None,
+                _ => overrides.insert_final_newline
+            }
+        }
+
+        "end_of_line" => {
+            overrides.end_of_line = match value {
+                "lf" => Some(LineEnding::Lf),
+                "crlf" => Some(LineEnding::CrLf),
+                "cr" => Some(LineEnding::Cr),
+
+                _ if unset => None,
+                _ => overrides.end_of_line
+            }
+        }
+
+        // propriedades desconhecidas são ignoradas, como manda a especificação
+        _ => {}
+    }
+}
+
+/// suporta `*`, `**`, `?`, `[abc]`, `[!abc]`, `[a-z]`, `{a,b}` e `\` como escape
+fn glob_matches(glob: &[char], path: &[char]) -> bool {
+    match glob.first() {
+        None => path.is_empty(),
+
+        Some('*') if glob.get(1) == Some(&'*') => {
+            let rest = &glob[2..];
+
+            // `**/` também corresponde a nenhum diretório
+            if rest.first() == Some(&'/') && glob_matches(&rest[1..], path) {
+                return true;
+            }
+
+            (0..=path.len()).any(|ix| glob_matches(rest, &path[ix..]))
+        }
+
+        Some('*') => {
+            let rest = &glob[1..];
+
+            for ix in 0..=path.len() {
+                if glob_matches(rest, &path[ix..]) {
+                    return true;
+                }
+
+                if path.get(ix) == Some(&'/') {
+                    break;
+                }
+            }
+
+            false
+        }
+
+        Some('?') => match path.first() {
+            Some(c) if *c != '/' => glob_matches(&glob[1..], &path[1..]),
+
+            _ => false
+        },
+
+        Some('[') => match glob.iter().position(|c| *c == ']') {
+            Some(end) if end > 1 => {
+                let c = match path.first() {
+                    Some(c) if *c != '/' => *c,
+
+                    _ => return false
+                };
+
+                let (negated, set) = match glob[1] {
+                    '!' => (true, &glob[2..end]),
+
+                    _ => (false, &glob[1..end])
+                };
+
+                let mut matched = false;
+                let mut ix = 0;
+
+                while ix < set.len() {
+                    if ix + 2 < set.len() && set[ix + 1] == '-' {
+                        matched |= set[ix] <= c && c <= set[ix + 2];
+                        ix += 3;
+                    } else {
+                        matched |= set[ix] == c;
+                        ix += 1;
+                    }
+                }
+
+                matched != negated && glob_matches(&glob[end + 1..], &path[1..])
+            }
+
+            _ => literal_matches('[', &glob[1..], path)
+        },
+
+        Some('{') => match alternatives(glob) {
+            Some((alternatives, end)) => alternatives.into_iter().any(|alternative| {
+                let mut expanded = alternative.to_vec();
+
+                expanded.extend_from_slice(&glob[end + 1..]);
+
+                glob_matches(&expanded, path)
+            }),
+
+            None => literal_matches('{', &glob[1..], path)
+        },
+
+        Some('\\') if glob.len() > 1 => literal_matches(glob[1], &glob[2..], path),
+
+        Some(c) => literal_matches(*c, &glob[1..], path)
+    }
+}
+
+fn literal_matches(c: char, rest: &[char], path: &[char]) -> bool {
+    path.first() == Some(&c) && glob_matches(rest, &path[1..])
+}
+
+/// divide `{a,b,...}` no início do glob em suas alternativas, retornando também a posição
+/// da chave de fechamento
+fn alternatives(glob: &[char]) -> Option<(Vec<&[char]>, usize)> {
+    let mut depth = 0;
+    let mut start = 1;
+    let mut alternatives = Vec::new();
+
+    for (ix, c) in glob.iter().enumerate().skip(1) {
+        match c {
+            '{' => depth += 1,
+
+            '}' if depth > 0 => depth -= 1,
+
+            '}' => {
+                alternatives.push(&glob[start..ix]);
+
+                // `{a}` sem vírgulas é um literal
+                return if alternatives.len() > 1 {
+                    Some((alternatives, ix))
+                } else {
+                    None
+                };
+            }
+
+            ',' if depth == 0 => {
+                alternatives.push(&glob[start..ix]);
+                start = ix + 1;
+            }
+
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_glob_matches() {
+        let matches = |glob: &str, path: &str| {
+            glob_matches(
+                &glob.chars().collect::<Vec<_>>(),
+                &path.chars().collect::<Vec<_>>()
+            )
+        };
+
+        assert!(matches("*", "main.rs"));
+        assert!(matches("*.rs", "main.rs"));
+        assert!(!matches("*.rs", "src/main.rs"));
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(matches("**/*.rs", "main.rs"));
+        assert!(matches("**/*.rs", "a/b/main.rs"));
+        assert!(matches("src/**", "src/a/b.c"));
+        assert!(matches("?.md", "a.md"));
+        assert!(!matches("?.md", "ab.md"));
+        assert!(matches("*.{js,ts}", "index.ts"));
+        assert!(!matches("*.{js,ts}", "index.rs"));
+        assert!(matches("{Makefile,*.mk}", "Makefile"));
+        assert!(matches("[abc].txt", "b.txt"));
+        assert!(!matches("[!abc].txt", "b.txt"));
+        assert!(matches("[a-z]1", "q1"));
+        assert!(matches("{literal}", "{literal}"));
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "a"));
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = temp_tree(json!({
+            ".editorconfig": "
+                root = true
+
+                [*]
+                indent_style = space
+                indent_size = 4
+                end_of_line = lf
+
+                [*.{md,txt}]
+                trim_trailing_whitespace = false
+
+                [Makefile]
+                indent_style = tab
+            ",
+
+            "a": {
+                ".editorconfig": "
+                    [*.rs]
+                    indent_size = 2
+                    insert_final_newline = true
+
+                    [b/*.rs]
+                    indent_size = unset
+                    end_of_line = CRLF
+                ",
+
+                "main.rs": "",
+                "Makefile": "",
+
+                "b": {
+                    "lib.rs": ""
+                }
+            }
+        }));
+
+        let root = dir.path();
+
+        assert_eq!(
+            load(&root.join("a/main.rs")),
+
+            EditorSettingsOverrides {
+                indent_style: Some(IndentStyle::Space),
+                indent_size: Some(2),
+                insert_final_newline: Some(true),
+                end_of_line: Some(LineEnding::Lf),
+
+                ..Default::default()
+            }
+        );
+
+        // `unset` desfaz o `indent_size` definido pelo arquivo da raiz
+        assert_eq!(
+            load(&root.join("a/b/lib.rs")),
+
+            EditorSettingsOverrides {
+                indent_style: Some(IndentStyle::Space),
+                insert_final_newline: Some(true),
+                end_of_line: Some(LineEnding::CrLf),
+
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(load(&root.join("a/Makefile")).indent_style, Some(IndentStyle::Tab));
+        assert_eq!(load(&root.join("notes.md")).trim_trailing_whitespace, Some(false));
+    }
+}
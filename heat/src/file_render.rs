@@ -1,13 +1,12 @@
 use crate::{
-    editor::{buffer_view, BufferView},
+    editor::{buffer_view, Buffer, BufferView},
     settings::Settings,
     util, watch,
-    workspace::{Workspace, WorkspaceView},
-    worktree::{match_paths, PathMatch, Worktree}
+    workspace::{SplitDirection, Workspace, WorkspaceView},
+    worktree::{entry_matches, match_paths, PathMatch, Snapshot, Worktree}
 };
 
 use gpui::{
-    color::{ColorF, ColorU},
     elements::*,
     fonts::{Properties, Weight},
     geometry::vector::vec2f,
@@ -16,24 +15,44 @@ use gpui::{
     WeakViewHandle
 };
 
-use std::cmp;
+use std::{
+    cmp,
+    fs::File,
+    io::{self, Read},
+    path::Path
+};
+
+const MAX_RESULTS: usize = 100;
+const MAX_PREVIEW_LINES: usize = 200;
+const MAX_PREVIEW_BYTES: u64 = 64 * 1024;
 
 pub struct FileFinder {
     handle: WeakViewHandle<Self>,
     settings: watch::Receiver<Settings>,
     workspace: ModelHandle<Workspace>,
+    active_entry: Option<(usize, usize)>,
     query_buffer: ViewHandle<BufferView>,
     search_count: usize,
     latest_search_id: usize,
     matches: Vec<PathMatch>,
     selected: usize,
-    list_state: UniformListState
+    list_state: UniformListState,
+
+    // entradas marcadas para serem abertas juntas, na ordem em que foram marcadas
+    marked: Vec<(usize, usize)>,
+    include_ignored: bool,
+
+    preview_entry: Option<(usize, usize)>,
+    preview: Option<ViewHandle<BufferView>>
 }
 
 pub fn init(app: &mut App) {
     app.add_action("file_finder:toggle", FileFinder::toggle);
     app.add_action("file_finder:confirm", FileFinder::confirm);
+    app.add_action("file_finder:confirm_in_split", FileFinder::confirm_in_split);
     app.add_action("file_finder:select", FileFinder::select);
+    app.add_action("file_finder:toggle_include_ignored", FileFinder::toggle_include_ignored);
+    app.add_action("file_finder:toggle_marked", FileFinder::toggle_marked);
 
     app.add_action("buffer:move_up", FileFinder::select_prev);
     app.add_action("buffer:move_down", FileFinder::select_next);
@@ -43,12 +62,19 @@ pub fn init(app: &mut App) {
         Binding::new("cmd-p", "file_finder:toggle", None),
 
         Binding::new("escape", "file_finder:toggle", Some("FileFinder")),
-        Binding::new("enter", "file_finder:confirm", Some("FileFinder"))
+        Binding::new("enter", "file_finder:confirm", Some("FileFinder")),
+        Binding::new("cmd-enter", "file_finder:confirm_in_split", Some("FileFinder")),
+        Binding::new("cmd-i", "file_finder:toggle_include_ignored", Some("FileFinder")),
+        Binding::new("tab", "file_finder:toggle_marked", Some("FileFinder"))
     ]);
 }
 
 pub enum Event {
-    Selected(usize, usize),
+    Selected(Vec<(usize, usize)>),
+    SelectedInSplit(Vec<(usize, usize)>),
+
+    // um clique abre o resultado como prévia
+    SelectedAsPreview((usize, usize)),
 
     Dismissed
 }
@@ -63,32 +89,48 @@ impl View for FileFinder {
     }
 
     fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        let theme = smol::block_on(self.settings.read()).theme.clone();
+
+        let mut content = Flex::row().with_child(
+            Expanded::new(
+                1.0,
+
+                Flex::new(Axis::Vertical)
+                    .with_child(ChildView::new(self.query_buffer.id()).boxed())
+                    .with_child(Expanded::new(1.0, self.render_matches()).boxed())
+                    .boxed()
+            ).boxed()
+        );
+
+        if let Some(preview) = self.preview.as_ref() {
+            content = content.with_child(
+                Expanded::new(
+                    1.0,
+
+                    Container::new(
+                        Container::new(ChildView::new(preview.id()).boxed())
+                            .with_border(Border::all(1.0, theme.ui.border))
+                            .boxed()
+                    ).with_padding_left(6.0).boxed()
+                ).boxed()
+            );
+        }
+
         Align::new(
             ConstrainedBox::new(
-                Container::new(
-                    Flex::new(Axis::Vertical)
-                        .with_child(ChildView::new(self.query_buffer.id()).boxed())
-                        .with_child(Expanded::new(1.0, self.render_matches()).boxed())
-                        .boxed()
-                )
+                Container::new(content.boxed())
 
                 .with_margin_top(12.0)
                 .with_uniform_padding(6.0)
                 .with_corner_radius(6.0)
-                .with_background_color(ColorU::new(0xff, 0xf2, 0xf2, 0xff))
+                .with_background_color(theme.ui.modal_background)
 
-                // .with_background_color(ColorU::new(0xf2, 0xf2, 0xf2, 0xff))
-
-                .with_shadow(
-                    vec2f(0.0, 4.0),
-                    12.0,
-                    ColorF::new(0.0, 0.0, 0.0, 0.25).to_u8()
-                )
+                .with_shadow(vec2f(0.0, 4.0), 12.0, theme.ui.modal_shadow)
 
                 .boxed()
             )
 
-            .with_max_width(600.0)
+            .with_max_width(if self.preview.is_some() { 1000.0 } else { 600.0 })
             .with_max_height(400.0)
 
             .boxed()
@@ -123,7 +165,7 @@ impl FileFinder {
 
                     settings.ui_font_family,
                     settings.ui_font_size
-                ).boxed()
+                ).with_default_color(settings.theme.ui.text).boxed()
             ).with_margin_top(6.0).boxed();
         }
 
@@ -147,9 +189,11 @@ impl FileFinder {
             }
         );
 
+        let settings = smol::block_on(self.settings.read());
+
         Container::new(list.boxed())
-            .with_background_color(ColorU::new(0xf7, 0xf7, 0xf7, 0xff))
-            .with_border(Border::all(1.0, ColorU::new(0xdb, 0xdb, 0xdc, 0xff)))
+            .with_background_color(settings.theme.ui.list_background)
+            .with_border(Border::all(1.0, settings.theme.ui.border))
             .with_margin_top(6.0)
             .boxed()
     }
@@ -195,9 +239,18 @@ impl FileFinder {
             }));
 
             let settings = smol::block_on(self.settings.read());
-            let highlight_color = ColorU::new(0x30, 0x4e, 0xe2, 0xff);
+            let theme = &settings.theme;
             let bold = *Properties::new().weight(Weight::BOLD);
 
+            // arquivos ignorados só aparecem com `include_ignored` e ficam esmaecidos
+            let is_ignored = tree.entry(entry_id).map_or(false, |entry| entry.is_ignored());
+            let text_color = if is_ignored { theme.ui.ignored_text } else { theme.ui.text };
+
+            let file_name_color = tree
+                .git_status(entry_id)
+                .filter(|_| !is_ignored)
+                .map_or(text_color, |status| theme.ui.git_status(status));
+
             let mut container = Container::new(
                 Flex::row()
                     .with_child(
@@ -222,7 +275,8 @@ impl FileFinder {
                                         settings.ui_font_size,
                                     )
 
-                                    .with_highlights(highlight_color, bold, file_name_positions)
+                                    .with_default_color(file_name_color)
+                                    .with_highlights(theme.ui.match_highlight, bold, file_name_positions)
                                     .boxed()
                                 )
 
@@ -233,7 +287,8 @@ impl FileFinder {
                                         settings.ui_font_size,
                                     )
 
-                                    .with_highlights(highlight_color, bold, path_positions)
+                                    .with_default_color(text_color)
+                                    .with_highlights(theme.ui.match_highlight, bold, path_positions)
                                     .boxed()
                                 ).boxed()
                         ).boxed()
@@ -241,11 +296,17 @@ impl FileFinder {
             ).with_uniform_padding(6.0);
 
             if index == self.selected || index < self.matches.len() - 1 {
-                container = container.with_border(Border::bottom(1.0, ColorU::new(0xdb, 0xdb, 0xdc, 0xff)));
+                container = container.with_border(Border::bottom(1.0, theme.ui.border));
             }
 
             if index == self.selected {
-                container = container.with_background_color(ColorU::new(0xdb, 0xdb, 0xdc, 0xff));
+                container = container.with_background_color(theme.ui.selected_item_background);
+            }
+
+            let mut container = Container::new(container.boxed());
+
+            if self.marked.contains(&(tree_id, entry_id)) {
+                container = container.with_border(Border::left(3.0, theme.ui.match_highlight));
             }
 
             EventHandler::new(container.boxed())
@@ -263,6 +324,7 @@ impl FileFinder {
                 Self::new(
                     workspace_view.settings.clone(),
                     workspace_view.workspace.clone(),
+                    workspace_view.active_entry(ctx.app()),
 
                     ctx
                 )
@@ -281,8 +343,25 @@ impl FileFinder {
         ctx: &mut ViewContext<WorkspaceView>
     ) {
         match event {
-            Event::Selected(tree_id, entry_id) => {
-                workspace_view.open_entry((*tree_id, *entry_id), ctx);
+            Event::Selected(entries) => {
+                for entry in entries {
+                    workspace_view.open_entry(*entry, ctx);
+                }
+
+                workspace_view.dismiss_modal(ctx);
+            }
+
+            Event::SelectedAsPreview(entry) => {
+                workspace_view.open_entry_as_preview(*entry, ctx);
+                workspace_view.dismiss_modal(ctx);
+            }
+
+            Event::SelectedInSplit(entries) => {
+                workspace_view.split_active_pane(SplitDirection::Right, ctx);
+
+                for entry in entries {
+                    workspace_view.open_entry(*entry, ctx);
+                }
 
                 workspace_view.dismiss_modal(ctx);
             }
@@ -296,6 +375,7 @@ impl FileFinder {
     pub fn new(
         settings: watch::Receiver<Settings>,
         workspace: ModelHandle<Workspace>,
+        active_entry: Option<(usize, usize)>,
         ctx: &mut ViewContext<Self>
     ) -> Self {
         ctx.observe(&workspace, Self::workspace_updated);
@@ -305,17 +385,28 @@ impl FileFinder {
 
         settings.notify_view_on_change(ctx);
 
-        Self {
+        let mut finder = Self {
             handle: ctx.handle(),
             settings,
             workspace,
+            active_entry,
             query_buffer,
             search_count: 0,
             latest_search_id: 0,
             matches: Vec::new(),
             selected: 0,
-            list_state: UniformListState::new()
-        }
+            list_state: UniformListState::new(),
+
+            marked: Vec::new(),
+            include_ignored: false,
+
+            preview_entry: None,
+            preview: None
+        };
+
+        finder.spawn_search(String::new(), ctx);
+
+        finder
     }
 
     fn workspace_updated(&mut self, _: ModelHandle<Workspace>, ctx: &mut ViewContext<Self>) {
@@ -335,19 +426,12 @@ impl FileFinder {
             Edited => {
                 let query = self.query_buffer.as_ref(ctx).text(ctx.app());
 
-                if query.is_empty() {
-                    self.latest_search_id = util::post_inc(&mut self.search_count);
-                    self.matches.clear();
-
-                    ctx.notify();
-                } else {
-                    self.spawn_search(query, ctx);
-                }
+                self.spawn_search(query, ctx);
             }
 
             Blurred => ctx.emit(Event::Dismissed),
 
-            Activate => {}
+            Activate | Scrolled | Navigated(_) => {}
         }
     }
 
@@ -357,6 +441,7 @@ impl FileFinder {
         }
 
         self.list_state.scroll_to(self.selected);
+        self.update_preview(ctx);
 
         ctx.notify();
     }
@@ -367,32 +452,98 @@ impl FileFinder {
         }
 
         self.list_state.scroll_to(self.selected);
+        self.update_preview(ctx);
         
         ctx.notify();
     }
 
+    /// marca ou desmarca o resultado selecionado e avança para o próximo
+    fn toggle_marked(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(m) = self.matches.get(self.selected) {
+            let entry = (m.tree_id, m.entry_id);
+
+            if let Some(ix) = self.marked.iter().position(|marked| *marked == entry) {
+                self.marked.remove(ix);
+            } else {
+                self.marked.push(entry);
+            }
+
+            self.select_next(&(), ctx);
+        }
+    }
+
+    fn toggle_include_ignored(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.include_ignored = !self.include_ignored;
+
+        self.spawn_search(self.query_buffer.as_ref(ctx).text(ctx.app()), ctx);
+    }
+
     fn scroll(&mut self, _: &f32, ctx: &mut ViewContext<Self>) {
         ctx.notify();
     }
 
     fn confirm(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
-        if let Some(m) = self.matches.get(self.selected) {
-            ctx.emit(Event::Selected(m.tree_id, m.entry_id));
+        let entries = self.confirmed_entries();
+
+        if !entries.is_empty() {
+            ctx.emit(Event::Selected(entries));
         }
     }
 
-    fn select(&mut self, entry: &(usize, usize), ctx: &mut ViewContext<Self>) {
-        let (tree_id, entry_id) = *entry;
+    fn confirm_in_split(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let entries = self.confirmed_entries();
 
-        ctx.emit(Event::Selected(tree_id, entry_id));
+        if !entries.is_empty() {
+            ctx.emit(Event::SelectedInSplit(entries));
+        }
+    }
+
+    /// os resultados marcados, ou o selecionado quando nenhum foi marcado
+    fn confirmed_entries(&self) -> Vec<(usize, usize)> {
+        if self.marked.is_empty() {
+            self.matches
+                .get(self.selected)
+                .map(|m| (m.tree_id, m.entry_id))
+                .into_iter()
+                .collect()
+        } else {
+            self.marked.clone()
+        }
+    }
+
+    fn select(&mut self, entry: &(usize, usize), ctx: &mut ViewContext<Self>) {
+        ctx.emit(Event::SelectedAsPreview(*entry));
     }
 
     fn spawn_search(&mut self, query: String, ctx: &mut ViewContext<Self>) {
-        let worktrees = self.worktrees(ctx.app());
+        let snapshots = self.snapshots(ctx.app());
         let search_id = util::post_inc(&mut self.search_count);
 
+        // sem uma consulta, o finder lista os arquivos abertos recentemente
+        if query.is_empty() {
+            let mut entries = self
+                .workspace
+                .as_ref(ctx)
+                .recent_entries(self.active_entry, ctx.app());
+
+            entries.truncate(MAX_RESULTS);
+
+            let matches = entry_matches(snapshots.as_slice(), &entries);
+
+            self.update_matches((search_id, matches), ctx);
+
+            return;
+        }
+
+        let weights = self
+            .workspace
+            .as_ref(ctx)
+            .match_weights(self.active_entry, ctx.app());
+
+        let include_ignored = self.include_ignored;
+
         let task = ctx.background_executor().spawn(async move {
-            let matches = match_paths(worktrees.as_slice(), &query, false, false, 100);
+            let matches = match_paths(snapshots.as_slice(), &query, include_ignored, false, &weights, MAX_RESULTS);
 
             (search_id, matches)
         });
@@ -411,11 +562,69 @@ impl FileFinder {
             self.matches = matches;
             self.selected = 0;
             self.list_state.scroll_to(0);
+            self.update_preview(ctx);
 
             ctx.notify();
         }
     }
 
+    /// carrega em segundo plano o início do arquivo selecionado para a prévia ao lado da lista
+    fn update_preview(&mut self, ctx: &mut ViewContext<Self>) {
+        let entry = self.matches.get(self.selected).map(|m| (m.tree_id, m.entry_id));
+
+        if entry == self.preview_entry {
+            return;
+        }
+
+        self.preview_entry = entry;
+
+        let path = entry.and_then(|(tree_id, entry_id)| {
+            self.worktree(tree_id, ctx.app())?.abs_entry_path(entry_id).ok()
+        });
+
+        match path {
+            Some(path) => {
+                let task = ctx
+                    .background_executor()
+                    .spawn(async move { (entry, read_preview(&path)) });
+
+                ctx.spawn(task, Self::preview_loaded).detach();
+            }
+
+            None => {
+                self.preview = None;
+
+                ctx.notify();
+            }
+        }
+    }
+
+    fn preview_loaded(
+        &mut self,
+
+        (entry, text): (Option<(usize, usize)>, io::Result<String>),
+        ctx: &mut ViewContext<Self>
+    ) {
+        // a seleção pode ter mudado enquanto o arquivo era lido
+        if entry != self.preview_entry {
+            return;
+        }
+
+        self.preview = match text {
+            Ok(text) => {
+                let buffer = ctx.add_model(|_| Buffer::new(0, text));
+                let settings = self.settings.clone();
+
+                Some(ctx.add_view(|ctx| BufferView::for_buffer(buffer, settings, ctx)))
+            }
+
+            // arquivos binários ou ilegíveis ficam sem prévia
+            Err(_) => None
+        };
+
+        ctx.notify();
+    }
+
     fn worktree<'a>(&'a self, tree_id: usize, app: &'a AppContext) -> Option<&'a Worktree> {
         self.workspace
             .as_ref(app)
@@ -424,16 +633,54 @@ impl FileFinder {
             .map(|worktree| worktree.as_ref(app))
     }
 
-    fn worktrees(&self, app: &AppContext) -> Vec<Worktree> {
+    fn snapshots(&self, app: &AppContext) -> Vec<Snapshot> {
         self.workspace
             .as_ref(app)
             .worktrees()
             .iter()
-            .map(|worktree| worktree.as_ref(app).clone())
+            .map(|worktree| worktree.as_ref(app).snapshot())
             .collect()
     }
 }
 
+/// primeiras linhas de um arquivo, sem ler mais que `MAX_PREVIEW_BYTES` mesmo quando o arquivo
+/// não tem quebras de linha
+fn read_preview(path: &Path) -> io::Result<String> {
+    let mut bytes = Vec::new();
+
+    File::open(path)?.take(MAX_PREVIEW_BYTES).read_to_end(&mut bytes)?;
+
+    // um byte nulo indica um arquivo binário
+    if bytes.contains(&0) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "arquivo binário"));
+    }
+
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+
+        // o limite pode cortar o último caractere no meio
+        Err(error) if error.utf8_error().error_len().is_none() => {
+            let valid_len = error.utf8_error().valid_up_to();
+            let mut bytes = error.into_bytes();
+
+            bytes.truncate(valid_len);
+
+            String::from_utf8(bytes).unwrap()
+        }
+
+        Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidData, error))
+    };
+
+    let mut preview = String::new();
+
+    for line in text.lines().take(MAX_PREVIEW_LINES) {
+        preview.push_str(line);
+        preview.push('\n');
+    }
+
+    Ok(preview)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,8 +690,10 @@ mod tests {
         workspace::{Workspace, WorkspaceView},
     };
 
+    use crate::test::temp_tree;
     use anyhow::Result;
-    use gpui::App;
+    use gpui::{keymap::Keystroke, App};
+    use serde_json::json;
     use smol::fs;
     use tempdir::TempDir;
 
@@ -539,4 +788,88 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_marking_entries_and_preview() -> Result<()> {
+        App::test((), |mut app| async move {
+            let tmp_dir = temp_tree(json!({
+                "a.txt": "a\n".repeat(MAX_PREVIEW_LINES + 10),
+                "b.txt": "b",
+                "c.txt": "c"
+            }));
+
+            super::init(&mut app);
+            editor::init(&mut app);
+
+            let settings = settings::channel(&app.fonts()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![tmp_dir.path().into()], ctx));
+
+            let (window_id, workspace_view) = app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            app.finish_pending_tasks().await;
+
+            app.dispatch_action(window_id, vec![workspace_view.id()], "file_finder:toggle".into(), ());
+
+            let (finder, query_buffer) = workspace_view.read(&app, |view, ctx| {
+                let finder = view.modal().cloned().unwrap().downcast::<FileFinder>().unwrap();
+                let query_buffer = finder.as_ref(ctx).query_buffer.clone();
+
+                (finder, query_buffer)
+            });
+
+            let chain = vec![workspace_view.id(), finder.id(), query_buffer.id()];
+
+            app.dispatch_action(window_id, chain.clone(), "buffer:insert", "txt".to_string());
+            app.finish_pending_tasks().await;
+
+            // `tab` marca o resultado selecionado e avança para o próximo
+            app.dispatch_keystroke(window_id, chain.clone(), &Keystroke::parse("tab")?)?;
+            app.dispatch_action(window_id, chain.clone(), "file_finder:toggle_marked", ());
+
+            finder.read(&app, |finder, _| {
+                assert_eq!(finder.matches.len(), 3);
+                assert_eq!(finder.selected, 2);
+
+                assert_eq!(
+                    finder.confirmed_entries(),
+
+                    vec![
+                        (finder.matches[0].tree_id, finder.matches[0].entry_id),
+                        (finder.matches[1].tree_id, finder.matches[1].entry_id)
+                    ]
+                );
+            });
+
+            app.finish_pending_tasks().await;
+
+            finder.read(&app, |finder, _| assert!(finder.preview.is_some()));
+
+            let preview = read_preview(&tmp_dir.path().join("a.txt"))?;
+
+            assert_eq!(preview.lines().count(), MAX_PREVIEW_LINES);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_read_preview() -> Result<()> {
+        let tmp_dir = temp_tree(json!({
+            "long.txt": format!("a{}", "é".repeat(MAX_PREVIEW_BYTES as usize)),
+            "binary": "a\u{0}b"
+        }));
+
+        std::fs::write(tmp_dir.path().join("invalid"), b"a\xffb")?;
+
+        // uma linha enorme é cortada no limite, sem partir o último caractere
+        let preview = read_preview(&tmp_dir.path().join("long.txt"))?;
+
+        assert_eq!(preview.len(), MAX_PREVIEW_BYTES as usize);
+        assert!(preview.ends_with("é\n"));
+
+        assert!(read_preview(&tmp_dir.path().join("binary")).is_err());
+        assert!(read_preview(&tmp_dir.path().join("invalid")).is_err());
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,227 @@
+use crate::config;
+use anyhow::{anyhow, Context, Result};
+
+use gpui::{
+    keymap::{Binding, Keymap},
+    App
+};
+
+use serde_json::Value;
+use smol::prelude::*;
+use std::{
+    io,
+    path::{Path, PathBuf}
+};
+
+// o keymap do usuário é uma lista de blocos, cada um com um predicado de contexto opcional:
+//
+// [
+//     {
+//         "context": "BufferView && !menu",
+//         "bindings": {
+//             "cmd-d": "buffer:select_next",
+//             "cmd-1": ["pane:activate_item", 0]
+//         }
+//     }
+// ]
+//
+// os bindings do usuário são adicionados depois dos padrões e por isso têm precedência
+
+pub fn path() -> Option<PathBuf> {
+    config::dir().map(|dir| dir.join("keymap.json"))
+}
+
+/// carrega o keymap do usuário e o recarrega sempre que o arquivo muda
+///
+/// deve ser chamado depois que os outros módulos registraram seus bindings, que passam a ser
+/// a base sobre a qual o keymap do usuário é aplicado
+pub fn init(app: &mut App) {
+    if let Some(path) = path() {
+        watch(app, path);
+    }
+}
+
+fn watch(app: &mut App, path: PathBuf) {
+    let default_keymap = app.update(|ctx| ctx.keymap().clone());
+
+    let mut changes = config::changes(path.clone());
+
+    reload(app, &default_keymap, &path, config::read(&path));
+
+    let mut app = app.clone();
+    let foreground = app.update(|ctx| ctx.foreground_executor());
+
+    foreground
+        .spawn(async move {
+            while let Some(source) = changes.next().await {
+                reload(&mut app, &default_keymap, &path, source);
+            }
+        }).detach();
+}
+
+fn reload(
+    app: &mut App,
+
+    default_keymap: &Keymap,
+    path: &Path,
+    source: io::Result<Option<String>>
+) {
+    let user_bindings = match source
+        .map_err(Into::into)
+        .and_then(|source| source.map_or(Ok(Vec::new()), |source| parse(&source)))
+    {
+        Ok(bindings) => bindings,
+
+        Err(error) => {
+            // mantém o keymap atual até que o arquivo seja corrigido
+            log::error!("erro ao carregar o keymap {:?}: {:#}", path, error);
+
+            return;
+        }
+    };
+
+    log::info!("keymap carregado com {} bindings do usuário", user_bindings.len());
+
+    let keymap = Keymap::new(
+        default_keymap
+            .bindings()
+            .iter()
+            .cloned()
+            .chain(user_bindings)
+            .collect()
+    );
+
+    app.update(|ctx| ctx.set_keymap(keymap));
+}
+
+pub fn parse(source: &str) -> Result<Vec<Binding>> {
+    let blocks = match serde_json::from_str(source)? {
+        Value::Array(blocks) => blocks,
+
+        _ => return Err(anyhow!("o keymap deve ser uma lista de blocos"))
+    };
+
+    let mut bindings = Vec::new();
+
+    for (ix, block) in blocks.iter().enumerate() {
+        let block = block
+            .as_object()
+            .ok_or_else(|| anyhow!("o bloco {} não é um objeto", ix))?;
+
+        let context = match block.get("context") {
+            None | Some(Value::Null) => None,
+
+            Some(Value::String(context)) => Some(context.as_str()),
+
+            Some(_) => return Err(anyhow!("o contexto do bloco {} deve ser uma string", ix))
+        };
+
+        let block_bindings = block
+            .get("bindings")
+            .and_then(Value::as_object)
+            .ok_or_else(|| anyhow!("o bloco {} não possui um objeto `bindings`", ix))?;
+
+        for (keystrokes, action) in block_bindings {
+            let binding = parse_binding(keystrokes, action, context)
+                .with_context(|| format!("binding inválido `{}` no bloco {}", keystrokes, ix))?;
+
+            bindings.push(binding);
+        }
+    }
+
+    Ok(bindings)
+}
+
+fn parse_binding(keystrokes: &str, action: &Value, context: Option<&str>) -> Result<Binding> {
+    match action {
+        Value::String(action) => Binding::load(keystrokes, action.as_str(), context),
+
+        Value::Array(action) => match action.as_slice() {
+            [Value::String(name)] => Binding::load(keystrokes, name.as_str(), context),
+
+            [Value::String(name), arg] => {
+                let binding = Binding::load(keystrokes, name.as_str(), context)?;
+
+                match arg {
+                    Value::Null => Ok(binding),
+                    Value::Bool(arg) => Ok(binding.with_arg(*arg)),
+                    Value::String(arg) => Ok(binding.with_arg(arg.clone())),
+
+                    Value::Number(arg) => arg
+                        .as_u64()
+                        .map(|arg| binding.with_arg(arg as usize))
+                        .ok_or_else(|| anyhow!("argumento numérico não suportado `{}`", arg)),
+
+                    _ => Err(anyhow!("argumento não suportado `{}`", arg))
+                }
+            }
+
+            _ => Err(anyhow!("a ação deve ser `\"nome\"` ou `[\"nome\", argumento]`"))
+        },
+
+        _ => Err(anyhow!("a ação deve ser `\"nome\"` ou `[\"nome\", argumento]`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::keymap::Keystroke;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let bindings = parse(
+            r#"[
+                {
+                    "bindings": {
+                        "cmd-shift-p": "command_palette:toggle"
+                    }
+                },
+
+                {
+                    "context": "Pane && !menu",
+
+                    "bindings": {
+                        "cmd-1": ["pane:activate_item", 0],
+                        "cmd-k cmd-w": ["pane:close_active_item"]
+                    }
+                }
+            ]"#
+        )?;
+
+        let summary = bindings
+            .iter()
+            .map(|binding| (binding.keystrokes().to_vec(), binding.action().to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+
+            vec![
+                (vec![Keystroke::parse("cmd-shift-p")?], "command_palette:toggle".to_string()),
+                (vec![Keystroke::parse("cmd-1")?], "pane:activate_item".to_string()),
+
+                (
+                    vec![Keystroke::parse("cmd-k")?, Keystroke::parse("cmd-w")?],
+                    "pane:close_active_item".to_string()
+                )
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| format!("{:#}", parse(source).err().unwrap());
+
+        assert!(error("{").contains("EOF"));
+        assert!(error(r#"{"bindings": {}}"#).contains("lista de blocos"));
+        assert!(error(r#"[{"context": "a"}]"#).contains("bindings"));
+
+        assert!(error(r#"[{"bindings": {"cmd": "a"}}]"#).contains("binding inválido `cmd` no bloco 0"));
+        assert!(error(r#"[{"context": "a ||", "bindings": {"x": "a"}}]"#).contains("predicado de contexto inválido"));
+        assert!(error(r#"[{"bindings": {"x": ["a", [1]]}}]"#).contains("argumento não suportado"));
+        assert!(error(r#"[{"bindings": {"x": 1}}]"#).contains("a ação deve ser"));
+    }
+}
@@ -1,11 +1,19 @@
 pub mod assets;
+pub mod buffer_switcher;
+pub mod command_palette;
+mod config;
+pub mod diff_view;
 pub mod editor;
+mod editor_config;
 pub mod file_finder;
+pub mod keymap_file;
 mod operation_queue;
+pub mod project_panel;
 pub mod settings;
 mod sum_tree;
 #[cfg(test)]
 mod test;
+pub mod theme;
 mod time;
 mod timer;
 mod util;
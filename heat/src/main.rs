@@ -5,25 +5,34 @@ use simplelog::SimpleLogger;
 use std::{fs, path::PathBuf};
 
 use heat::{
-    assets, editor, file_finder, settings,
+    assets, buffer_switcher, command_palette, diff_view, editor, file_finder, keymap_file, project_panel, settings,
 
-    workspace::{self, OpenParams}
+    workspace::{self, session, OpenParams}
 };
 
 fn main() {
     init_logger();
 
     let app = gpui::App::new(assets::Assets).unwrap();
-    let (_, settings_rx) = settings::channel(&app.fonts()).unwrap();
+    let (settings_tx, settings_rx) = settings::channel(&app.fonts()).unwrap();
 
     {
         let mut app = app.clone();
+        let mut resign_app = app.clone();
 
         platform::runner()
             .on_finish_launching(move || {
+                settings::init(&mut app, settings_tx);
+
                 workspace::init(&mut app);
                 editor::init(&mut app);
                 file_finder::init(&mut app);
+                command_palette::init(&mut app);
+                buffer_switcher::init(&mut app);
+                diff_view::init(&mut app);
+                project_panel::init(&mut app);
+                keymap_file::init(&mut app);
+                session::init(&mut app);
 
                 if stdout_is_a_pty() {
                     app.platform().activate(true);
@@ -41,8 +50,13 @@ fn main() {
                             settings: settings_rx
                         }
                     );
+                } else {
+                    // sem argumentos, reabre as janelas da última sessão
+                    session::restore(&mut app, settings_rx);
                 }
-            }).run();
+            })
+            .on_resign_active(move || session::save(&mut resign_app))
+            .run();
     }
 }
 
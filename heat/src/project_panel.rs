@@ -0,0 +1,903 @@
+use crate::{
+    editor::BufferView,
+    settings::Settings,
+    watch,
+    workspace::{Workspace, WorkspaceView},
+    worktree::{GitStatus, Worktree}
+};
+
+use anyhow::{anyhow, Result};
+
+use gpui::{
+    elements::*,
+    keymap::{self, Binding},
+    App, AppContext, Axis, Border, Entity, ModelHandle, View, ViewContext, ViewHandle, WeakViewHandle
+};
+
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    path::Path
+};
+
+const INDENT_WIDTH: f32 = 12.0;
+
+// a árvore é expandida sob demanda: apenas os filhos dos diretórios expandidos são percorridos
+// ao reconstruir as linhas visíveis, e a raiz de cada árvore de trabalho começa expandida
+
+pub struct ProjectPanel {
+    handle: WeakViewHandle<Self>,
+    settings: watch::Receiver<Settings>,
+    workspace: ModelHandle<Workspace>,
+    expanded_dirs: HashMap<usize, HashSet<usize>>,
+    visible_entries: Vec<VisibleEntry>,
+    selected: Option<(usize, usize)>,
+    list_state: UniformListState,
+    filename_editor: ViewHandle<BufferView>,
+    edit_state: Option<EditState>,
+    pending_delete: Option<(usize, usize)>
+}
+
+/// operação que aguarda um nome no editor do painel
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EditState {
+    Create {
+        tree_id: usize,
+        parent_id: usize,
+        is_dir: bool
+    },
+
+    Rename {
+        tree_id: usize,
+        entry_id: usize
+    },
+
+    /// o destino é um caminho relativo à raiz da árvore de trabalho
+    Move {
+        tree_id: usize,
+        entry_id: usize
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct VisibleEntry {
+    tree_id: usize,
+    entry_id: usize,
+    depth: usize,
+    name: String,
+
+    is_dir: bool,
+    is_expanded: bool,
+    is_ignored: bool,
+    git_status: Option<GitStatus>
+}
+
+pub fn init(app: &mut App) {
+    app.add_action("project_panel:toggle", ProjectPanel::toggle);
+    app.add_action("project_panel:reveal_active_file", ProjectPanel::reveal_active_file);
+
+    app.add_action("project_panel:select_prev", ProjectPanel::select_prev);
+    app.add_action("project_panel:select_next", ProjectPanel::select_next);
+    app.add_action("project_panel:expand", ProjectPanel::expand);
+    app.add_action("project_panel:collapse", ProjectPanel::collapse);
+    app.add_action("project_panel:confirm", ProjectPanel::confirm);
+    app.add_action("project_panel:click", ProjectPanel::click);
+    app.add_action("project_panel:cancel", ProjectPanel::cancel);
+    app.add_action("uniform_list:scroll", ProjectPanel::scroll);
+
+    app.add_action("project_panel:new_file", ProjectPanel::new_file);
+    app.add_action("project_panel:new_directory", ProjectPanel::new_directory);
+    app.add_action("project_panel:rename", ProjectPanel::rename);
+    app.add_action("project_panel:move", ProjectPanel::move_entry);
+    app.add_action("project_panel:duplicate", ProjectPanel::duplicate);
+    app.add_action("project_panel:delete", ProjectPanel::delete);
+    app.add_action("project_panel:delete_permanently", ProjectPanel::delete_permanently);
+
+    app.add_bindings(vec![
+        Binding::new("cmd-b", "project_panel:toggle", None),
+        Binding::new("shift-cmd-e", "project_panel:reveal_active_file", None),
+
+        // enquanto o editor de nomes está aberto, apenas confirmar e cancelar chegam ao painel,
+        // e as demais teclas ficam com o editor
+        Binding::new("up", "project_panel:select_prev", Some("ProjectPanel && !editing")),
+        Binding::new("down", "project_panel:select_next", Some("ProjectPanel && !editing")),
+        Binding::new("right", "project_panel:expand", Some("ProjectPanel && !editing")),
+        Binding::new("left", "project_panel:collapse", Some("ProjectPanel && !editing")),
+        Binding::new("enter", "project_panel:confirm", Some("ProjectPanel")),
+        Binding::new("escape", "project_panel:cancel", Some("ProjectPanel")),
+
+        Binding::new("cmd-n", "project_panel:new_file", Some("ProjectPanel && !editing")),
+        Binding::new("alt-cmd-n", "project_panel:new_directory", Some("ProjectPanel && !editing")),
+        Binding::new("alt-cmd-r", "project_panel:rename", Some("ProjectPanel && !editing")),
+        Binding::new("alt-cmd-m", "project_panel:move", Some("ProjectPanel && !editing")),
+        Binding::new("cmd-d", "project_panel:duplicate", Some("ProjectPanel && !editing")),
+        Binding::new("cmd-backspace", "project_panel:delete", Some("ProjectPanel && !editing")),
+        Binding::new("alt-cmd-backspace", "project_panel:delete_permanently", Some("ProjectPanel && !editing"))
+    ]);
+}
+
+pub enum Event {
+    Open(usize, usize)
+}
+
+impl Entity for ProjectPanel {
+    type Event = Event;
+}
+
+impl View for ProjectPanel {
+    fn ui_name() -> &'static str {
+        "ProjectPanel"
+    }
+
+    fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        let theme = smol::block_on(self.settings.read()).theme.clone();
+        let handle = self.handle.clone();
+
+        let list = UniformList::new(
+            self.list_state.clone(),
+            self.visible_entries.len(),
+
+            move |mut range, items, app| {
+                let panel = handle.upgrade(app).unwrap();
+                let panel = panel.as_ref(app);
+
+                let start = range.start;
+                let selected = panel.selected_index();
+
+                range.end = cmp::min(range.end, panel.visible_entries.len());
+
+                items.extend(
+                    panel.visible_entries[range]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, entry)| panel.render_entry(entry, selected == Some(start + i)))
+                );
+            }
+        );
+
+        let mut content = Flex::new(Axis::Vertical).with_child(Expanded::new(1.0, list.boxed()).boxed());
+
+        if let Some(prompt) = self.render_prompt() {
+            content = content.with_child(prompt);
+        }
+
+        Container::new(content.boxed())
+            .with_background_color(theme.ui.list_background)
+            .with_border(Border::right(1.0, theme.ui.border))
+            .boxed()
+    }
+
+    fn keymap_context(&self, _: &AppContext) -> keymap::Context {
+        let mut ctx = Self::default_keymap_context();
+
+        if self.edit_state.is_some() {
+            ctx.set.insert("editing".into());
+        }
+
+        ctx
+    }
+}
+
+impl ProjectPanel {
+    pub fn new(
+        settings: watch::Receiver<Settings>,
+        workspace: ModelHandle<Workspace>,
+        ctx: &mut ViewContext<Self>
+    ) -> Self {
+        ctx.observe(&workspace, Self::workspace_updated);
+
+        settings.notify_view_on_change(ctx);
+
+        let filename_editor = ctx.add_view(|ctx| BufferView::single_line(settings.clone(), ctx));
+
+        let mut panel = Self {
+            handle: ctx.handle(),
+            settings,
+            workspace,
+            expanded_dirs: HashMap::new(),
+            visible_entries: Vec::new(),
+            selected: None,
+            list_state: UniformListState::new(),
+            filename_editor,
+            edit_state: None,
+            pending_delete: None
+        };
+
+        panel.update_visible_entries(ctx.app());
+
+        panel
+    }
+
+    fn render_entry(&self, entry: &VisibleEntry, selected: bool) -> Box<dyn Element> {
+        let settings = smol::block_on(self.settings.read());
+        let theme = &settings.theme;
+
+        let disclosure = match (entry.is_dir, entry.is_expanded) {
+            (true, true) => "▾ ",
+            (true, false) => "▸ ",
+
+            (false, _) => "  "
+        };
+
+        let color = if entry.is_ignored {
+            theme.ui.ignored_text
+        } else if let Some(status) = entry.git_status {
+            theme.ui.git_status(status)
+        } else {
+            theme.ui.text
+        };
+
+        let mut container = Container::new(
+            Label::new(
+                format!("{}{}", disclosure, entry.name),
+
+                settings.ui_font_family,
+                settings.ui_font_size
+            ).with_default_color(color).boxed()
+        )
+
+        .with_uniform_padding(4.0)
+        .with_padding_left(4.0 + entry.depth as f32 * INDENT_WIDTH);
+
+        if selected {
+            container = container.with_background_color(theme.ui.selected_item_background);
+        }
+
+        let entry = (entry.tree_id, entry.entry_id);
+
+        EventHandler::new(container.boxed())
+            .on_mouse_down(move |ctx, _| {
+                ctx.dispatch_action("project_panel:click", entry);
+
+                true
+            }).boxed()
+    }
+
+    /// linha no pé do painel com o editor de nomes ou a confirmação de exclusão
+    fn render_prompt(&self) -> Option<Box<dyn Element>> {
+        let settings = smol::block_on(self.settings.read());
+
+        let message = match (self.edit_state, self.pending_delete) {
+            (Some(EditState::Create { is_dir: false, .. }), _) => "novo arquivo:".to_string(),
+            (Some(EditState::Create { is_dir: true, .. }), _) => "novo diretório:".to_string(),
+            (Some(EditState::Rename { .. }), _) => "renomear para:".to_string(),
+            (Some(EditState::Move { .. }), _) => "mover para:".to_string(),
+
+            (None, Some(entry)) => format!(
+                "excluir {} permanentemente? enter confirma, escape cancela",
+                self.entry_name(entry).unwrap_or_default()
+            ),
+
+            (None, None) => return None
+        };
+
+        let mut prompt = Flex::new(Axis::Vertical).with_child(
+            Label::new(message, settings.ui_font_family, settings.ui_font_size)
+                .with_default_color(settings.theme.ui.text)
+                .boxed()
+        );
+
+        if self.edit_state.is_some() {
+            prompt = prompt.with_child(ChildView::new(self.filename_editor.id()).boxed());
+        }
+
+        Some(
+            Container::new(prompt.boxed())
+                .with_uniform_padding(6.0)
+                .with_border(Border::top(1.0, settings.theme.ui.border))
+                .boxed()
+        )
+    }
+
+    fn toggle(workspace_view: &mut WorkspaceView, _: &(), ctx: &mut ViewContext<WorkspaceView>) {
+        workspace_view.toggle_sidebar(ctx, |ctx, workspace_view| {
+            let handle = ctx.add_view(|ctx| {
+                Self::new(
+                    workspace_view.settings.clone(),
+                    workspace_view.workspace.clone(),
+
+                    ctx
+                )
+            });
+
+            ctx.subscribe_to_view(&handle, Self::on_event);
+
+            handle
+        });
+    }
+
+    /// mostra o painel e seleciona o arquivo do item ativo, expandindo os seus diretórios
+    fn reveal_active_file(
+        workspace_view: &mut WorkspaceView,
+        _: &(),
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        let entry = match workspace_view
+            .active_pane()
+            .as_ref(ctx)
+            .active_item()
+            .and_then(|item| item.entry_id(ctx.app()))
+        {
+            Some(entry) => entry,
+
+            None => return
+        };
+
+        if !workspace_view.sidebar().map_or(false, |sidebar| sidebar.is::<Self>()) {
+            Self::toggle(workspace_view, &(), ctx);
+        }
+
+        if let Some(panel) = workspace_view.sidebar().cloned().and_then(|sidebar| sidebar.downcast::<Self>()) {
+            panel.update(ctx, |panel, ctx| panel.reveal(entry, ctx));
+        }
+    }
+
+    fn on_event(
+        workspace_view: &mut WorkspaceView,
+        _: ViewHandle<ProjectPanel>,
+        event: &Event,
+        ctx: &mut ViewContext<WorkspaceView>
+    ) {
+        match event {
+            Event::Open(tree_id, entry_id) => workspace_view.open_entry((*tree_id, *entry_id), ctx)
+        }
+    }
+
+    fn workspace_updated(&mut self, _: ModelHandle<Workspace>, ctx: &mut ViewContext<Self>) {
+        self.update_visible_entries(ctx.app());
+
+        ctx.notify();
+    }
+
+    pub fn reveal(&mut self, (tree_id, entry_id): (usize, usize), ctx: &mut ViewContext<Self>) {
+        if let Some(tree) = self.worktree(tree_id, ctx.app()).cloned() {
+            let expanded_dirs = self.expanded_dirs.entry(tree_id).or_default();
+            let mut parent = tree.entry(entry_id).and_then(|entry| entry.parent());
+
+            while let Some(parent_id) = parent {
+                expanded_dirs.insert(parent_id);
+
+                parent = tree.entry(parent_id).and_then(|entry| entry.parent());
+            }
+
+            self.selected = Some((tree_id, entry_id));
+
+            self.update_visible_entries(ctx.app());
+            self.scroll_to_selected();
+
+            ctx.notify();
+        }
+    }
+
+    fn select_prev(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let index = match self.selected_index() {
+            Some(index) => index.saturating_sub(1),
+
+            None => 0
+        };
+
+        self.select_index(index, ctx);
+    }
+
+    fn select_next(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        let index = match self.selected_index() {
+            Some(index) => index + 1,
+
+            None => 0
+        };
+
+        self.select_index(index, ctx);
+    }
+
+    /// expande o diretório selecionado ou, se ele já está expandido, seleciona o primeiro filho
+    fn expand(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(index) = self.selected_index() {
+            let entry = self.visible_entries[index].clone();
+
+            if !entry.is_dir {
+                return;
+            }
+
+            if entry.is_expanded {
+                self.select_index(index + 1, ctx);
+            } else {
+                self.set_expanded((entry.tree_id, entry.entry_id), true, ctx);
+            }
+        }
+    }
+
+    /// recolhe o diretório selecionado ou, se ele não está expandido, seleciona o pai
+    fn collapse(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(index) = self.selected_index() {
+            let entry = self.visible_entries[index].clone();
+
+            if entry.is_expanded {
+                self.set_expanded((entry.tree_id, entry.entry_id), false, ctx);
+            } else if let Some(parent_id) = self
+                .worktree(entry.tree_id, ctx.app())
+                .and_then(|tree| tree.entry(entry.entry_id))
+                .and_then(|entry| entry.parent())
+            {
+                self.selected = Some((entry.tree_id, parent_id));
+                self.scroll_to_selected();
+
+                ctx.notify();
+            }
+        }
+    }
+
+    fn confirm(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(edit_state) = self.edit_state {
+            let name = self.filename_editor.as_ref(ctx).text(ctx.app());
+
+            match self.apply_edit(edit_state, name.trim(), ctx) {
+                Ok(entry) => {
+                    self.edit_state = None;
+
+                    ctx.focus_self();
+
+                    self.reveal(entry, ctx);
+                }
+
+                // o editor continua aberto para que o nome possa ser corrigido
+                Err(error) => log::error!("{:#}", error)
+            }
+
+            return;
+        }
+
+        if let Some((tree_id, entry_id)) = self.pending_delete.take() {
+            self.delete_entry((tree_id, entry_id), true, ctx);
+
+            return;
+        }
+
+        if let Some(index) = self.selected_index() {
+            let entry = self.visible_entries[index].clone();
+
+            if entry.is_dir {
+                self.set_expanded((entry.tree_id, entry.entry_id), !entry.is_expanded, ctx);
+            } else {
+                ctx.emit(Event::Open(entry.tree_id, entry.entry_id));
+            }
+        }
+    }
+
+    fn click(&mut self, entry: &(usize, usize), ctx: &mut ViewContext<Self>) {
+        self.selected = Some(*entry);
+
+        ctx.focus_self();
+
+        self.confirm(&(), ctx);
+
+        ctx.notify();
+    }
+
+    fn scroll(&mut self, _: &f32, ctx: &mut ViewContext<Self>) {
+        ctx.notify();
+    }
+
+    fn cancel(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.edit_state.take().is_some() || self.pending_delete.take().is_some() {
+            ctx.focus_self();
+            ctx.notify();
+        }
+    }
+
+    fn new_file(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.start_create(false, ctx);
+    }
+
+    fn new_directory(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.start_create(true, ctx);
+    }
+
+    /// cria dentro do diretório selecionado ou no diretório do arquivo selecionado
+    fn start_create(&mut self, is_dir: bool, ctx: &mut ViewContext<Self>) {
+        let (tree_id, entry_id) = match self.selected.or_else(|| {
+            self.visible_entries.first().map(|entry| (entry.tree_id, entry.entry_id))
+        }) {
+            Some(entry) => entry,
+
+            None => return
+        };
+
+        let parent_id = match self.worktree(tree_id, ctx.app()).and_then(|tree| tree.entry(entry_id)) {
+            Some(entry) if entry.is_dir() => entry_id,
+            Some(entry) => entry.parent().unwrap_or(0),
+
+            None => return
+        };
+
+        self.start_edit(EditState::Create { tree_id, parent_id, is_dir }, String::new(), ctx);
+    }
+
+    fn rename(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some((tree_id, entry_id)) = self.selected {
+            if let Some(name) = self.entry_name((tree_id, entry_id)) {
+                self.start_edit(EditState::Rename { tree_id, entry_id }, name, ctx);
+            }
+        }
+    }
+
+    fn move_entry(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some((tree_id, entry_id)) = self.selected {
+            if let Some(tree) = self.worktree(tree_id, ctx.app()) {
+                // o caminho da entrada começa com o nome da raiz, que não faz parte do destino
+                let path = match tree.entry_path(entry_id) {
+                    Ok(path) => path.iter().skip(1).collect::<std::path::PathBuf>(),
+
+                    Err(_) => return
+                };
+
+                let path = path.to_string_lossy().to_string();
+
+                self.start_edit(EditState::Move { tree_id, entry_id }, path, ctx);
+            }
+        }
+    }
+
+    fn start_edit(&mut self, edit_state: EditState, text: String, ctx: &mut ViewContext<Self>) {
+        self.edit_state = Some(edit_state);
+        self.pending_delete = None;
+
+        self.filename_editor.update(ctx, |editor, ctx| editor.set_text(&text, ctx));
+
+        ctx.focus(&self.filename_editor);
+        ctx.notify();
+    }
+
+    fn apply_edit(&mut self, edit_state: EditState, name: &str, ctx: &mut ViewContext<Self>) -> Result<(usize, usize)> {
+        let tree_id = match edit_state {
+            EditState::Create { tree_id, .. } | EditState::Rename { tree_id, .. } | EditState::Move { tree_id, .. } => tree_id
+        };
+
+        let handle = self
+            .workspace
+            .as_ref(ctx)
+            .worktrees()
+            .get(&tree_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("a árvore de trabalho não existe mais"))?;
+
+        match edit_state {
+            EditState::Create { parent_id, is_dir, .. } => {
+                let entry_id = handle.update(ctx, |tree, ctx| tree.create_entry(parent_id, OsStr::new(name), is_dir, ctx))?;
+
+                self.expanded_dirs.entry(tree_id).or_default().insert(parent_id);
+
+                Ok((tree_id, entry_id))
+            }
+
+            EditState::Rename { entry_id, .. } => {
+                handle.update(ctx, |tree, ctx| tree.rename_entry(entry_id, OsStr::new(name), ctx))?;
+
+                Ok((tree_id, entry_id))
+            }
+
+            EditState::Move { entry_id, .. } => {
+                let tree = handle.as_ref(ctx);
+                let path = Path::new(name);
+
+                let file_name = path
+                    .file_name()
+                    .ok_or_else(|| anyhow!("destino inválido {:?}", name))?
+                    .to_owned();
+
+                let parent_path = tree.path().join(path.parent().unwrap_or(Path::new("")));
+
+                let parent_id = tree
+                    .entry_for_path(&parent_path)
+                    .ok_or_else(|| anyhow!("o diretório {:?} não existe", parent_path))?;
+
+                handle.update(ctx, |tree, ctx| tree.move_entry(entry_id, parent_id, &file_name, ctx))?;
+
+                Ok((tree_id, entry_id))
+            }
+        }
+    }
+
+    fn duplicate(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some((tree_id, entry_id)) = self.selected {
+            if let Some(handle) = self.workspace.as_ref(ctx).worktrees().get(&tree_id).cloned() {
+                match handle.update(ctx, |tree, ctx| tree.duplicate_entry(entry_id, ctx)) {
+                    Ok(new_entry_id) => self.reveal((tree_id, new_entry_id), ctx),
+
+                    Err(error) => log::error!("erro ao duplicar a entrada: {:#}", error)
+                }
+            }
+        }
+    }
+
+    /// move a entrada selecionada para a lixeira
+    fn delete(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.selected {
+            self.delete_entry(entry, false, ctx);
+        }
+    }
+
+    /// pede confirmação antes de excluir a entrada selecionada sem passar pela lixeira
+    fn delete_permanently(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.selected.is_some() {
+            self.pending_delete = self.selected;
+            self.edit_state = None;
+
+            ctx.focus_self();
+            ctx.notify();
+        }
+    }
+
+    fn delete_entry(&mut self, (tree_id, entry_id): (usize, usize), permanently: bool, ctx: &mut ViewContext<Self>) {
+        let handle = match self.workspace.as_ref(ctx).worktrees().get(&tree_id).cloned() {
+            Some(handle) => handle,
+
+            None => return
+        };
+
+        // a seleção passa para a linha anterior, já que a entrada sai da lista
+        let previous = self
+            .selected_index()
+            .and_then(|index| index.checked_sub(1))
+            .and_then(|index| self.visible_entries.get(index))
+            .map(|entry| (entry.tree_id, entry.entry_id));
+
+        match handle.update(ctx, |tree, ctx| tree.delete_entry(entry_id, permanently, ctx)) {
+            Ok(()) => {
+                self.selected = previous;
+
+                ctx.notify();
+            }
+
+            Err(error) => log::error!("erro ao excluir a entrada: {:#}", error)
+        }
+    }
+
+    fn entry_name(&self, (tree_id, entry_id): (usize, usize)) -> Option<String> {
+        self.visible_entries
+            .iter()
+            .find(|entry| entry.tree_id == tree_id && entry.entry_id == entry_id)
+            .map(|entry| entry.name.clone())
+    }
+
+    fn set_expanded(&mut self, (tree_id, entry_id): (usize, usize), expanded: bool, ctx: &mut ViewContext<Self>) {
+        let expanded_dirs = self.expanded_dirs.entry(tree_id).or_default();
+
+        if expanded {
+            expanded_dirs.insert(entry_id);
+        } else {
+            expanded_dirs.remove(&entry_id);
+        }
+
+        self.update_visible_entries(ctx.app());
+
+        ctx.notify();
+    }
+
+    fn select_index(&mut self, index: usize, ctx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.visible_entries.get(index) {
+            self.selected = Some((entry.tree_id, entry.entry_id));
+            self.scroll_to_selected();
+
+            ctx.notify();
+        }
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected = self.selected?;
+
+        self.visible_entries
+            .iter()
+            .position(|entry| (entry.tree_id, entry.entry_id) == selected)
+    }
+
+    fn scroll_to_selected(&self) {
+        if let Some(index) = self.selected_index() {
+            self.list_state.scroll_to(index);
+        }
+    }
+
+    fn update_visible_entries(&mut self, app: &AppContext) {
+        let mut worktrees = self.workspace.as_ref(app).worktrees().iter().collect::<Vec<_>>();
+
+        worktrees.sort_by_key(|worktree| worktree.as_ref(app).path());
+
+        self.visible_entries.clear();
+
+        for worktree in worktrees {
+            let tree = worktree.as_ref(app);
+
+            if tree.entry_count() == 0 {
+                continue;
+            }
+
+            let expanded_dirs = self
+                .expanded_dirs
+                .entry(worktree.id())
+                .or_insert_with(|| Some(0).into_iter().collect());
+
+            push_visible_entries(tree, worktree.id(), 0, 0, expanded_dirs, &mut self.visible_entries);
+        }
+    }
+
+    fn worktree<'a>(&'a self, tree_id: usize, app: &'a AppContext) -> Option<&'a Worktree> {
+        self.workspace
+            .as_ref(app)
+            .worktrees()
+            .get(&tree_id)
+            .map(|worktree| worktree.as_ref(app))
+    }
+}
+
+fn push_visible_entries(
+    tree: &Worktree,
+    tree_id: usize,
+    entry_id: usize,
+    depth: usize,
+
+    expanded_dirs: &HashSet<usize>,
+    visible_entries: &mut Vec<VisibleEntry>
+) {
+    let entry = match tree.entry(entry_id) {
+        Some(entry) => entry,
+
+        None => return
+    };
+
+    let is_expanded = entry.is_dir() && expanded_dirs.contains(&entry_id);
+
+    visible_entries.push(VisibleEntry {
+        tree_id,
+        entry_id,
+        depth,
+        name: entry.name().to_string_lossy().into(),
+
+        is_dir: entry.is_dir(),
+        is_expanded,
+        is_ignored: entry.is_ignored(),
+        git_status: tree.git_status(entry_id)
+    });
+
+    if is_expanded {
+        for child_id in tree.children(entry_id) {
+            push_visible_entries(tree, tree_id, child_id, depth + 1, expanded_dirs, visible_entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor, settings, test::temp_tree};
+    use anyhow::Result;
+    use gpui::{keymap::Keystroke, FontCache};
+    use serde_json::json;
+
+    #[test]
+    fn test_navigation() -> Result<()> {
+        App::test((), |mut app| async move {
+            init(&mut app);
+
+            let dir = temp_tree(json!({
+                "root": {
+                    "b.txt": "",
+
+                    "a": {
+                        "c": {
+                            "d.txt": ""
+                        }
+                    },
+
+                    "a.txt": ""
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().join("root")], ctx));
+
+            app.finish_pending_tasks().await; // abre e popula a árvore de trabalho
+
+            let (window_id, panel) = app.add_window(|ctx| ProjectPanel::new(settings, workspace.clone(), ctx));
+
+            let lines = |app: &App| {
+                panel.read(app, |panel, _| {
+                    panel.visible_entries.iter().map(|entry| {
+                        let selected = panel.selected == Some((entry.tree_id, entry.entry_id));
+
+                        format!(
+                            "{}{}{}{}",
+                            "  ".repeat(entry.depth),
+                            entry.name,
+                            if entry.is_dir { "/" } else { "" },
+                            if selected { " <==" } else { "" }
+                        )
+                    }).collect::<Vec<_>>()
+                })
+            };
+
+            // diretórios primeiro, em ordem alfabética, e apenas a raiz expandida
+            assert_eq!(lines(&app), vec!["root/", "  a/", "  a.txt", "  b.txt"]);
+
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:select_next", ());
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:select_next", ());
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:expand", ());
+
+            assert_eq!(lines(&app), vec!["root/", "  a/ <==", "    c/", "  a.txt", "  b.txt"]);
+
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:expand", ());
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:collapse", ());
+
+            assert_eq!(lines(&app), vec!["root/", "  a/ <==", "    c/", "  a.txt", "  b.txt"]);
+
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:collapse", ());
+
+            assert_eq!(lines(&app), vec!["root/", "  a/ <==", "  a.txt", "  b.txt"]);
+
+            // revelar um arquivo expande todos os seus diretórios
+            let entry = workspace.read(&app, |workspace, app| {
+                workspace.entry_for_path(&dir.path().join("root/a/c/d.txt"), app).unwrap()
+            });
+
+            panel.update(&mut app, |panel, ctx| panel.reveal(entry, ctx));
+
+            assert_eq!(
+                lines(&app),
+                vec!["root/", "  a/", "    c/", "      d.txt <==", "  a.txt", "  b.txt"]
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_keystrokes_while_renaming() -> Result<()> {
+        App::test((), |mut app| async move {
+            editor::init(&mut app);
+            init(&mut app);
+
+            let dir = temp_tree(json!({
+                "root": {
+                    "a.txt": "",
+                    "b.txt": ""
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().join("root")], ctx));
+
+            app.finish_pending_tasks().await; // abre e popula a árvore de trabalho
+
+            let (window_id, panel) = app.add_window(|ctx| ProjectPanel::new(settings, workspace.clone(), ctx));
+
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:select_next", ());
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:select_next", ());
+            app.dispatch_action(window_id, vec![panel.id()], "project_panel:rename", ());
+
+            let (selected, entries, editor) = panel.read(&app, |panel, _| {
+                (panel.selected, panel.visible_entries.clone(), panel.filename_editor.clone())
+            });
+
+            assert_eq!(entries[1].name, "a.txt");
+            assert_eq!(selected, Some((entries[1].tree_id, entries[1].entry_id)));
+
+            let responder_chain = vec![panel.id(), editor.id()];
+
+            // as setas movem o cursor do editor e os atalhos de entradas não agem sobre o painel
+            for keystroke in &["up", "down", "left", "right", "cmd-n", "alt-cmd-r", "cmd-d", "cmd-backspace"] {
+                app.dispatch_keystroke(window_id, responder_chain.clone(), &Keystroke::parse(keystroke)?)?;
+
+                panel.read(&app, |panel, _| {
+                    assert_eq!(panel.selected, selected, "{}", keystroke);
+                    assert_eq!(panel.visible_entries, entries, "{}", keystroke);
+                    assert!(matches!(panel.edit_state, Some(EditState::Rename { .. })), "{}", keystroke);
+                });
+            }
+
+            app.dispatch_keystroke(window_id, responder_chain, &Keystroke::parse("escape")?)?;
+
+            panel.read(&app, |panel, _| assert_eq!(panel.edit_state, None));
+
+            Ok(())
+        })
+    }
+}
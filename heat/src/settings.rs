@@ -1,34 +1,515 @@
-use crate::watch;
-use anyhow::Result;
-use gpui::fonts::{FamilyId, FontCache};
+use crate::{
+    config,
+    theme::{self, Theme},
+    watch
+};
+use anyhow::{anyhow, Result};
+
+use gpui::{
+    fonts::{FamilyId, FontCache},
+    App
+};
+
+use serde_json::Value;
+use smol::prelude::*;
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc
+};
+
+// as configurações do editor são resolvidas em camadas, da menos para a mais específica:
+// valores padrões, arquivo do usuário, seção `languages` do arquivo do usuário e, por fim,
+// os arquivos `.editorconfig` encontrados a partir do diretório do buffer
 
 #[derive(Clone)]
 pub struct Settings {
     pub buffer_font_family: FamilyId,
     pub buffer_font_size: f32,
 
-    pub tab_size: usize,
+    pub editor: EditorSettings,
+    pub language_overrides: HashMap<String, EditorSettingsOverrides>,
+
+    pub theme: Arc<Theme>,
 
     pub ui_font_family: FamilyId,
     pub ui_font_size: f32
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IndentStyle {
+    Tab,
+    Space
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r"
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EditorSettings {
+    pub indent_style: IndentStyle,
+    pub indent_size: usize,
+    pub trim_trailing_whitespace: bool,
+    pub insert_final_newline: bool,
+    pub end_of_line: LineEnding
+}
+
+/// uma camada de configurações do editor, onde `None` mantém o valor da camada anterior
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EditorSettingsOverrides {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<usize>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub end_of_line: Option<LineEnding>
+}
+
+impl EditorSettings {
+    pub fn apply(&mut self, overrides: &EditorSettingsOverrides) {
+        if let Some(indent_style) = overrides.indent_style {
+            self.indent_style = indent_style;
+        }
+
+        if let Some(indent_size) = overrides.indent_size {
+            self.indent_size = indent_size;
+        }
+
+        if let Some(trim_trailing_whitespace) = overrides.trim_trailing_whitespace {
+            self.trim_trailing_whitespace = trim_trailing_whitespace;
+        }
+
+        if let Some(insert_final_newline) = overrides.insert_final_newline {
+            self.insert_final_newline = insert_final_newline;
+        }
+
+        if let Some(end_of_line) = overrides.end_of_line {
+            self.end_of_line = end_of_line;
+        }
+    }
+
+    /// texto inserido para avançar um nível de indentação
+    pub fn indent_text(&self) -> String {
+        match self.indent_style {
+            IndentStyle::Tab => "\t".to_string(),
+            IndentStyle::Space => " ".repeat(self.indent_size)
+        }
+    }
+}
+
+impl EditorSettingsOverrides {
+    /// aplica uma chave de configuração do editor, retornando `false` se a chave não for uma delas
+    fn set(&mut self, key: &str, value: &Value) -> Result<bool> {
+        match key {
+            "indent_style" => {
+                self.indent_style = Some(match value.as_str() {
+                    Some("tab") => IndentStyle::Tab,
+                    Some("space") => IndentStyle::Space,
+
+                    _ => return Err(anyhow!("`{}` deve ser \"tab\" ou \"space\"", key))
+                });
+            }
+
+            "indent_size" => {
+                self.indent_size = Some(
+                    value
+                        .as_u64()
+                        .filter(|indent_size| *indent_size > 0)
+                        .ok_or_else(|| anyhow!("`{}` deve ser um inteiro positivo", key))? as usize
+                );
+            }
+
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = Some(boolean(key, value)?),
+            "insert_final_newline" => self.insert_final_newline = Some(boolean(key, value)?),
+
+            "end_of_line" => {
+                self.end_of_line = Some(match value.as_str() {
+                    Some("lf") => LineEnding::Lf,
+                    Some("crlf") => LineEnding::CrLf,
+                    Some("cr") => LineEnding::Cr,
+
+                    _ => return Err(anyhow!("`{}` deve ser \"lf\", \"crlf\" ou \"cr\"", key))
+                });
+            }
+
+            _ => return Ok(false)
+        }
+
+        Ok(true)
+    }
+}
+
 impl Settings {
     pub fn new(font_cache: &FontCache) -> Result<Self> {
         Ok(Self {
             buffer_font_family: font_cache.load_family(&["Fira Code", "Monaco"])?,
             buffer_font_size: 16.0,
 
-            tab_size: 4,
+            editor: EditorSettings {
+                indent_style: IndentStyle::Space,
+                indent_size: 4,
+                trim_trailing_whitespace: false,
+                insert_final_newline: false,
+                end_of_line: LineEnding::Lf
+            },
+
+            language_overrides: HashMap::new(),
+
+            theme: Arc::new(Theme::light()),
 
             ui_font_family: font_cache.load_family(&["SF Pro Display"])?,
             ui_font_size: 12.0
         })
     }
+
+    /// aplica as chaves de um arquivo de configurações sobre estas configurações
+    ///
+    /// as famílias de fontes aceitam um nome ou uma lista de nomes em ordem de preferência, a
+    /// seção `languages` associa o nome de uma linguagem às configurações do editor para ela e
+    /// `theme` é o nome de um tema embutido ou de um arquivo no diretório de temas
+    pub fn with_overrides(&self, source: &str, font_cache: &FontCache) -> Result<Self> {
+        let overrides = match serde_json::from_str(source)? {
+            Value::Object(overrides) => overrides,
+
+            _ => return Err(anyhow!("as configurações devem ser um objeto"))
+        };
+
+        let mut settings = self.clone();
+        let mut editor_overrides = EditorSettingsOverrides::default();
+
+        for (key, value) in &overrides {
+            match key.as_str() {
+                "buffer_font_family" => settings.buffer_font_family = font_family(key, value, font_cache)?,
+                "buffer_font_size" => settings.buffer_font_size = font_size(key, value)?,
+
+                "languages" => settings.language_overrides = language_overrides(value)?,
+
+                "theme" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| anyhow!("`{}` deve ser o nome de um tema", key))?;
+
+                    settings.theme = Arc::new(theme::load(name)?);
+                }
+
+                "ui_font_family" => settings.ui_font_family = font_family(key, value, font_cache)?,
+                "ui_font_size" => settings.ui_font_size = font_size(key, value)?,
+
+                _ => {
+                    if !editor_overrides.set(key, value)? {
+                        return Err(anyhow!("configuração desconhecida `{}`", key));
+                    }
+                }
+            }
+        }
+
+        settings.editor.apply(&editor_overrides);
+
+        Ok(settings)
+    }
+
+    /// configurações efetivas do editor para um buffer
+    ///
+    /// `editor_config` é a camada lida dos arquivos `.editorconfig`, que tem precedência
+    /// sobre as configurações do usuário
+    pub fn editor_settings(
+        &self,
+
+        path: Option<&Path>,
+        editor_config: &EditorSettingsOverrides
+    ) -> EditorSettings {
+        let mut settings = self.editor.clone();
+
+        if let Some(overrides) = path
+            .and_then(language_name)
+            .and_then(|language| self.language_overrides.get(language))
+        {
+            settings.apply(overrides);
+        }
+
+        settings.apply(editor_config);
+
+        settings
+    }
+}
+
+/// nome da linguagem de um arquivo, usado como chave na seção `languages`
+pub fn language_name(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?;
+
+    if file_name == "Makefile" || file_name == "makefile" {
+        return Some("Makefile");
+    }
+
+    let language = match path.extension()?.to_str()? {
+        "rs" => "Rust",
+        "toml" => "TOML",
+        "json" => "JSON",
+        "md" => "Markdown",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "hpp" => "C++",
+        "go" => "Go",
+        "py" => "Python",
+        "js" => "JavaScript",
+        "ts" => "TypeScript",
+        "yml" | "yaml" => "YAML",
+        "mk" => "Makefile",
+
+        _ => return None
+    };
+
+    Some(language)
+}
+
+fn language_overrides(value: &Value) -> Result<HashMap<String, EditorSettingsOverrides>> {
+    let languages = value
+        .as_object()
+        .ok_or_else(|| anyhow!("`languages` deve ser um objeto"))?;
+
+    let mut overrides = HashMap::new();
+
+    for (language, settings) in languages {
+        let settings = settings
+            .as_object()
+            .ok_or_else(|| anyhow!("as configurações de `{}` devem ser um objeto", language))?;
+
+        let mut language_overrides = EditorSettingsOverrides::default();
+
+        for (key, value) in settings {
+            if !language_overrides.set(key, value)? {
+                return Err(anyhow!("configuração desconhecida `{}` em `{}`", key, language));
+            }
+        }
+
+        overrides.insert(language.clone(), language_overrides);
+    }
+
+    Ok(overrides)
+}
+
+fn boolean(key: &str, value: &Value) -> Result<bool> {
+    value
+        .as_bool()
+        .ok_or_else(|| anyhow!("`{}` deve ser um booleano", key))
+}
+
+fn font_family(key: &str, value: &Value, font_cache: &FontCache) -> Result<FamilyId> {
+    let names = match value {
+        Value::String(name) => vec![name.as_str()],
+
+        Value::Array(names) => names
+            .iter()
+            .map(|name| name.as_str().ok_or_else(|| anyhow!("`{}` deve conter apenas nomes", key)))
+            .collect::<Result<Vec<_>>>()?,
+
+        _ => return Err(anyhow!("`{}` deve ser um nome ou uma lista de nomes", key))
+    };
+
+    font_cache.load_family(&names)
+}
+
+fn font_size(key: &str, value: &Value) -> Result<f32> {
+    value
+        .as_f64()
+        .filter(|size| *size > 0.0)
+        .map(|size| size as f32)
+        .ok_or_else(|| anyhow!("`{}` deve ser um número positivo", key))
 }
 
 pub fn channel(
     font_cache: &FontCache
 ) -> Result<(watch::Sender<Settings>, watch::Receiver<Settings>)> {
     Ok(watch::channel(Settings::new(font_cache)?))
-}
\ No newline at end of file
+}
+
+pub fn path() -> Option<PathBuf> {
+    config::dir().map(|dir| dir.join("settings.json"))
+}
+
+/// carrega o arquivo de configurações do usuário e publica uma nova versão no canal
+/// sempre que ele muda
+pub fn init(app: &mut App, mut tx: watch::Sender<Settings>) {
+    let path = match path() {
+        Some(path) => path,
+
+        None => return
+    };
+
+    let font_cache = app.fonts();
+
+    // as chaves removidas do arquivo voltam aos valores padrões
+    let defaults = match Settings::new(&font_cache) {
+        Ok(defaults) => defaults,
+
+        Err(error) => {
+            log::error!("erro ao carregar as configurações padrões: {}", error);
+
+            return;
+        }
+    };
+
+    // o arquivo do tema escolhido também é observado, para que edições nele sejam aplicadas
+    let mut changes = config::changes_with_dependencies(path.clone(), theme_paths);
+
+    smol::block_on(reload(&mut tx, &defaults, &font_cache, &path, config::read(&path)));
+
+    let foreground = app.update(|ctx| ctx.foreground_executor());
+
+    foreground
+        .spawn(async move {
+            while let Some(source) = changes.next().await {
+                reload(&mut tx, &defaults, &font_cache, &path, source).await;
+            }
+        }).detach();
+}
+
+/// arquivo do tema do usuário escolhido no arquivo de configurações
+fn theme_paths(source: &str) -> Vec<PathBuf> {
+    serde_json::from_str::<Value>(source)
+        .ok()
+        .and_then(|settings| theme::path(settings.get("theme")?.as_str()?))
+        .into_iter()
+        .collect()
+}
+
+async fn reload(
+    tx: &mut watch::Sender<Settings>,
+
+    defaults: &Settings,
+    font_cache: &Arc<FontCache>,
+    path: &Path,
+    source: io::Result<Option<String>>
+) {
+    let settings = source.map_err(Into::into).and_then(|source| match source {
+        Some(source) => defaults.with_overrides(&source, font_cache),
+
+        None => Ok(defaults.clone())
+    });
+
+    match settings {
+        Ok(settings) => {
+            log::info!("configurações carregadas de {:?}", path);
+
+            tx.update(|value| *value = settings).await;
+        }
+
+        // mantém as configurações atuais até que o arquivo seja corrigido
+        Err(error) => log::error!("erro ao carregar as configurações {:?}: {:#}", path, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() -> Result<()> {
+        let font_cache = FontCache::new();
+        let defaults = Settings::new(&font_cache)?;
+
+        let settings = defaults.with_overrides(
+            r#"{
+                "indent_size": 2,
+                "buffer_font_size": 14,
+                "buffer_font_family": ["Monaco"],
+                "theme": "dark"
+            }"#,
+
+            &font_cache
+        )?;
+
+        assert_eq!(settings.editor.indent_size, 2);
+        assert_eq!(settings.buffer_font_size, 14.0);
+        assert_eq!(settings.buffer_font_family, font_cache.load_family(&["Monaco"])?);
+        assert_eq!(settings.ui_font_size, defaults.ui_font_size);
+        assert_eq!(settings.theme.name, "dark");
+
+        let error = |source: &str| format!("{}", defaults.with_overrides(source, &font_cache).err().unwrap());
+
+        assert!(error("[]").contains("devem ser um objeto"));
+        assert!(error(r#"{"indent_size": 0}"#).contains("inteiro positivo"));
+        assert!(error(r#"{"indent_style": "tabs"}"#).contains("\"tab\""));
+        assert!(error(r#"{"end_of_line": "\\n"}"#).contains("\"lf\""));
+        assert!(error(r#"{"languages": {"Rust": {"tab": 2}}}"#).contains("desconhecida `tab` em `Rust`"));
+        assert!(error(r#"{"ui_font_size": "12"}"#).contains("número positivo"));
+        assert!(error(r#"{"line_height": 2}"#).contains("configuração desconhecida"));
+        assert!(error(r#"{"theme": 1}"#).contains("nome de um tema"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_theme_paths() {
+        assert_eq!(theme_paths(r#"{"theme": "dark"}"#), Vec::<PathBuf>::new());
+        assert_eq!(theme_paths(r#"{"theme": 1}"#), Vec::<PathBuf>::new());
+        assert_eq!(theme_paths("{"), Vec::<PathBuf>::new());
+
+        assert_eq!(
+            theme_paths(r#"{"theme": "solarized"}"#),
+            theme::path("solarized").into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_editor_settings() -> Result<()> {
+        let font_cache = FontCache::new();
+
+        let settings = Settings::new(&font_cache)?.with_overrides(
+            r#"{
+                "indent_size": 2,
+                "trim_trailing_whitespace": true,
+
+                "languages": {
+                    "Rust": { "indent_size": 4 },
+                    "Makefile": { "indent_style": "tab" }
+                }
+            }"#,
+
+            &font_cache
+        )?;
+
+        let no_editor_config = EditorSettingsOverrides::default();
+
+        let resolve = |path: &str, editor_config: &EditorSettingsOverrides| {
+            settings.editor_settings(Some(Path::new(path)), editor_config)
+        };
+
+        assert_eq!(resolve("a/b.txt", &no_editor_config).indent_size, 2);
+        assert_eq!(resolve("a/b.rs", &no_editor_config).indent_size, 4);
+        assert_eq!(resolve("a/Makefile", &no_editor_config).indent_style, IndentStyle::Tab);
+        assert!(resolve("a/b.rs", &no_editor_config).trim_trailing_whitespace);
+
+        // o `.editorconfig` tem precedência sobre as configurações de linguagem
+        let editor_config = EditorSettingsOverrides {
+            indent_size: Some(8),
+            end_of_line: Some(LineEnding::CrLf),
+
+            ..Default::default()
+        };
+
+        let rust = resolve("a/b.rs", &editor_config);
+
+        assert_eq!(rust.indent_size, 8);
+        assert_eq!(rust.end_of_line, LineEnding::CrLf);
+        assert_eq!(rust.indent_text(), " ".repeat(8));
+
+        assert_eq!(settings.editor_settings(None, &no_editor_config), settings.editor);
+
+        Ok(())
+    }
+}
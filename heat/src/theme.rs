@@ -0,0 +1,481 @@
+use crate::{config, editor::DiffHunkKind, worktree::GitStatus};
+use anyhow::{anyhow, Result};
+use gpui::color::ColorU;
+use serde_json::Value;
+use std::path::PathBuf;
+
+// um arquivo de tema parte de um dos temas embutidos e sobrescreve apenas as cores que define:
+//
+// {
+//     "base": "dark",
+//     "ui": {
+//         "border": "#3c3f41",
+//         "match_highlight": "#6897bb"
+//     },
+//     "syntax": {
+//         "comment": "#808080",
+//         "keyword": { "color": "#cc7832", "bold": true }
+//     }
+// }
+//
+// os estilos de sintaxe são procurados pelo nome do token e depois pelos seus prefixos, então
+// `keyword.control` usa o estilo de `keyword` quando não possui um próprio
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub ui: UiColors,
+    pub syntax: Vec<(String, TokenStyle)>
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UiColors {
+    pub workspace_background: ColorU,
+    pub border: ColorU,
+    pub text: ColorU,
+    pub ignored_text: ColorU,
+
+    pub git_added: ColorU,
+    pub git_modified: ColorU,
+    pub git_untracked: ColorU,
+    pub git_conflicted: ColorU,
+    pub git_deleted: ColorU,
+
+    pub tab_background: ColorU,
+    pub active_tab_background: ColorU,
+
+    pub modal_background: ColorU,
+    pub modal_shadow: ColorU,
+    pub list_background: ColorU,
+    pub selected_item_background: ColorU,
+    pub match_highlight: ColorU,
+
+    pub editor_background: ColorU,
+    pub editor_text: ColorU,
+    pub gutter_background: ColorU,
+    pub line_number: ColorU,
+    pub selection: ColorU,
+    pub cursor: ColorU,
+    pub head_text_background: ColorU,
+    pub conflict_ours_background: ColorU,
+    pub conflict_theirs_background: ColorU,
+    pub conflict_marker_background: ColorU,
+    pub conflict_action_text: ColorU
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TokenStyle {
+    pub color: ColorU,
+    pub bold: bool,
+    pub italic: bool
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            name: "light".into(),
+
+            ui: UiColors {
+                workspace_background: rgb(0xeaeaeb),
+                border: rgb(0xdbdbdc),
+                text: rgb(0x000000),
+                ignored_text: rgb(0x9a9a9a),
+
+                git_added: rgb(0x3f8a2f),
+                git_modified: rgb(0x2f62c9),
+                git_untracked: rgb(0xb05a00),
+                git_conflicted: rgb(0xc92f2f),
+                git_deleted: rgb(0xc92f2f),
+
+                tab_background: rgb(0xeaeaeb),
+                active_tab_background: rgb(0xffffff),
+
+                modal_background: rgb(0xf2f2f2),
+                modal_shadow: ColorU::new(0x00, 0x00, 0x00, 0x40),
+                list_background: rgb(0xf7f7f7),
+                selected_item_background: rgb(0xdbdbdc),
+                match_highlight: rgb(0x304ee2),
+
+                editor_background: rgb(0xffffff),
+                editor_text: rgb(0x000000),
+                gutter_background: rgb(0xffffff),
+                line_number: rgb(0x000000),
+                selection: rgb(0xb4d5fe),
+                cursor: rgb(0x000000),
+                head_text_background: rgb(0xfbe9e9),
+                conflict_ours_background: rgb(0xe6f4e2),
+                conflict_theirs_background: rgb(0xe3ecfa),
+                conflict_marker_background: rgb(0xeeeeee),
+                conflict_action_text: rgb(0x6f6f6f)
+            },
+
+            syntax: vec![
+                ("comment".into(), TokenStyle::new(rgb(0x8e908c)).italic()),
+                ("keyword".into(), TokenStyle::new(rgb(0x8959a8)).bold()),
+                ("string".into(), TokenStyle::new(rgb(0x718c00))),
+                ("number".into(), TokenStyle::new(rgb(0xf5871f))),
+                ("function".into(), TokenStyle::new(rgb(0x4271ae))),
+                ("type".into(), TokenStyle::new(rgb(0xc99e00)))
+            ]
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".into(),
+
+            ui: UiColors {
+                workspace_background: rgb(0x1e1f22),
+                border: rgb(0x3c3f41),
+                text: rgb(0xdcdcdc),
+                ignored_text: rgb(0x6f737a),
+
+                git_added: rgb(0x62a35c),
+                git_modified: rgb(0x6897bb),
+                git_untracked: rgb(0xd5915a),
+                git_conflicted: rgb(0xe05555),
+                git_deleted: rgb(0xe05555),
+
+                tab_background: rgb(0x1e1f22),
+                active_tab_background: rgb(0x2b2d30),
+
+                modal_background: rgb(0x2b2d30),
+                modal_shadow: ColorU::new(0x00, 0x00, 0x00, 0x80),
+                list_background: rgb(0x25272a),
+                selected_item_background: rgb(0x3c3f41),
+                match_highlight: rgb(0x6897bb),
+
+                editor_background: rgb(0x2b2d30),
+                editor_text: rgb(0xdcdcdc),
+                gutter_background: rgb(0x2b2d30),
+                line_number: rgb(0x6f737a),
+                selection: rgb(0x214283),
+                cursor: rgb(0xdcdcdc),
+                head_text_background: rgb(0x402a2c),
+                conflict_ours_background: rgb(0x2d3b2b),
+                conflict_theirs_background: rgb(0x2b3442),
+                conflict_marker_background: rgb(0x35373a),
+                conflict_action_text: rgb(0x9a9da3)
+            },
+
+            syntax: vec![
+                ("comment".into(), TokenStyle::new(rgb(0x808080)).italic()),
+                ("keyword".into(), TokenStyle::new(rgb(0xcc7832)).bold()),
+                ("string".into(), TokenStyle::new(rgb(0x6a8759))),
+                ("number".into(), TokenStyle::new(rgb(0x6897bb))),
+                ("function".into(), TokenStyle::new(rgb(0xffc66d))),
+                ("type".into(), TokenStyle::new(rgb(0xa9b7c6)))
+            ]
+        }
+    }
+
+    /// interpreta um arquivo de tema chamado `name`
+    pub fn parse(name: &str, source: &str) -> Result<Self> {
+        let theme = match serde_json::from_str(source)? {
+            Value::Object(theme) => theme,
+
+            _ => return Err(anyhow!("o tema deve ser um objeto"))
+        };
+
+        let mut result = match theme.get("base") {
+            None | Some(Value::Null) => Self::light(),
+
+            Some(Value::String(base)) => match base.as_str() {
+                "light" => Self::light(),
+                "dark" => Self::dark(),
+
+                _ => return Err(anyhow!("`base` deve ser \"light\" ou \"dark\""))
+            },
+
+            Some(_) => return Err(anyhow!("`base` deve ser uma string"))
+        };
+
+        result.name = name.into();
+
+        for (key, value) in &theme {
+            match key.as_str() {
+                "base" => {}
+
+                "ui" => {
+                    let colors = value
+                        .as_object()
+                        .ok_or_else(|| anyhow!("`ui` deve ser um objeto"))?;
+
+                    for (name, color) in colors {
+                        let color = parse_color(name, color)?;
+
+                        *result
+                            .ui
+                            .color_mut(name)
+                            .ok_or_else(|| anyhow!("cor desconhecida `{}`", name))? = color;
+                    }
+                }
+
+                "syntax" => {
+                    let styles = value
+                        .as_object()
+                        .ok_or_else(|| anyhow!("`syntax` deve ser um objeto"))?;
+
+                    for (token, style) in styles {
+                        let style = parse_token_style(token, style)?;
+
+                        match result.syntax.iter_mut().find(|(name, _)| name == token) {
+                            Some((_, existing)) => *existing = style,
+
+                            None => result.syntax.push((token.clone(), style))
+                        }
+                    }
+                }
+
+                _ => return Err(anyhow!("chave desconhecida `{}` no tema", key))
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// estilo de um token, como `keyword.control`, recorrendo aos prefixos do nome
+    pub fn token_style(&self, token: &str) -> Option<TokenStyle> {
+        let mut token = token;
+
+        loop {
+            if let Some((_, style)) = self.syntax.iter().find(|(name, _)| name == token) {
+                return Some(*style);
+            }
+
+            token = &token[..token.rfind('.')?];
+        }
+    }
+}
+
+impl UiColors {
+    /// cor do nome de um arquivo com o status do git dado
+    pub fn git_status(&self, status: GitStatus) -> ColorU {
+        match status {
+            GitStatus::Ignored => self.ignored_text,
+            GitStatus::Untracked => self.git_untracked,
+            GitStatus::Added => self.git_added,
+            GitStatus::Modified => self.git_modified,
+            GitStatus::Conflicted => self.git_conflicted
+        }
+    }
+
+    /// cor do marcador de um trecho do diff com o `HEAD` na calha
+    pub fn diff_hunk(&self, kind: DiffHunkKind) -> ColorU {
+        match kind {
+            DiffHunkKind::Added => self.git_added,
+            DiffHunkKind::Modified => self.git_modified,
+            DiffHunkKind::Deleted => self.git_deleted
+        }
+    }
+
+    fn color_mut(&mut self, name: &str) -> Option<&mut ColorU> {
+        let color = match name {
+            "workspace_background" => &mut self.workspace_background,
+            "border" => &mut self.border,
+            "text" => &mut self.text,
+            "ignored_text" => &mut self.ignored_text,
+
+            "git_added" => &mut self.git_added,
+            "git_modified" => &mut self.git_modified,
+            "git_untracked" => &mut self.git_untracked,
+            "git_conflicted" => &mut self.git_conflicted,
+            "git_deleted" => &mut self.git_deleted,
+
+            "tab_background" => &mut self.tab_background,
+            "active_tab_background" => &mut self.active_tab_background,
+
+            "modal_background" => &mut self.modal_background,
+            "modal_shadow" => &mut self.modal_shadow,
+            "list_background" => &mut self.list_background,
+            "selected_item_background" => &mut self.selected_item_background,
+            "match_highlight" => &mut self.match_highlight,
+
+            "editor_background" => &mut self.editor_background,
+            "editor_text" => &mut self.editor_text,
+            "gutter_background" => &mut self.gutter_background,
+            "line_number" => &mut self.line_number,
+            "selection" => &mut self.selection,
+            "cursor" => &mut self.cursor,
+            "head_text_background" => &mut self.head_text_background,
+            "conflict_ours_background" => &mut self.conflict_ours_background,
+            "conflict_theirs_background" => &mut self.conflict_theirs_background,
+            "conflict_marker_background" => &mut self.conflict_marker_background,
+            "conflict_action_text" => &mut self.conflict_action_text,
+
+            _ => return None
+        };
+
+        Some(color)
+    }
+}
+
+impl TokenStyle {
+    pub fn new(color: ColorU) -> Self {
+        Self {
+            color,
+            bold: false,
+            italic: false
+        }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+
+        self
+    }
+
+    fn italic(mut self) -> Self {
+        self.italic = true;
+
+        self
+    }
+}
+
+/// diretório dos arquivos de tema do usuário
+pub fn dir() -> Option<PathBuf> {
+    config::dir().map(|dir| dir.join("themes"))
+}
+
+/// arquivo de um tema do usuário, ou `None` para os temas embutidos
+pub fn path(name: &str) -> Option<PathBuf> {
+    match name {
+        "light" | "dark" => None,
+
+        _ => dir().map(|dir| dir.join(format!("{}.json", name)))
+    }
+}
+
+/// carrega um tema embutido ou o arquivo `<name>.json` do diretório de temas do usuário
+pub fn load(name: &str) -> Result<Theme> {
+    match name {
+        "light" => return Ok(Theme::light()),
+        "dark" => return Ok(Theme::dark()),
+
+        _ => {}
+    }
+
+    let path = path(name)
+        .ok_or_else(|| anyhow!("o diretório de configurações não foi encontrado"))?;
+
+    match config::read(&path)? {
+        Some(source) => Theme::parse(name, &source),
+
+        None => Err(anyhow!("tema desconhecido `{}`", name))
+    }
+}
+
+fn rgb(rgb: u32) -> ColorU {
+    ColorU::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 0xff)
+}
+
+/// aceita `#rrggbb` e `#rrggbbaa`
+fn parse_color(key: &str, value: &Value) -> Result<ColorU> {
+    let invalid = || anyhow!("`{}` deve ser uma cor no formato \"#rrggbb\" ou \"#rrggbbaa\"", key);
+
+    let hex = value
+        .as_str()
+        .and_then(|color| color.strip_prefix('#'))
+        .ok_or_else(invalid)?;
+
+    if (hex.len() != 6 && hex.len() != 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let component = |ix: usize| u8::from_str_radix(&hex[ix..ix + 2], 16).unwrap();
+
+    let alpha = if hex.len() == 8 { component(6) } else { 0xff };
+
+    Ok(ColorU::new(component(0), component(2), component(4), alpha))
+}
+
+fn parse_token_style(token: &str, value: &Value) -> Result<TokenStyle> {
+    let style = match value {
+        Value::String(_) => return Ok(TokenStyle::new(parse_color(token, value)?)),
+
+        Value::Object(style) => style,
+
+        _ => return Err(anyhow!("o estilo de `{}` deve ser uma cor ou um objeto", token))
+    };
+
+    let mut result = TokenStyle::new(parse_color(
+        token,
+        style.get("color").unwrap_or(&Value::Null)
+    )?);
+
+    for (key, value) in style {
+        match key.as_str() {
+            "color" => {}
+
+            "bold" => {
+                result.bold = value
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("`{}.bold` deve ser um booleano", token))?
+            }
+
+            "italic" => {
+                result.italic = value
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("`{}.italic` deve ser um booleano", token))?
+            }
+
+            _ => return Err(anyhow!("chave desconhecida `{}` no estilo de `{}`", key, token))
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let theme = Theme::parse(
+            "custom",
+
+            r##"{
+                "base": "dark",
+
+                "ui": {
+                    "border": "#102030",
+                    "modal_shadow": "#00000020"
+                },
+
+                "syntax": {
+                    "keyword": { "color": "#aabbcc", "italic": true },
+                    "keyword.control": "#ddeeff"
+                }
+            }"##
+        )?;
+
+        assert_eq!(theme.name, "custom");
+        assert_eq!(theme.ui.border, ColorU::new(0x10, 0x20, 0x30, 0xff));
+        assert_eq!(theme.ui.modal_shadow, ColorU::new(0x00, 0x00, 0x00, 0x20));
+        assert_eq!(theme.ui.text, Theme::dark().ui.text);
+
+        assert_eq!(
+            theme.token_style("keyword.operator"),
+
+            Some(TokenStyle {
+                color: ColorU::new(0xaa, 0xbb, 0xcc, 0xff),
+                bold: false,
+                italic: true
+            })
+        );
+
+        assert_eq!(theme.token_style("keyword.control"), Some(TokenStyle::new(rgb(0xddeeff))));
+        assert_eq!(theme.token_style("string.escape"), Theme::dark().token_style("string"));
+        assert_eq!(theme.token_style("punctuation"), None);
+
+        let error = |source: &str| format!("{}", Theme::parse("custom", source).err().unwrap());
+
+        assert!(error(r#"{"base": "solarized"}"#).contains("\"light\" ou \"dark\""));
+        assert!(error(r#"{"ui": {"border": "red"}}"#).contains("#rrggbb"));
+        assert!(error(r##"{"ui": {"borders": "#000000"}}"##).contains("cor desconhecida `borders`"));
+        assert!(error(r##"{"syntax": {"type": {"color": "#000000", "underline": true}}}"##).contains("chave desconhecida `underline`"));
+
+        Ok(())
+    }
+}
@@ -1,72 +1,192 @@
-// todo: essa implementação está atualmente quebrada
-
 use gpui::{Entity, ModelContext, View, ViewContext};
-use smol::{channel, lock::RwLock};
-use std::ops::Deref;
-use std::sync::Arc;
+use parking_lot::Mutex;
+use smol::{channel, lock::RwLock, prelude::*};
+
+use std::{
+    ops::Deref,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering::SeqCst},
+        Arc
+    },
+    task::{Context, Poll}
+};
+
+// cada atualização incrementa a versão do valor compartilhado
+//
+// cada receiver guarda a última versão que viu e tem o seu próprio canal de notificação,
+// então clones não competem pelas mesmas notificações e atualizações seguidas são
+// agrupadas em uma só quando o receiver não as consumiu a tempo
+
+struct Shared<T> {
+    value: RwLock<T>,
+    version: AtomicUsize,
+    subscribers: Mutex<Vec<channel::Sender<()>>>
+}
 
 pub struct Sender<T> {
-    value: Arc<RwLock<T>>,
-    updated: channel::Sender<()>
+    shared: Arc<Shared<T>>
 }
 
-#[derive(Clone)]
 pub struct Receiver<T> {
-    value: Arc<RwLock<T>>,
+    shared: Arc<Shared<T>>,
+    version: usize,
     updated: channel::Receiver<()>
 }
 
 impl<T> Sender<T> {
     pub async fn update<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
-        let result = f(&mut *self.value.write().await);
+        let result = f(&mut *self.shared.value.write().await);
+
+        self.shared.version.fetch_add(1, SeqCst);
 
-        self.updated.send(()).await.unwrap();
+        self.shared
+            .subscribers
+            .lock()
+            .retain(|subscriber| match subscriber.try_send(()) {
+                Err(channel::TrySendError::Closed(_)) => false,
+
+                _ => true
+            });
 
         result
     }
+
+    pub fn receiver(&self) -> Receiver<T> {
+        Receiver::subscribe(self.shared.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // fecha os canais para que os receivers saibam que não haverá novas versões
+        self.shared.subscribers.lock().clear();
+    }
 }
 
 impl<T> Receiver<T> {
-    pub async fn updated(&self) {
-        let _ = self.updated.recv().await;
+    fn subscribe(shared: Arc<Shared<T>>) -> Self {
+        let (tx, rx) = channel::bounded(1);
+
+        let version = shared.version.load(SeqCst);
+
+        shared.subscribers.lock().push(tx);
+
+        Self {
+            shared,
+            version,
+            updated: rx
+        }
+    }
+
+    /// espera por uma versão mais nova do que a última vista por este receiver
+    ///
+    /// retorna `None` se o sender foi descartado
+    pub async fn updated(&mut self) -> Option<()> {
+        self.next().await
     }
 
     pub async fn read<'a>(&'a self) -> impl 'a + Deref<Target = T> {
-        self.value.read().await
+        self.shared.value.read().await
     }
 }
 
-// todo: essas implementações estão quebradas porque eles lidam apenas com uma única atualização
-impl<T: 'static + Clone> Receiver<T> {
-    pub fn notify_model_on_change<M: 'static + Entity>(&self, ctx: &mut ModelContext<M>) {
-        let watch = self.clone();
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut receiver = Self::subscribe(self.shared.clone());
 
-        let _ = ctx.spawn(async move { watch.updated().await }, |_, _, ctx| {
-            ctx.notify()
-        });
+        receiver.version = self.version;
+
+        receiver
     }
+}
 
-    pub fn notify_view_on_change<V: 'static + View>(&self, ctx: &mut ViewContext<V>) {
-        let watch = self.clone();
+impl<T> Stream for Receiver<T> {
+    type Item = ();
 
-        let _ = ctx.spawn(async move { watch.updated().await }, |_, _, ctx| {
-            ctx.notify()
-        });
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        loop {
+            let version = self.shared.version.load(SeqCst);
+
+            if version != self.version {
+                self.version = version;
+
+                return Poll::Ready(Some(()));
+            }
+
+            match Pin::new(&mut self.updated).poll_next(cx) {
+                Poll::Ready(Some(())) => continue,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T: 'static> Receiver<T> {
+    pub fn notify_model_on_change<M: 'static + Entity>(&self, ctx: &mut ModelContext<M>) {
+        ctx.spawn_stream(self.clone(), |_, _, ctx| ctx.notify(), |_, _| {})
+            .detach();
+    }
+
+    pub fn notify_view_on_change<V: 'static + View>(&self, ctx: &mut ViewContext<V>) {
+        ctx.spawn_stream(self.clone(), |_, _, ctx| ctx.notify(), |_, _| {})
+            .detach();
     }
 }
 
 pub fn channel<T>(value: T) -> (Sender<T>, Receiver<T>) {
-    let value = Arc::new(RwLock::new(value));
+    let shared = Arc::new(Shared {
+        value: RwLock::new(value),
+        version: AtomicUsize::new(0),
+        subscribers: Mutex::new(Vec::new())
+    });
+
+    let receiver = Receiver::subscribe(shared.clone());
+
+    (Sender { shared }, receiver)
+}
 
-    let (s, r) = channel::unbounded();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let sender = Sender {
-        value: value.clone(),
+    #[test]
+    fn test_multiple_receivers() {
+        smol::block_on(async {
+            let (mut tx, mut rx_1) = channel(0);
+            let mut rx_2 = rx_1.clone();
 
-        updated: s
-    };
+            tx.update(|value| *value = 1).await;
 
-    let receiver = Receiver { value, updated: r };
-    
-    (sender, receiver)
-}
\ No newline at end of file
+            // cada receiver é notificado da mesma atualização
+            assert_eq!(rx_1.updated().await, Some(()));
+            assert_eq!(*rx_1.read().await, 1);
+
+            assert_eq!(rx_2.updated().await, Some(()));
+            assert_eq!(*rx_2.read().await, 1);
+
+            // atualizações não consumidas são agrupadas
+            tx.update(|value| *value = 2).await;
+            tx.update(|value| *value = 3).await;
+
+            assert_eq!(rx_1.updated().await, Some(()));
+            assert_eq!(*rx_1.read().await, 3);
+
+            // um clone parte da versão do receiver original
+            let mut rx_3 = rx_2.clone();
+
+            assert_eq!(rx_3.updated().await, Some(()));
+            assert_eq!(*rx_3.read().await, 3);
+
+            let mut rx_4 = tx.receiver();
+
+            drop(tx);
+
+            assert_eq!(rx_1.updated().await, None);
+            assert_eq!(rx_2.updated().await, Some(()));
+            assert_eq!(rx_2.updated().await, None);
+            assert_eq!(rx_4.updated().await, None);
+        });
+    }
+}
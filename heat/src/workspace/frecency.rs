@@ -0,0 +1,296 @@
+use crate::config;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Map, Value};
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+// o histórico é guardado por raiz das árvores de trabalho, para que as mesmas pastas abertas de
+// novo recuperem os seus pesos mesmo sem uma sessão restaurada:
+//
+// {
+//     "/projetos/heat": [
+//         { "path": "/projetos/heat/src/main.rs", "count": 3.0, "last_access": 1620000000 }
+//     ]
+// }
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+const MONTH: u64 = 30 * DAY;
+
+// quando a soma dos acessos passa desse limite, todos os contadores decaem e os arquivos
+// esquecidos deixam o histórico
+const MAX_TOTAL_COUNT: f64 = 1000.0;
+const COUNT_DECAY: f64 = 0.9;
+const MIN_COUNT: f64 = 1.0;
+
+/// histórico de arquivos abertos e ativados, ponderado pela frequência e pela recência dos acessos
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frecency {
+    entries: HashMap<PathBuf, Access>
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Access {
+    pub count: f64,
+
+    /// segundos desde a época unix
+    pub last_access: u64
+}
+
+/// instante atual em segundos desde a época unix
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+impl Frecency {
+    pub fn record(&mut self, path: PathBuf, time: u64) {
+        let access = self.entries.entry(path).or_insert(Access {
+            count: 0.0,
+            last_access: time
+        });
+
+        access.count += 1.0;
+        access.last_access = access.last_access.max(time);
+
+        if self.entries.values().map(|access| access.count).sum::<f64>() > MAX_TOTAL_COUNT {
+            for access in self.entries.values_mut() {
+                access.count *= COUNT_DECAY;
+            }
+
+            self.entries.retain(|_, access| access.count >= MIN_COUNT);
+        }
+    }
+
+    pub fn insert(&mut self, path: PathBuf, access: Access) {
+        self.entries.insert(path, access);
+    }
+
+    /// acrescenta as entradas de `other` que ainda não estão no histórico
+    pub fn merge(&mut self, other: Frecency) {
+        for (path, access) in other.entries {
+            self.entries.entry(path).or_insert(access);
+        }
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(&Path) -> bool) {
+        self.entries.retain(|path, _| f(path));
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&Path, &Access)> {
+        self.entries.iter().map(|(path, access)| (path.as_path(), access))
+    }
+
+    /// frequência do arquivo multiplicada por um peso que diminui com o tempo desde o último acesso
+    pub fn score(&self, path: &Path, now: u64) -> f64 {
+        self.entries.get(path).map_or(0.0, |access| {
+            let weight = match now.saturating_sub(access.last_access) {
+                age if age <= 4 * HOUR => 1.5,
+                age if age <= DAY => 1.0,
+                age if age <= WEEK => 0.5,
+                age if age <= MONTH => 0.25,
+
+                _ => 0.1
+            };
+
+            access.count * weight
+        })
+    }
+
+    /// caminhos do acesso mais recente para o mais antigo
+    pub fn recent(&self) -> Vec<&Path> {
+        let mut entries = self.entries().collect::<Vec<_>>();
+
+        entries.sort_by_key(|(path, access)| (Reverse(access.last_access), *path));
+        entries.into_iter().map(|(path, _)| path).collect()
+    }
+}
+
+/// arquivo com o histórico de todas as raízes
+pub fn store_path() -> Option<PathBuf> {
+    // os testes informam o seu próprio arquivo, para não tocar no histórico do usuário
+    if cfg!(test) {
+        return None;
+    }
+
+    config::dir().map(|dir| dir.join("frecency.json"))
+}
+
+/// histórico guardado para `root`, sem os arquivos que não existem mais
+pub fn load(store: &Path, root: &Path) -> Result<Frecency> {
+    let source = match config::read(store)? {
+        Some(source) => source,
+
+        None => return Ok(Frecency::default())
+    };
+
+    let stored = serde_json::from_str::<Value>(&source)?;
+
+    let mut frecency = match stored.get(root.to_string_lossy().as_ref()) {
+        Some(entries) => parse(entries)?,
+
+        None => Frecency::default()
+    };
+
+    frecency.retain(Path::is_file);
+
+    Ok(frecency)
+}
+
+/// substitui no arquivo o histórico de cada raiz pelas entradas de `frecency` dentro dela, sem
+/// mexer nas demais raízes
+pub fn save(store: &Path, roots: &[PathBuf], frecency: &Frecency) -> Result<()> {
+    // um arquivo ilegível é só descartado, já que o histórico pode ser refeito
+    let mut stored = config::read(store)?
+        .and_then(|source| serde_json::from_str::<Value>(&source).ok())
+        .and_then(|stored| stored.as_object().cloned())
+        .unwrap_or_else(Map::new);
+
+    for root in roots {
+        let entries = frecency.entries().filter(|(path, _)| path.starts_with(root));
+
+        stored.insert(root.to_string_lossy().into(), serialize(entries));
+    }
+
+    if let Some(dir) = store.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(store, serde_json::to_string_pretty(&Value::Object(stored))?)?;
+
+    Ok(())
+}
+
+fn serialize<'a>(entries: impl Iterator<Item = (&'a Path, &'a Access)>) -> Value {
+    entries
+        .map(|(path, access)| json!({
+            "path": path.to_string_lossy(),
+            "count": access.count,
+            "last_access": access.last_access
+        })).collect()
+}
+
+fn parse(value: &Value) -> Result<Frecency> {
+    let mut frecency = Frecency::default();
+
+    for entry in value
+        .as_array()
+        .ok_or_else(|| anyhow!("o histórico de uma raiz deve ser uma lista"))?
+    {
+        let path = entry
+            .get("path")
+            .and_then(Value::as_str)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .ok_or_else(|| anyhow!("`path` deve ser um caminho absoluto"))?;
+
+        let count = entry
+            .get("count")
+            .and_then(Value::as_f64)
+            .ok_or_else(|| anyhow!("`count` deve ser um número"))?;
+
+        let last_access = entry
+            .get("last_access")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("`last_access` deve ser um inteiro"))?;
+
+        frecency.insert(path, Access { count, last_access });
+    }
+
+    Ok(frecency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+
+    #[test]
+    fn test_score_and_recent() {
+        let mut frecency = Frecency::default();
+        let day = DAY;
+
+        frecency.record("/a".into(), 0);
+        frecency.record("/a".into(), 0);
+        frecency.record("/b".into(), day);
+
+        // acessos frequentes vencem um acesso mais recente, mas um acesso de hoje vence acessos de
+        // dias atrás
+        assert!(frecency.score(Path::new("/a"), day) > frecency.score(Path::new("/b"), day));
+
+        frecency.record("/c".into(), 10 * day);
+
+        assert!(frecency.score(Path::new("/c"), 10 * day) > frecency.score(Path::new("/a"), 10 * day));
+
+        assert_eq!(frecency.score(Path::new("/a"), 3 * day), 1.0);
+        assert_eq!(frecency.score(Path::new("/d"), 0), 0.0);
+
+        assert_eq!(frecency.recent(), vec![Path::new("/c"), Path::new("/b"), Path::new("/a")]);
+    }
+
+    #[test]
+    fn test_count_decay() {
+        let mut frecency = Frecency::default();
+
+        frecency.record("/raro".into(), 0);
+
+        for _ in 0..MAX_TOTAL_COUNT as usize {
+            frecency.record("/frequente".into(), 1);
+        }
+
+        // o arquivo acessado uma única vez é esquecido quando os contadores decaem
+        assert_eq!(frecency.recent(), vec![Path::new("/frequente")]);
+        assert!(frecency.score(Path::new("/frequente"), 1) < MAX_TOTAL_COUNT * 1.5);
+    }
+
+    #[test]
+    fn test_load_and_save() -> Result<()> {
+        let dir = temp_tree(json!({
+            "a": { "1.txt": "", "2.txt": "" },
+            "b": { "3.txt": "" }
+        }));
+
+        let root_a = dir.path().join("a");
+        let root_b = dir.path().join("b");
+        let store = dir.path().join("config/frecency.json");
+
+        assert_eq!(load(&store, &root_a)?, Frecency::default());
+
+        let mut frecency = Frecency::default();
+
+        frecency.record(root_a.join("1.txt"), 100);
+        frecency.record(root_a.join("1.txt"), 200);
+        frecency.record(root_a.join("removed.txt"), 200);
+        frecency.record(root_b.join("3.txt"), 300);
+
+        save(&store, &[root_a.clone()], &frecency)?;
+
+        // só as entradas dentro da raiz salva são guardadas para ela, e os arquivos removidos
+        // são descartados ao carregar
+        let loaded = load(&store, &root_a)?;
+
+        assert_eq!(loaded.recent(), vec![root_a.join("1.txt").as_path()]);
+        assert_eq!(loaded.score(&root_a.join("1.txt"), 200), frecency.score(&root_a.join("1.txt"), 200));
+        assert_eq!(load(&store, &root_b)?, Frecency::default());
+
+        // salvar outra raiz mantém o histórico das demais
+        save(&store, &[root_b.clone()], &frecency)?;
+
+        assert_eq!(load(&store, &root_a)?, loaded);
+        assert_eq!(load(&store, &root_b)?.recent(), vec![root_b.join("3.txt").as_path()]);
+
+        fs::write(&store, r#"{"/a": [{"path": "/a/1.txt"}]}"#)?;
+
+        assert!(format!("{}", load(&store, Path::new("/a")).err().unwrap()).contains("`count`"));
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+pub mod frecency;
 pub mod pane;
 pub mod pane_group;
+pub mod session;
 pub mod workspace;
 pub mod workspace_view;
 
@@ -9,12 +11,31 @@ pub use workspace::*;
 pub use workspace_view::*;
 
 use crate::{settings::Settings, watch};
-use gpui::{App, MutableAppContext};
+use gpui::{keymap::Binding, App, MutableAppContext};
 use std::path::PathBuf;
 
 pub fn init(app: &mut App) {
     app.add_global_action("workspace:open_paths", open_paths);
 
+    app.add_action("workspace:drag_tab", WorkspaceView::drag_tab);
+    app.add_action("workspace:drop_tab", WorkspaceView::drop_tab);
+    app.add_action("workspace:cancel_tab_drag", WorkspaceView::cancel_tab_drag);
+
+    app.add_action("workspace:start_resize", WorkspaceView::start_resize);
+    app.add_action("workspace:resize", WorkspaceView::resize);
+    app.add_action("workspace:end_resize", WorkspaceView::end_resize);
+    app.add_action("workspace:grow_active_pane", WorkspaceView::grow_active_pane);
+    app.add_action("workspace:shrink_active_pane", WorkspaceView::shrink_active_pane);
+    app.add_action("workspace:equalize_panes", WorkspaceView::equalize_panes);
+    app.add_action("workspace:toggle_zoom", WorkspaceView::toggle_zoom);
+
+    app.add_bindings(vec![
+        Binding::new("ctrl-cmd-=", "workspace:grow_active_pane", None),
+        Binding::new("ctrl-cmd--", "workspace:shrink_active_pane", None),
+        Binding::new("ctrl-cmd-0", "workspace:equalize_panes", None),
+        Binding::new("shift-cmd-enter", "workspace:toggle_zoom", None)
+    ]);
+
     pane::init(app);
 }
 
@@ -1,7 +1,20 @@
-use super::{ItemViewHandle, SplitDirection};
-use crate::{settings::Settings, watch};
-use gpui::{color::ColorU, elements::*, keymap::Binding, App, AppContext, Border, Entity, View, ViewContext};
-use std::cmp;
+use super::{session::PaneState, ItemViewHandle, SplitDirection};
+use crate::{editor::Anchor, settings::Settings, watch};
+
+use gpui::{
+    elements::*,
+    fonts::{Properties, Style},
+    geometry::vector::Vector2F,
+    keymap::Binding,
+    App, AppContext, Border, Entity, View, ViewContext, WeakViewHandle
+};
+
+use std::{cmp, mem, ops::Range};
+
+const MAX_NAVIGATION_ENTRIES: usize = 100;
+
+const TAB_WIDTH: f32 = 180.0;
+const PINNED_TAB_WIDTH: f32 = 100.0;
 
 pub fn init(app: &mut App) {
     app.add_action("pane:activate_item", |pane: &mut Pane, index: &usize, ctx| {
@@ -20,6 +33,38 @@ pub fn init(app: &mut App) {
         pane.close_active_item(ctx);
     });
 
+    app.add_action("pane:close_item", |pane: &mut Pane, index: &usize, ctx| {
+        pane.close_item(*index, ctx);
+    });
+
+    app.add_action("pane:close_other_items", |pane: &mut Pane, _: &(), ctx| {
+        pane.close_other_items(ctx);
+    });
+
+    app.add_action("pane:close_items_to_the_right", |pane: &mut Pane, _: &(), ctx| {
+        pane.close_items_to_the_right(ctx);
+    });
+
+    app.add_action("pane:toggle_pin_active_item", |pane: &mut Pane, _: &(), ctx| {
+        pane.toggle_pin_active_item(ctx);
+    });
+
+    app.add_action("pane:hover_tab", |pane: &mut Pane, index: &Option<usize>, ctx| {
+        pane.hover_tab(*index, ctx);
+    });
+
+    app.add_action("horizontal_list:scroll", |_: &mut Pane, _: &f32, ctx| {
+        ctx.notify();
+    });
+
+    app.add_action("pane:go_back", |pane: &mut Pane, _: &(), ctx| {
+        pane.go_back(ctx);
+    });
+
+    app.add_action("pane:go_forward", |pane: &mut Pane, _: &(), ctx| {
+        pane.go_forward(ctx);
+    });
+
     app.add_action("pane:split_up", |pane: &mut Pane, _: &(), ctx| {
         pane.split(SplitDirection::Up, ctx);
     });
@@ -36,23 +81,116 @@ pub fn init(app: &mut App) {
         pane.split(SplitDirection::Right, ctx);
     });
 
+    app.add_action("pane:activate_left", |pane: &mut Pane, _: &(), ctx| {
+        pane.activate_neighbor(SplitDirection::Left, ctx);
+    });
+
+    app.add_action("pane:activate_right", |pane: &mut Pane, _: &(), ctx| {
+        pane.activate_neighbor(SplitDirection::Right, ctx);
+    });
+
+    app.add_action("pane:activate_up", |pane: &mut Pane, _: &(), ctx| {
+        pane.activate_neighbor(SplitDirection::Up, ctx);
+    });
+
+    app.add_action("pane:activate_down", |pane: &mut Pane, _: &(), ctx| {
+        pane.activate_neighbor(SplitDirection::Down, ctx);
+    });
+
+    app.add_action("pane:swap_left", |pane: &mut Pane, _: &(), ctx| {
+        pane.swap_with_neighbor(SplitDirection::Left, ctx);
+    });
+
+    app.add_action("pane:swap_right", |pane: &mut Pane, _: &(), ctx| {
+        pane.swap_with_neighbor(SplitDirection::Right, ctx);
+    });
+
+    app.add_action("pane:swap_up", |pane: &mut Pane, _: &(), ctx| {
+        pane.swap_with_neighbor(SplitDirection::Up, ctx);
+    });
+
+    app.add_action("pane:swap_down", |pane: &mut Pane, _: &(), ctx| {
+        pane.swap_with_neighbor(SplitDirection::Down, ctx);
+    });
+
+    app.add_action("pane:move_item_left", |pane: &mut Pane, _: &(), ctx| {
+        pane.move_active_item_to_neighbor(SplitDirection::Left, ctx);
+    });
+
+    app.add_action("pane:move_item_right", |pane: &mut Pane, _: &(), ctx| {
+        pane.move_active_item_to_neighbor(SplitDirection::Right, ctx);
+    });
+
+    app.add_action("pane:move_item_up", |pane: &mut Pane, _: &(), ctx| {
+        pane.move_active_item_to_neighbor(SplitDirection::Up, ctx);
+    });
+
+    app.add_action("pane:move_item_down", |pane: &mut Pane, _: &(), ctx| {
+        pane.move_active_item_to_neighbor(SplitDirection::Down, ctx);
+    });
+
     app.add_bindings(vec![
         Binding::new("shift-cmd-{", "pane:activate_prev_item", Some("Pane")),
         Binding::new("shift-cmd-}", "pane:activate_next_item", Some("Pane")),
         
         Binding::new("cmd-w", "pane:close_active_item", Some("Pane")),
+        Binding::new("alt-cmd-t", "pane:close_other_items", Some("Pane")),
+        Binding::new("cmd-k shift-enter", "pane:toggle_pin_active_item", Some("Pane")),
+        Binding::new("ctrl--", "pane:go_back", Some("Pane")),
+        Binding::new("ctrl-_", "pane:go_forward", Some("Pane")),
         Binding::new("cmd-k up", "pane:split_up", Some("Pane")),
         Binding::new("cmd-k down", "pane:split_down", Some("Pane")),
         Binding::new("cmd-k left", "pane:split_left", Some("Pane")),
-        Binding::new("cmd-k right", "pane:split_right", Some("Pane"))
+        Binding::new("cmd-k right", "pane:split_right", Some("Pane")),
+        Binding::new("cmd-k cmd-left", "pane:activate_left", Some("Pane")),
+        Binding::new("cmd-k cmd-right", "pane:activate_right", Some("Pane")),
+        Binding::new("cmd-k cmd-up", "pane:activate_up", Some("Pane")),
+        Binding::new("cmd-k cmd-down", "pane:activate_down", Some("Pane")),
+        Binding::new("cmd-k shift-left", "pane:swap_left", Some("Pane")),
+        Binding::new("cmd-k shift-right", "pane:swap_right", Some("Pane")),
+        Binding::new("cmd-k shift-up", "pane:swap_up", Some("Pane")),
+        Binding::new("cmd-k shift-down", "pane:swap_down", Some("Pane")),
+        Binding::new("cmd-k alt-left", "pane:move_item_left", Some("Pane")),
+        Binding::new("cmd-k alt-right", "pane:move_item_right", Some("Pane")),
+        Binding::new("cmd-k alt-up", "pane:move_item_up", Some("Pane")),
+        Binding::new("cmd-k alt-down", "pane:move_item_down", Some("Pane"))
     ]);
 }
 
 pub enum Event {
     Activate,
+    ActivateItem,
     Remove,
 
-    Split(SplitDirection)
+    Split(SplitDirection),
+
+    // painel vizinho na direção, segundo a posição das panes na tela
+    ActivateNeighbor(SplitDirection),
+    SwapWithNeighbor(SplitDirection),
+    MoveActiveItemToNeighbor(SplitDirection),
+
+    // ids dos itens que saíram do painel, fechados ou substituídos
+    ItemsRemoved(Vec<usize>),
+
+    // o histórico de navegação aponta para um item já fechado, que precisa ser reaberto
+    ReopenEntry((usize, usize), Option<NavigationPosition>)
+}
+
+/// posição de um item no histórico de navegação, guardada em âncoras para continuar válida
+/// depois de edições no buffer
+#[derive(Clone, Debug)]
+pub struct NavigationPosition {
+    // cauda e cabeça de cada seleção
+    pub selections: Vec<Range<Anchor>>,
+
+    pub scroll_position: Vector2F
+}
+
+#[derive(Clone, Debug)]
+struct NavigationEntry {
+    item_id: usize,
+    entry_id: Option<(usize, usize)>,
+    position: Option<NavigationPosition>
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -67,17 +205,45 @@ pub struct TabState {
 }
 
 pub struct Pane {
+    handle: WeakViewHandle<Self>,
     items: Vec<Box<dyn ItemViewHandle>>,
     active_item: usize,
-    settings: watch::Receiver<Settings>
+    settings: watch::Receiver<Settings>,
+
+    // os primeiros `pinned_count` itens estão fixados e não saem dessa posição
+    pinned_count: usize,
+
+    // item aberto como prévia, substituído pela próxima prévia aberta no painel
+    preview_item: Option<usize>,
+
+    hovered_tab: Option<usize>,
+    tab_list_state: HorizontalListState,
+
+    back_history: Vec<NavigationEntry>,
+    forward_history: Vec<NavigationEntry>,
+
+    // enquanto o histórico é percorrido, as ativações não são registradas como saltos
+    navigating: bool
 }
 
 impl Pane {
-    pub fn new(settings: watch::Receiver<Settings>) -> Self {
+    pub fn new(settings: watch::Receiver<Settings>, ctx: &mut ViewContext<Self>) -> Self {
+        settings.notify_view_on_change(ctx);
+
         Self {
+            handle: ctx.handle(),
             items: Vec::new(),
             active_item: 0,
-            settings
+            settings,
+
+            pinned_count: 0,
+            preview_item: None,
+            hovered_tab: None,
+            tab_list_state: HorizontalListState::new(),
+
+            back_history: Vec::new(),
+            forward_history: Vec::new(),
+            navigating: false
         }
     }
 
@@ -91,15 +257,99 @@ impl Pane {
         item: Box<dyn ItemViewHandle>,
         ctx: &mut ViewContext<Self>
     ) -> usize {
-        let item_idx = cmp::min(self.active_item + 1, self.items.len());
-        
+        self.insert_item(self.active_item + 1, item, ctx)
+    }
+
+    /// insere o item na posição, ou na primeira depois dos itens fixados
+    pub fn insert_item(
+        &mut self,
+
+        index: usize,
+        item: Box<dyn ItemViewHandle>,
+        ctx: &mut ViewContext<Self>
+    ) -> usize {
+        let item_idx = cmp::min(cmp::max(index, self.pinned_count), self.items.len());
+
+        self.insert_item_at(item_idx, item, ctx);
+
+        item_idx
+    }
+
+    /// insere o item entre os fixados, na posição ou no fim deles
+    pub fn insert_pinned_item(
+        &mut self,
+
+        index: usize,
+        item: Box<dyn ItemViewHandle>,
+        ctx: &mut ViewContext<Self>
+    ) -> usize {
+        let item_idx = cmp::min(index, self.pinned_count);
+
+        self.insert_item_at(item_idx, item, ctx);
+        self.pinned_count += 1;
+
+        item_idx
+    }
+
+    fn insert_item_at(&mut self, item_idx: usize, item: Box<dyn ItemViewHandle>, ctx: &mut ViewContext<Self>) {
         self.items.insert(item_idx, item);
+
+        // o item ativo continua o mesmo
+        if item_idx <= self.active_item && self.items.len() > 1 {
+            self.active_item += 1;
+        }
+
         ctx.notify();
+    }
 
-        item_idx
+    /// adiciona um item como prévia, ocupando o lugar da prévia anterior
+    pub fn add_preview_item(
+        &mut self,
+
+        item: Box<dyn ItemViewHandle>,
+        ctx: &mut ViewContext<Self>
+    ) -> usize {
+        let preview_ix = self
+            .preview_item
+            .and_then(|item_id| self.items.iter().position(|item| item.id() == item_id));
+
+        self.preview_item = Some(item.id());
+
+        match preview_ix {
+            Some(ix) => {
+                if ix == self.active_item {
+                    self.push_active_item_navigation(ctx.app());
+                }
+
+                let replaced = mem::replace(&mut self.items[ix], item);
+
+                ctx.emit(Event::ItemsRemoved(vec![replaced.id()]));
+                ctx.notify();
+
+                ix
+            }
+
+            None => self.add_item(item, ctx)
+        }
+    }
+
+    /// deixa de tratar o item como prévia, de modo que a próxima prévia não o substitua
+    pub fn keep_item(&mut self, item_id: usize, ctx: &mut ViewContext<Self>) {
+        if self.preview_item == Some(item_id) {
+            self.preview_item = None;
+
+            ctx.notify();
+        }
+    }
+
+    pub fn is_preview(&self, item_id: usize) -> bool {
+        self.preview_item == Some(item_id)
+    }
+
+    pub fn is_pinned(&self, index: usize) -> bool {
+        index < self.pinned_count
     }
 
-    #[cfg(test)]
     pub fn items(&self) -> &[Box<dyn ItemViewHandle>] {
         &self.items
     }
@@ -108,6 +358,11 @@ impl Pane {
         self.items.get(self.active_item).cloned()
     }
 
+    /// item à esquerda do ativo
+    pub fn prev_item(&self) -> Option<Box<dyn ItemViewHandle>> {
+        self.items.get(self.active_item.checked_sub(1)?).cloned()
+    }
+
     pub fn activate_entry(
         &mut self,
 
@@ -127,50 +382,192 @@ impl Pane {
         }
     }
 
+    /// estado salvo na sessão, contendo apenas os itens que podem ser restaurados
+    pub fn state(&self, app: &AppContext) -> PaneState {
+        let mut active_item = 0;
+        let mut items = Vec::new();
+
+        for (ix, item) in self.items.iter().enumerate() {
+            if let Some(state) = item.state(app) {
+                if ix == self.active_item {
+                    active_item = items.len();
+                }
+
+                items.push(state);
+            }
+        }
+
+        PaneState { items, active_item }
+    }
+
     pub fn item_index(&self, item: &dyn ItemViewHandle) -> Option<usize> {
         self.items.iter().position(|i| i.id() == item.id())
     }
 
     pub fn activate_item(&mut self, index: usize, ctx: &mut ViewContext<Self>) {
         if index < self.items.len() {
+            if index != self.active_item {
+                self.push_active_item_navigation(ctx.app());
+            }
+
             self.active_item = index;
+            self.tab_list_state.scroll_to(index);
             self.focus_active_item(ctx);
 
+            ctx.emit(Event::ActivateItem);
             ctx.notify();
         }
     }
 
     pub fn activate_prev_item(&mut self, ctx: &mut ViewContext<Self>) {
+        self.push_active_item_navigation(ctx.app());
+
         if self.active_item > 0 {
             self.active_item -= 1;
         } else {
             self.active_item = self.items.len() - 1;
         }
 
+        self.tab_list_state.scroll_to(self.active_item);
         self.focus_active_item(ctx);
 
+        ctx.emit(Event::ActivateItem);
         ctx.notify();
     }
 
     pub fn activate_next_item(&mut self, ctx: &mut ViewContext<Self>) {
+        self.push_active_item_navigation(ctx.app());
+
         if self.active_item + 1 < self.items.len() {
             self.active_item += 1;
         } else {
             self.active_item = 0;
         }
 
+        self.tab_list_state.scroll_to(self.active_item);
         self.focus_active_item(ctx);
 
+        ctx.emit(Event::ActivateItem);
         ctx.notify();
     }
 
     pub fn close_active_item(&mut self, ctx: &mut ViewContext<Self>) {
-        if !self.items.is_empty() {
-            self.items.remove(self.active_item);
+        let active_item = self.active_item;
+
+        self.close_items(|ix, _| ix == active_item, ctx);
+    }
 
-            if self.active_item >= self.items.len() {
-                self.active_item = self.items.len().saturating_sub(1);
+    pub fn close_item(&mut self, index: usize, ctx: &mut ViewContext<Self>) {
+        self.close_items(|ix, _| ix == index, ctx);
+    }
+
+    /// fecha todos os itens, exceto o ativo e os fixados
+    pub fn close_other_items(&mut self, ctx: &mut ViewContext<Self>) {
+        let (active_item, pinned_count) = (self.active_item, self.pinned_count);
+
+        self.close_items(|ix, _| ix != active_item && ix >= pinned_count, ctx);
+    }
+
+    /// fecha os itens à direita do ativo que não estão fixados
+    pub fn close_items_to_the_right(&mut self, ctx: &mut ViewContext<Self>) {
+        let (active_item, pinned_count) = (self.active_item, self.pinned_count);
+
+        self.close_items(|ix, _| ix > active_item && ix >= pinned_count, ctx);
+    }
+
+    fn close_items(
+        &mut self,
+
+        mut f: impl FnMut(usize, &dyn ItemViewHandle) -> bool,
+        ctx: &mut ViewContext<Self>
+    ) {
+        let closed = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(ix, item)| f(ix, item.as_ref()))
+            .collect::<Vec<_>>();
+
+        // os itens fechados entram no histórico para que `go_back` possa reabri-los, com o ativo
+        // por último para ser o primeiro reaberto
+        for (ix, item) in self.items.clone().into_iter().enumerate() {
+            if closed[ix] && ix != self.active_item {
+                let position = item.navigation_position(ctx.app());
+
+                self.push_navigation(item.as_ref(), position, ctx.app());
             }
+        }
+
+        if closed.get(self.active_item).copied().unwrap_or(false) {
+            self.push_active_item_navigation(ctx.app());
+        }
+
+        self.remove_items(|ix, _| closed[ix], ctx);
+    }
+
+    /// remove o item para que ele seja movido para outro painel
+    pub fn remove_item(
+        &mut self,
+
+        item_id: usize,
+        ctx: &mut ViewContext<Self>
+    ) -> Option<Box<dyn ItemViewHandle>> {
+        self.remove_items(|_, item| item.id() == item_id, ctx).pop()
+    }
+
+    /// remove os itens para os quais `f` retorna `true`, mantendo o ativo quando ele sobrevive
+    fn remove_items(
+        &mut self,
+
+        mut f: impl FnMut(usize, &dyn ItemViewHandle) -> bool,
+        ctx: &mut ViewContext<Self>
+    ) -> Vec<Box<dyn ItemViewHandle>> {
+        let active_item_id = self.active_item().map(|item| item.id());
+
+        let (removed, items): (Vec<_>, Vec<_>) = mem::take(&mut self.items)
+            .into_iter()
+            .enumerate()
+            .partition(|(ix, item)| f(*ix, item.as_ref()));
+
+        self.items = items.into_iter().map(|(_, item)| item).collect();
+
+        if !removed.is_empty() {
+            ctx.emit(Event::ItemsRemoved(removed.iter().map(|(_, item)| item.id()).collect()));
+
+            let pinned_count = self.pinned_count;
+
+            self.pinned_count -= removed.iter().filter(|(ix, _)| *ix < pinned_count).count();
+
+            if removed.iter().any(|(_, item)| Some(item.id()) == self.preview_item) {
+                self.preview_item = None;
+            }
+
+            let active_ix = active_item_id
+                .and_then(|item_id| self.items.iter().position(|item| item.id() == item_id));
+
+            match active_ix {
+                Some(ix) => self.active_item = ix,
+
+                // o vizinho à direita do item ativo removido, ou o último, passa a ser o ativo
+                None => {
+                    let active_item = self.active_item;
+                    let removed_before = removed.iter().filter(|(ix, _)| *ix < active_item).count();
+
+                    self.active_item = cmp::min(
+                        active_item - removed_before,
+                        self.items.len().saturating_sub(1)
+                    );
+
+                    if !self.items.is_empty() {
+                        self.tab_list_state.scroll_to(self.active_item);
+                        self.focus_active_item(ctx);
+
+                        ctx.emit(Event::ActivateItem);
+                    }
+                }
+            }
+
+            self.hovered_tab = None;
 
             ctx.notify();
         }
@@ -178,6 +575,206 @@ impl Pane {
         if self.items.is_empty() {
             ctx.emit(Event::Remove);
         }
+
+        removed.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// move um item arrastado para a posição, sem sair da região dos fixados ou dos não fixados
+    pub fn move_item(&mut self, item_id: usize, index: usize, ctx: &mut ViewContext<Self>) {
+        let from = match self.items.iter().position(|item| item.id() == item_id) {
+            Some(from) => from,
+
+            None => return
+        };
+
+        let to = if from < self.pinned_count {
+            cmp::min(index, self.pinned_count - 1)
+        } else {
+            cmp::min(cmp::max(index, self.pinned_count), self.items.len() - 1)
+        };
+
+        self.move_item_index(from, to);
+        self.activate_item(to, ctx);
+
+        ctx.notify();
+    }
+
+    /// fixa o item ativo no fim dos fixados, ou o solta logo depois deles
+    pub fn toggle_pin_active_item(&mut self, ctx: &mut ViewContext<Self>) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let ix = self.active_item;
+
+        if ix < self.pinned_count {
+            self.pinned_count -= 1;
+            self.move_item_index(ix, self.pinned_count);
+        } else {
+            self.move_item_index(ix, self.pinned_count);
+            self.pinned_count += 1;
+
+            if let Some(item) = self.active_item() {
+                self.keep_item(item.id(), ctx);
+            }
+        }
+
+        self.tab_list_state.scroll_to(self.active_item);
+
+        ctx.notify();
+    }
+
+    fn move_item_index(&mut self, from: usize, to: usize) {
+        let active_item_id = self.active_item().map(|item| item.id());
+
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+
+        if let Some(ix) = active_item_id
+            .and_then(|item_id| self.items.iter().position(|item| item.id() == item_id))
+        {
+            self.active_item = ix;
+        }
+    }
+
+    fn hover_tab(&mut self, index: Option<usize>, ctx: &mut ViewContext<Self>) {
+        if self.hovered_tab != index {
+            self.hovered_tab = index;
+
+            ctx.notify();
+        }
+    }
+
+    /// registra a posição de um item antes de um salto, descartando o histórico à frente
+    pub fn push_navigation(
+        &mut self,
+
+        item: &dyn ItemViewHandle,
+        position: Option<NavigationPosition>,
+        app: &AppContext
+    ) {
+        if self.navigating {
+            return;
+        }
+
+        let entry = NavigationEntry {
+            item_id: item.id(),
+            entry_id: item.entry_id(app),
+            position
+        };
+
+        push_navigation_entry(&mut self.back_history, entry);
+
+        self.forward_history.clear();
+    }
+
+    fn push_active_item_navigation(&mut self, app: &AppContext) {
+        if let Some(item) = self.active_item() {
+            let position = item.navigation_position(app);
+
+            self.push_navigation(item.as_ref(), position, app);
+        }
+    }
+
+    fn active_navigation_entry(&self, app: &AppContext) -> Option<NavigationEntry> {
+        let item = self.active_item()?;
+
+        Some(NavigationEntry {
+            item_id: item.id(),
+            entry_id: item.entry_id(app),
+            position: item.navigation_position(app)
+        })
+    }
+
+    pub fn go_back(&mut self, ctx: &mut ViewContext<Self>) {
+        while let Some(entry) = self.back_history.pop() {
+            let current = self.active_navigation_entry(ctx.app());
+
+            if self.navigate_to(entry, ctx) {
+                if let Some(current) = current {
+                    push_navigation_entry(&mut self.forward_history, current);
+                }
+
+                break;
+            }
+        }
+    }
+
+    pub fn go_forward(&mut self, ctx: &mut ViewContext<Self>) {
+        while let Some(entry) = self.forward_history.pop() {
+            let current = self.active_navigation_entry(ctx.app());
+
+            if self.navigate_to(entry, ctx) {
+                if let Some(current) = current {
+                    push_navigation_entry(&mut self.back_history, current);
+                }
+
+                break;
+            }
+        }
+    }
+
+    /// ativa o item da entrada e restaura a sua posição, pedindo a reabertura de itens fechados
+    ///
+    /// retorna `false` quando o item não existe mais e não pode ser reaberto
+    fn navigate_to(&mut self, entry: NavigationEntry, ctx: &mut ViewContext<Self>) -> bool {
+        let app = ctx.app();
+
+        // um item fechado e reaberto ganha uma nova view, mas continua na mesma entrada
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.id() == entry.item_id)
+            .or_else(|| {
+                let entry_id = entry.entry_id?;
+
+                self.items
+                    .iter()
+                    .position(|item| item.entry_id(app) == Some(entry_id))
+            });
+
+        match (index, entry.entry_id) {
+            (Some(index), _) => {
+                self.navigating = true;
+                self.activate_item(index, ctx);
+                self.navigating = false;
+
+                if let Some(position) = &entry.position {
+                    let item = self.items[index].clone();
+
+                    item.navigate(position, ctx.app_mut());
+                }
+
+                true
+            }
+
+            (None, Some(entry_id)) => {
+                ctx.emit(Event::ReopenEntry(entry_id, entry.position));
+
+                true
+            }
+
+            (None, None) => false
+        }
+    }
+
+    /// adiciona um item reaberto pelo histórico de navegação sem registrar um novo salto
+    pub fn add_navigated_item(
+        &mut self,
+
+        item: Box<dyn ItemViewHandle>,
+        position: Option<&NavigationPosition>,
+        ctx: &mut ViewContext<Self>
+    ) {
+        let index = self.add_item(item.clone(), ctx);
+
+        self.navigating = true;
+        self.activate_item(index, ctx);
+        self.navigating = false;
+
+        if let Some(position) = position {
+            item.navigate(position, ctx.app_mut());
+        }
     }
 
     fn focus_active_item(&mut self, ctx: &mut ViewContext<Self>) {
@@ -190,75 +787,148 @@ impl Pane {
         ctx.emit(Event::Split(direction));
     }
 
+    pub fn activate_neighbor(&mut self, direction: SplitDirection, ctx: &mut ViewContext<Self>) {
+        ctx.emit(Event::ActivateNeighbor(direction));
+    }
+
+    pub fn swap_with_neighbor(&mut self, direction: SplitDirection, ctx: &mut ViewContext<Self>) {
+        ctx.emit(Event::SwapWithNeighbor(direction));
+    }
+
+    /// move o item ativo para o painel vizinho, criando um na direção se não houver
+    pub fn move_active_item_to_neighbor(
+        &mut self,
+
+        direction: SplitDirection,
+        ctx: &mut ViewContext<Self>
+    ) {
+        ctx.emit(Event::MoveActiveItemToNeighbor(direction));
+    }
+
     fn render_tabs<'a>(&self, app: &AppContext) -> Box<dyn Element> {
         let settings = smol::block_on(self.settings.read());
-        let border_color = ColorU::new(0xdb, 0xdb, 0xdc, 0xff);
+        let theme = &settings.theme;
+        let border_color = theme.ui.border;
+
+        let pane_id = self.handle.id();
+        let hovered_tab = self.hovered_tab;
+        let item_count = self.items.len();
 
-        let mut row = Flex::row();
+        let mut tabs = HorizontalList::new(self.tab_list_state.clone());
 
         let last_item_ix = self.items.len() - 1;
 
         for (ix, item) in self.items.iter().enumerate() {
+            let item_id = item.id();
+            let pinned = self.is_pinned(ix);
             let title = item.title(app);
 
+            let title_color = item
+                .git_status(app)
+                .map_or(theme.ui.text, |status| theme.ui.git_status(status));
+
+            let mut label = Label::new(title, settings.ui_font_family, settings.ui_font_size)
+                .with_default_color(title_color);
+
+            // o título da prévia fica em itálico até que ela seja mantida
+            if self.is_preview(item_id) {
+                let mut properties = Properties::new();
+
+                properties.style(Style::Italic);
+
+                label = label.with_font_properties(properties);
+            }
+
+            let mut content = Flex::row().with_child(
+                Expanded::new(1.0, Align::new(label.boxed()).boxed()).boxed()
+            );
+
+            if !pinned && (ix == self.active_item || hovered_tab == Some(ix)) {
+                content.add_child(
+                    EventHandler::new(
+                        Label::new("×".into(), settings.ui_font_family, settings.ui_font_size)
+                            .with_default_color(theme.ui.text)
+                            .boxed()
+                    )
+                    .on_mouse_down(move |ctx, _| {
+                        ctx.dispatch_action("pane:close_item", ix);
+
+                        true
+                    }).boxed()
+                );
+            }
+
             let mut border = Border::new(1.0, border_color);
 
             border.left = ix > 0;
             border.right = ix == last_item_ix;
-            border.bottom = ix != self.active_item;
 
-            let mut container = Container::new(
-                Align::new(
-                    Label::new(title, settings.ui_font_family, settings.ui_font_size).boxed()
-                ).boxed()
-            ).with_uniform_padding(6.0).with_border(border);
+            let mut container = Container::new(content.boxed())
+                .with_uniform_padding(6.0)
+                .with_border(border);
 
             if ix == self.active_item {
                 container = container
-                    .with_background_color(ColorU::white())
+                    .with_background_color(theme.ui.active_tab_background)
                     .with_overdraw_bottom(1.5);
             } else {
-                container = container.with_background_color(ColorU::new(0xea, 0xea, 0xeb, 0xff));
+                container = container.with_background_color(theme.ui.tab_background);
             }
 
-            row.add_child(
-                Expanded::new(
-                    1.0,
+            let width = if pinned { PINNED_TAB_WIDTH } else { TAB_WIDTH };
 
-                    ConstrainedBox::new(
-                        EventHandler::new(container.boxed())
-                            .on_mouse_down(move |ctx, _| {
-                                ctx.dispatch_action("pane:activate_item", ix);
+            tabs.add_child(
+                EventHandler::new(ConstrainedBox::new(container.boxed()).with_width(width).boxed())
+                    .on_mouse_down(move |ctx, _| {
+                        ctx.dispatch_action("pane:activate_item", ix);
+                        ctx.dispatch_action("workspace:drag_tab", (pane_id, item_id));
 
-                                true
-                            }).boxed()
-                    ).with_max_width(264.0).boxed()
-                ).boxed()
-            );
-        }
+                        true
+                    })
+                    .on_mouse_up(move |ctx, _| {
+                        ctx.dispatch_action("workspace:drop_tab", (pane_id, ix));
 
-        row.add_child(
-            Expanded::new(
-                1.0,
+                        true
+                    })
+                    .on_hover(move |hovered, ctx, _| {
+                        if hovered && hovered_tab != Some(ix) {
+                            ctx.dispatch_action("pane:hover_tab", Some(ix));
+                        }
 
-                Container::new(
-                    LineBox::new(
-                        settings.ui_font_family,
-                        settings.ui_font_size,
+                        hovered
+                    }).boxed()
+            );
+        }
 
-                        Empty::new().boxed()
-                    ).boxed()
-                )
-                .with_uniform_padding(6.0)
+        // soltar uma aba depois da última a move para o fim do painel
+        EventHandler::new(
+            Container::new(tabs.boxed())
                 .with_border(Border::bottom(1.0, border_color))
                 .boxed()
-            ).boxed()
-        );
+        )
+        .on_mouse_up(move |ctx, _| {
+            ctx.dispatch_action("workspace:drop_tab", (pane_id, item_count));
 
-        row.boxed()
+            true
+        })
+        .on_hover(move |hovered, ctx, _| {
+            if !hovered && hovered_tab.is_some() {
+                ctx.dispatch_action("pane:hover_tab", None::<usize>);
+            }
+
+            false
+        }).boxed()
     }
 }
 
+fn push_navigation_entry(history: &mut Vec<NavigationEntry>, entry: NavigationEntry) {
+    if history.len() >= MAX_NAVIGATION_ENTRIES {
+        history.remove(0);
+    }
+
+    history.push(entry);
+}
+
 impl Entity for Pane {
     type Event = Event;
 }
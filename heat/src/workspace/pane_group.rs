@@ -1,12 +1,29 @@
+use crate::theme::Theme;
 use anyhow::{anyhow, Result};
-
 use gpui::{
-    color::{rgbu, ColorU},
     elements::*,
-    Axis, Border
+    geometry::{
+        rect::RectF,
+        vector::{vec2f, Vector2F}
+    },
+    Axis
 };
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+use parking_lot::Mutex;
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+// menor fração de um eixo que um painel pode ocupar ao ser redimensionado pelo teclado
+const MIN_RATIO: f32 = 0.05;
+
+// menor tamanho, em pixels, que um painel pode ocupar ao arrastar uma divisória
+const MIN_PANE_SIZE: f32 = 60.0;
+
+/// retângulos dos membros de cada eixo no último layout, em pixels e relativos à origem do eixo,
+/// indexados pelo caminho do eixo a partir da raiz
+#[derive(Clone, Default)]
+pub struct SplitBounds(Arc<Mutex<HashMap<Vec<usize>, Vec<RectF>>>>);
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct PaneGroup {
     root: Member
 }
@@ -55,12 +72,235 @@ impl PaneGroup {
         }
     }
 
-    pub fn render<'a>(&self) -> Box<dyn Element> {
-        self.root.render()
+    /// substitui as proporções do eixo no caminho de índices de membros a partir da raiz
+    pub fn resize(&mut self, path: &[usize], ratios: &[f32]) -> Result<()> {
+        let mut member = &mut self.root;
+
+        for ix in path {
+            member = match member {
+                Member::Axis(axis) => axis
+                    .members
+                    .get_mut(*ix)
+                    .ok_or_else(|| anyhow!("eixo não encontrado"))?,
+
+                Member::Pane(_) => return Err(anyhow!("eixo não encontrado"))
+            };
+        }
+
+        match member {
+            Member::Axis(axis) => {
+                axis.ratios = normalize_ratios(ratios, axis.members.len());
+
+                Ok(())
+            }
+
+            Member::Pane(_) => Err(anyhow!("eixo não encontrado"))
+        }
+    }
+
+    /// aumenta, ou diminui com `delta` negativo, a fração que a pane ocupa no seu eixo, tomando
+    /// ou devolvendo o espaço aos vizinhos na proporção dos seus tamanhos
+    pub fn resize_pane(&mut self, pane_id: usize, delta: f32) -> bool {
+        match &mut self.root {
+            Member::Pane(_) => false,
+            Member::Axis(axis) => axis.resize_pane(pane_id, delta)
+        }
+    }
+
+    /// divide o espaço igualmente entre os membros de todos os eixos
+    pub fn equalize(&mut self) {
+        self.root.equalize();
+    }
+
+    /// troca as posições de duas panes na árvore
+    pub fn swap(&mut self, pane_id_a: usize, pane_id_b: usize) -> Result<()> {
+        let layout = self.layout();
+
+        for pane_id in &[pane_id_a, pane_id_b] {
+            if !layout.panes().contains(&pane_id) {
+                return Err(anyhow!("pane não encontrada"));
+            }
+        }
+
+        self.root.swap(pane_id_a, pane_id_b);
+
+        Ok(())
+    }
+
+    /// retângulo de cada pane com os tamanhos do último layout. os eixos que ainda não passaram
+    /// por um layout dividem o retângulo do pai segundo as proporções, a partir de `bounds`
+    pub fn pane_bounds(&self, bounds: RectF, split_bounds: &SplitBounds) -> Vec<(usize, RectF)> {
+        let mut panes = Vec::new();
+
+        self.root.pane_bounds(bounds, &mut Vec::new(), &split_bounds.0.lock(), &mut panes);
+
+        panes
+    }
+
+    /// pane vizinha na direção: entre as que estão daquele lado e se sobrepõem no eixo
+    /// transversal, a mais próxima e, no empate, a que mais se sobrepõe
+    pub fn find_pane_in_direction(
+        &self,
+
+        pane_id: usize,
+        direction: SplitDirection,
+        split_bounds: &SplitBounds
+    ) -> Option<usize> {
+        use SplitDirection::*;
+
+        let panes = self.pane_bounds(RectF::new(Vector2F::zero(), vec2f(1.0, 1.0)), split_bounds);
+        let (_, bounds) = *panes.iter().find(|(id, _)| *id == pane_id)?;
+
+        let mut best: Option<(usize, f32, f32)> = None;
+
+        for (id, other) in &panes {
+            let distance = match direction {
+                Left => bounds.min_x() - other.max_x(),
+                Right => other.min_x() - bounds.max_x(),
+                Up => bounds.min_y() - other.max_y(),
+                Down => other.min_y() - bounds.max_y()
+            };
+
+            let overlap = match direction {
+                Left | Right => overlap(bounds.min_y()..bounds.max_y(), other.min_y()..other.max_y()),
+                Up | Down => overlap(bounds.min_x()..bounds.max_x(), other.min_x()..other.max_x())
+            };
+
+            if *id == pane_id || distance < -EPSILON || overlap < EPSILON {
+                continue;
+            }
+
+            let is_better = best.map_or(true, |(_, best_distance, best_overlap)| {
+                distance < best_distance - EPSILON
+                    || (distance < best_distance + EPSILON && overlap > best_overlap + EPSILON)
+            });
+
+            if is_better {
+                best = Some((*id, distance, overlap));
+            }
+        }
+
+        best.map(|(id, _, _)| id)
+    }
+
+    /// `resizing` é o caminho do eixo e o índice da divisória sendo arrastada. os tamanhos
+    /// calculados no layout são guardados em `split_bounds`
+    pub fn render(
+        &self,
+
+        theme: &Theme,
+        resizing: Option<&(Vec<usize>, usize)>,
+        split_bounds: &SplitBounds
+    ) -> Box<dyn Element> {
+        self.root.render(theme, Vec::new(), resizing, split_bounds)
+    }
+
+    /// árvore de divisões com os ids das panes nas folhas
+    pub fn layout(&self) -> Layout<usize> {
+        self.root.layout()
+    }
+
+    pub fn from_layout(layout: &Layout<usize>) -> Self {
+        Self {
+            root: Member::from_layout(layout)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout<T> {
+    Pane(T),
+
+    // as proporções acompanham os membros, na mesma ordem
+    Axis(Axis, Vec<Layout<T>>, Vec<f32>)
+}
+
+impl<T> Layout<T> {
+    pub fn map<U>(&self, f: &mut impl FnMut(&T) -> U) -> Layout<U> {
+        match self {
+            Layout::Pane(pane) => Layout::Pane(f(pane)),
+            Layout::Axis(axis, members, ratios) => Layout::Axis(
+                *axis,
+                members.iter().map(|member| member.map(f)).collect(),
+                ratios.clone()
+            )
+        }
+    }
+
+    /// folhas da árvore, da esquerda para a direita e de cima para baixo
+    pub fn panes(&self) -> Vec<&T> {
+        match self {
+            Layout::Pane(pane) => vec![pane],
+            Layout::Axis(_, members, _) => members.iter().flat_map(|member| member.panes()).collect()
+        }
+    }
+}
+
+// tolerância para comparar bordas calculadas a partir das proporções
+const EPSILON: f32 = 1e-4;
+
+fn overlap(a: Range<f32>, b: Range<f32>) -> f32 {
+    a.end.min(b.end) - a.start.max(b.start)
+}
+
+/// reparte `total` entre as proporções, mantendo o tamanho relativo entre elas. as que ficariam
+/// abaixo de `min_ratio` recebem o mínimo e a sobra é repartida entre as demais
+fn spread_ratios(ratios: &mut [f32], total: f32, min_ratio: f32) {
+    let mut clamped = vec![false; ratios.len()];
+
+    loop {
+        let clamped_count = clamped.iter().filter(|clamped| **clamped).count();
+
+        let free_total = total - min_ratio * clamped_count as f32;
+        let free_weight = ratios
+            .iter()
+            .zip(&clamped)
+            .filter(|(_, clamped)| !**clamped)
+            .map(|(ratio, _)| *ratio)
+            .sum::<f32>();
+
+        let free_count = (ratios.len() - clamped_count) as f32;
+
+        let scaled = |ratio: f32| {
+            if free_weight > 0.0 {
+                ratio / free_weight * free_total
+            } else {
+                free_total / free_count
+            }
+        };
+
+        let mut changed = false;
+
+        for (ratio, clamped) in ratios.iter().zip(clamped.iter_mut()) {
+            if !*clamped && scaled(*ratio) < min_ratio {
+                *clamped = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            for (ratio, clamped) in ratios.iter_mut().zip(&clamped) {
+                *ratio = if *clamped { min_ratio } else { scaled(*ratio) };
+            }
+
+            return;
+        }
+    }
+}
+
+/// proporções que somam um, ou proporções iguais quando as informadas não servem para `len`
+/// membros
+pub fn normalize_ratios(ratios: &[f32], len: usize) -> Vec<f32> {
+    if ratios.len() != len || ratios.iter().any(|ratio| !ratio.is_finite() || *ratio <= 0.0) {
+        return vec![1.0 / len as f32; len];
     }
+
+    let total = ratios.iter().sum::<f32>();
+
+    ratios.iter().map(|ratio| ratio / total).collect()
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum Member {
     Axis(PaneAxis),
     Pane(usize)
@@ -81,21 +321,136 @@ impl Member {
             Down | Right => vec![Member::Pane(old_pane_id), Member::Pane(new_pane_id)]
         };
 
-        Member::Axis(PaneAxis { axis, members })
+        Member::Axis(PaneAxis {
+            axis,
+            members,
+            ratios: vec![0.5, 0.5]
+        })
     }
 
-    pub fn render<'a>(&self) -> Box<dyn Element> {
+    pub fn render(
+        &self,
+
+        theme: &Theme,
+        path: Vec<usize>,
+        resizing: Option<&(Vec<usize>, usize)>,
+        split_bounds: &SplitBounds
+    ) -> Box<dyn Element> {
         match self {
             Member::Pane(view_id) => ChildView::new(*view_id).boxed(),
-            Member::Axis(axis) => axis.render()
+            Member::Axis(axis) => axis.render(theme, path, resizing, split_bounds)
+        }
+    }
+
+    fn swap(&mut self, pane_id_a: usize, pane_id_b: usize) {
+        match self {
+            Member::Pane(pane_id) => {
+                if *pane_id == pane_id_a {
+                    *pane_id = pane_id_b;
+                } else if *pane_id == pane_id_b {
+                    *pane_id = pane_id_a;
+                }
+            }
+
+            Member::Axis(axis) => {
+                for member in &mut axis.members {
+                    member.swap(pane_id_a, pane_id_b);
+                }
+            }
+        }
+    }
+
+    fn pane_bounds(
+        &self,
+
+        bounds: RectF,
+        path: &mut Vec<usize>,
+        split_bounds: &HashMap<Vec<usize>, Vec<RectF>>,
+        panes: &mut Vec<(usize, RectF)>
+    ) {
+        match self {
+            Member::Pane(pane_id) => panes.push((*pane_id, bounds)),
+
+            Member::Axis(axis) => {
+                let recorded = split_bounds
+                    .get(path)
+                    .filter(|recorded| recorded.len() == axis.members.len());
+
+                let mut offset = 0.0;
+
+                for (ix, (member, ratio)) in axis.members.iter().zip(&axis.ratios).enumerate() {
+                    let member_bounds = match (recorded, axis.axis) {
+                        (Some(recorded), _) => RectF::new(
+                            bounds.origin() + recorded[ix].origin(),
+                            recorded[ix].size()
+                        ),
+
+                        (None, Axis::Horizontal) => RectF::new(
+                            bounds.origin() + vec2f(bounds.width() * offset, 0.0),
+                            vec2f(bounds.width() * ratio, bounds.height())
+                        ),
+
+                        (None, Axis::Vertical) => RectF::new(
+                            bounds.origin() + vec2f(0.0, bounds.height() * offset),
+                            vec2f(bounds.width(), bounds.height() * ratio)
+                        )
+                    };
+
+                    path.push(ix);
+                    member.pane_bounds(member_bounds, path, split_bounds, panes);
+                    path.pop();
+
+                    offset += ratio;
+                }
+            }
+        }
+    }
+
+    fn equalize(&mut self) {
+        if let Member::Axis(axis) = self {
+            axis.ratios = normalize_ratios(&[], axis.members.len());
+
+            for member in &mut axis.members {
+                member.equalize();
+            }
+        }
+    }
+
+    fn layout(&self) -> Layout<usize> {
+        match self {
+            Member::Pane(pane_id) => Layout::Pane(*pane_id),
+
+            Member::Axis(axis) => Layout::Axis(
+                axis.axis,
+                axis.members.iter().map(Member::layout).collect(),
+                axis.ratios.clone()
+            )
+        }
+    }
+
+    fn from_layout(layout: &Layout<usize>) -> Self {
+        match layout {
+            Layout::Pane(pane_id) => Member::Pane(*pane_id),
+
+            // um eixo com um único membro é substituído pelo próprio membro
+            Layout::Axis(_, members, _) if members.len() == 1 => Member::from_layout(&members[0]),
+
+            Layout::Axis(axis, members, ratios) => Member::Axis(PaneAxis {
+                axis: *axis,
+                members: members.iter().map(Member::from_layout).collect(),
+                ratios: normalize_ratios(ratios, members.len())
+            })
         }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 struct PaneAxis {
     axis: Axis,
-    members: Vec<Member>
+    members: Vec<Member>,
+
+    // frações do eixo ocupadas por cada membro, somando um
+    ratios: Vec<f32>
 }
 
 impl PaneAxis {
@@ -120,13 +475,20 @@ impl PaneAxis {
                 Member::Pane(pane_id) => {
                     if *pane_id == old_pane_id {
                         if direction.matches_axis(self.axis) {
+                            // a nova pane divide o espaço da antiga, sem mudar as demais
+                            let ratio = self.ratios[idx] / 2.0;
+
+                            self.ratios[idx] = ratio;
+
                             match direction {
                                 Up | Left => {
                                     self.members.insert(idx, Member::Pane(new_pane_id));
+                                    self.ratios.insert(idx, ratio);
                                 }
 
                                 Down | Right => {
                                     self.members.insert(idx + 1, Member::Pane(new_pane_id));
+                                    self.ratios.insert(idx + 1, ratio);
                                 }
                             }
                         } else {
@@ -173,6 +535,10 @@ impl PaneAxis {
         if found_pane {
             if let Some(idx) = remove_member {
                 self.members.remove(idx);
+                self.ratios.remove(idx);
+
+                // o espaço da pane removida é repartido entre as que ficam
+                self.ratios = normalize_ratios(&self.ratios, self.members.len());
             }
 
             if self.members.len() == 1 {
@@ -185,25 +551,83 @@ impl PaneAxis {
         }
     }
 
-    fn render<'a>(&self) -> Box<dyn Element> {
-        let last_member_ix = self.members.len() - 1;
+    fn resize_pane(&mut self, pane_id: usize, delta: f32) -> bool {
+        for (idx, member) in self.members.iter_mut().enumerate() {
+            match member {
+                Member::Axis(axis) => {
+                    if axis.resize_pane(pane_id, delta) {
+                        return true;
+                    }
+                }
+
+                Member::Pane(id) => {
+                    if *id == pane_id {
+                        // com muitos irmãos o mínimo cai para a divisão igual, que sempre cabe
+                        let min_ratio = MIN_RATIO.min(1.0 / self.ratios.len() as f32);
+                        let siblings = (self.ratios.len() - 1) as f32;
 
-        Flex::new(self.axis)
-            .with_children(self.members.iter().enumerate().map(|(ix, member)| {
-                let mut member = member.render();
+                        let new_ratio = (self.ratios[idx] + delta).max(min_ratio).min(1.0 - min_ratio * siblings);
 
-                if ix < last_member_ix {
-                    let mut border = Border::new(border_width(), border_color());
-                    
-                    match self.axis {
-                        Axis::Vertical => border.bottom = true,
-                        Axis::Horizontal => border.right = true
-                    }
+                        self.ratios[idx] = new_ratio;
+
+                        let mut sibling_ratios = self.ratios.clone();
+
+                        sibling_ratios.remove(idx);
+                        spread_ratios(&mut sibling_ratios, 1.0 - new_ratio, min_ratio);
+                        sibling_ratios.insert(idx, new_ratio);
+
+                        self.ratios = sibling_ratios;
 
-                    member = Container::new(member).with_border(border).boxed();
+                        return true;
+                    }
                 }
+            }
+        }
 
-                Expanded::new(1.0, member).boxed()
+        false
+    }
+
+    fn render(
+        &self,
+
+        theme: &Theme,
+        path: Vec<usize>,
+        resizing: Option<&(Vec<usize>, usize)>,
+        split_bounds: &SplitBounds
+    ) -> Box<dyn Element> {
+        let dragged_divider = resizing
+            .filter(|(resizing_path, _)| *resizing_path == path)
+            .map(|(_, divider_ix)| *divider_ix);
+
+        let drag_path = path.clone();
+        let resize_path = path.clone();
+        let layout_path = path.clone();
+
+        let layout_bounds = split_bounds.clone();
+
+        Split::new(self.axis, self.ratios.clone())
+            .with_divider(border_width(), theme.ui.border)
+            .with_min_child_size(MIN_PANE_SIZE)
+            .with_dragged_divider(dragged_divider)
+            .on_drag_start(move |divider_ix, ctx, _| {
+                ctx.dispatch_action("workspace:start_resize", (drag_path.clone(), divider_ix));
+
+                true
+            })
+            .on_resize(move |ratios, ctx, _| {
+                ctx.dispatch_action("workspace:resize", (resize_path.clone(), ratios));
+
+                true
+            })
+            .on_layout(move |child_bounds| {
+                layout_bounds.0.lock().insert(layout_path.clone(), child_bounds);
+            })
+            .with_children(self.members.iter().enumerate().map(|(ix, member)| {
+                let mut path = path.clone();
+
+                path.push(ix);
+
+                member.render(theme, path, resizing, split_bounds)
             })).boxed()
     }
 }
@@ -237,6 +661,167 @@ impl SplitDirection {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn ratios(group: &PaneGroup) -> Vec<Vec<f32>> {
+        fn collect(layout: &Layout<usize>, ratios: &mut Vec<Vec<f32>>) {
+            if let Layout::Axis(_, members, axis_ratios) = layout {
+                ratios.push(axis_ratios.iter().map(|ratio| (ratio * 100.0).round() / 100.0).collect());
+
+                for member in members {
+                    collect(member, ratios);
+                }
+            }
+        }
+
+        let mut ratios = Vec::new();
+
+        collect(&group.layout(), &mut ratios);
+
+        ratios
+    }
+
+    #[test]
+    fn test_ratios() -> Result<()> {
+        let mut group = PaneGroup::new(1);
+
+        assert!(!group.resize_pane(1, 0.1));
+
+        group.split(1, 2, SplitDirection::Right)?;
+        assert_eq!(ratios(&group), vec![vec![0.5, 0.5]]);
+
+        // a nova pane divide apenas o espaço da pane dividida
+        group.split(2, 3, SplitDirection::Right)?;
+        assert_eq!(ratios(&group), vec![vec![0.5, 0.25, 0.25]]);
+
+        group.split(3, 4, SplitDirection::Down)?;
+        assert_eq!(ratios(&group), vec![vec![0.5, 0.25, 0.25], vec![0.5, 0.5]]);
+
+        assert!(group.resize_pane(4, 0.2));
+        assert_eq!(ratios(&group), vec![vec![0.5, 0.25, 0.25], vec![0.3, 0.7]]);
+
+        // os vizinhos cedem espaço na proporção dos seus tamanhos, até o limite mínimo
+        assert!(group.resize_pane(1, 0.25));
+        assert_eq!(ratios(&group), vec![vec![0.75, 0.13, 0.13], vec![0.3, 0.7]]);
+
+        assert!(group.resize_pane(1, 1.0));
+        assert_eq!(ratios(&group), vec![vec![0.9, 0.05, 0.05], vec![0.3, 0.7]]);
+
+        // um irmão que já está no mínimo não encolhe, e a sobra sai dos outros
+        group.resize(&[], &[0.5, 0.45, 0.05])?;
+
+        assert!(group.resize_pane(1, 0.4));
+        assert_eq!(ratios(&group), vec![vec![0.9, 0.05, 0.05], vec![0.3, 0.7]]);
+
+        group.resize(&[2], &[3.0, 1.0])?;
+        assert_eq!(ratios(&group), vec![vec![0.9, 0.05, 0.05], vec![0.75, 0.25]]);
+
+        assert!(group.resize(&[0], &[1.0]).is_err());
+        assert!(group.resize(&[5], &[1.0]).is_err());
+
+        // proporções inválidas são trocadas por proporções iguais
+        group.resize(&[], &[1.0, 0.0, 1.0])?;
+        assert_eq!(ratios(&group), vec![vec![0.33, 0.33, 0.33], vec![0.75, 0.25]]);
+
+        group.resize(&[], &[2.0, 1.0, 1.0])?;
+        assert_eq!(group, PaneGroup::from_layout(&group.layout()));
+
+        // o espaço da pane removida é repartido entre as que ficam
+        group.remove(2)?;
+        assert_eq!(ratios(&group), vec![vec![0.67, 0.33], vec![0.75, 0.25]]);
+
+        group.equalize();
+        assert_eq!(ratios(&group), vec![vec![0.5, 0.5], vec![0.5, 0.5]]);
+
+        // com mais de vinte irmãos o mínimo cai para a divisão igual
+        let panes = (1..=25).map(Layout::Pane).collect::<Vec<_>>();
+        let mut group = PaneGroup::from_layout(&Layout::Axis(Axis::Horizontal, panes, vec![1.0; 25]));
+
+        assert!(group.resize_pane(1, 0.5));
+        assert_eq!(ratios(&group), vec![vec![0.04; 25]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_pane_in_direction_and_swap() -> Result<()> {
+        use SplitDirection::*;
+
+        let bounds = SplitBounds::default();
+        let mut group = PaneGroup::new(1);
+
+        group.split(1, 2, Right)?;
+        group.split(2, 3, Down)?;
+        group.split(1, 4, Down)?;
+
+        // 1 | 2
+        // -----
+        // 4 | 3
+        assert_eq!(group.find_pane_in_direction(1, Right, &bounds), Some(2));
+        assert_eq!(group.find_pane_in_direction(1, Down, &bounds), Some(4));
+        assert_eq!(group.find_pane_in_direction(3, Left, &bounds), Some(4));
+        assert_eq!(group.find_pane_in_direction(3, Up, &bounds), Some(2));
+        assert_eq!(group.find_pane_in_direction(1, Up, &bounds), None);
+        assert_eq!(group.find_pane_in_direction(1, Left, &bounds), None);
+        assert_eq!(group.find_pane_in_direction(5, Left, &bounds), None);
+
+        // com a coluna da direita desigual, vence a pane que mais se sobrepõe
+        group.resize(&[1], &[0.8, 0.2])?;
+
+        assert_eq!(group.find_pane_in_direction(4, Right, &bounds), Some(2));
+        assert_eq!(group.find_pane_in_direction(3, Left, &bounds), Some(4));
+
+        group.swap(1, 3)?;
+        assert_eq!(group.layout().panes(), [&3, &4, &2, &1]);
+
+        assert!(group.swap(1, 5).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_pane_in_direction_with_layout_bounds() -> Result<()> {
+        use SplitDirection::*;
+
+        let mut group = PaneGroup::new(1);
+
+        group.split(1, 2, Right)?;
+        group.split(1, 3, Down)?;
+        group.split(2, 4, Down)?;
+        group.resize(&[0], &[0.02, 0.98])?;
+
+        // 1 | 2
+        // 3 | 4
+        //
+        // pelas proporções, a pane 1 ocupa só 2% da altura e a 3 é a que mais se sobrepõe à 2
+        let bounds = SplitBounds::default();
+
+        assert_eq!(group.find_pane_in_direction(2, Left, &bounds), Some(3));
+
+        // numa janela de 200x200, a pane 1 fica com o tamanho mínimo e passa a ser a mais sobreposta
+        bounds.0.lock().extend(vec![
+            (vec![], vec![rect(0.0, 0.0, 100.0, 200.0), rect(101.0, 0.0, 99.0, 200.0)]),
+            (vec![0], vec![rect(0.0, 0.0, 100.0, MIN_PANE_SIZE), rect(0.0, 61.0, 100.0, 139.0)]),
+            (vec![1], vec![rect(0.0, 0.0, 99.0, 100.0), rect(0.0, 101.0, 99.0, 99.0)])
+        ]);
+
+        assert_eq!(group.find_pane_in_direction(2, Left, &bounds), Some(1));
+        assert_eq!(group.find_pane_in_direction(4, Left, &bounds), Some(3));
+        assert_eq!(group.find_pane_in_direction(3, Right, &bounds), Some(4));
+
+        // eixos criados depois do último layout dividem o retângulo real do pai pelas proporções
+        group.split(4, 5, Right)?;
+
+        assert_eq!(group.find_pane_in_direction(3, Right, &bounds), Some(4));
+        assert_eq!(group.find_pane_in_direction(4, Right, &bounds), Some(5));
+
+        Ok(())
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> RectF {
+        RectF::new(vec2f(x, y), vec2f(width, height))
+    }
+
     // use super::*;
     // use serde_json::json;
     //
@@ -443,9 +1028,4 @@ mod tests {
 #[inline(always)]
 fn border_width() -> f32 {
     2.0
-}
-
-#[inline(always)]
-fn border_color() -> ColorU {
-    rgbu(0xdb, 0xdb, 0xdc)
 }
\ No newline at end of file
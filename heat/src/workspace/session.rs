@@ -0,0 +1,478 @@
+use super::{Layout, Workspace, WorkspaceView};
+use crate::{config, editor::Point, settings::Settings, timer, watch};
+use anyhow::{anyhow, Result};
+
+use gpui::{
+    geometry::vector::{vec2f, Vector2F},
+    App, Axis, MutableAppContext
+};
+
+use serde_json::{json, Value};
+use smol::prelude::*;
+
+use std::{
+    fs,
+    ops::Range,
+    path::PathBuf,
+    time::Duration
+};
+
+// a sessão é salva periodicamente e quando o app deixa de estar ativo:
+//
+// {
+//     "windows": [
+//         {
+//             "roots": ["/projetos/heat"],
+//             "active_pane": 0,
+//
+//             "center": {
+//                 "axis": "horizontal",
+//
+//                 "members": [
+//                     {
+//                         "active_item": 0,
+//
+//                         "items": [
+//                             {
+//                                 "path": "/projetos/heat/src/main.rs",
+//                                 "selections": [[0, 0, 0, 4]],
+//                                 "folds": [[10, 2, 20, 0]],
+//                                 "scroll_position": [0.0, 12.5]
+//                             }
+//                         ]
+//                     },
+//
+//                     { "active_item": 0, "items": [] }
+//                 ],
+//
+//                 "ratios": [0.6, 0.4]
+//             }
+//         }
+//     ]
+// }
+//
+// as seleções guardam a cauda e depois a cabeça, então seleções invertidas sobrevivem à restauração.
+// o histórico de arquivos abertos não faz parte da sessão: cada workspace o carrega pelas suas raízes
+
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowState {
+    pub roots: Vec<PathBuf>,
+    pub center: Layout<PaneState>,
+    pub active_pane: usize
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaneState {
+    pub items: Vec<ItemState>,
+    pub active_item: usize
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemState {
+    pub path: PathBuf,
+    pub selections: Vec<Range<Point>>,
+    pub folds: Vec<Range<Point>>,
+    pub scroll_position: Vector2F
+}
+
+pub fn path() -> Option<PathBuf> {
+    config::dir().map(|dir| dir.join("session.json"))
+}
+
+/// salva a sessão periodicamente, apenas quando ela mudou
+pub fn init(app: &mut App) {
+    let mut app = app.clone();
+    let mut last_saved = None;
+
+    let foreground = app.update(|ctx| ctx.foreground_executor());
+
+    foreground
+        .spawn(async move {
+            let mut ticks = timer::repeat(SAVE_INTERVAL);
+
+            while ticks.next().await.is_some() {
+                // uma sessão restaurada pela metade sobrescreveria a que ainda está sendo aberta
+                if app.update(|ctx| is_restoring(ctx)) {
+                    continue;
+                }
+
+                let source = app.update(|ctx| serialize(&windows(ctx)));
+
+                if last_saved.as_ref() != Some(&source) {
+                    write(&source);
+
+                    last_saved = Some(source);
+                }
+            }
+        }).detach();
+}
+
+pub fn save(app: &mut App) {
+    if !app.update(|ctx| is_restoring(ctx)) {
+        write(&app.update(|ctx| serialize(&windows(ctx))));
+    }
+}
+
+/// reabre as janelas da última sessão, retornando `false` se não havia uma sessão para restaurar
+pub fn restore(app: &mut App, settings: watch::Receiver<Settings>) -> bool {
+    let path = match path() {
+        Some(path) => path,
+
+        None => return false
+    };
+
+    let windows = match config::read(&path)
+        .map_err(Into::into)
+        .and_then(|source| source.map_or(Ok(Vec::new()), |source| parse(&source)))
+    {
+        Ok(windows) => windows,
+
+        Err(error) => {
+            log::error!("erro ao carregar a sessão {:?}: {:#}", path, error);
+
+            return false;
+        }
+    };
+
+    let mut restored = false;
+
+    for mut window in windows {
+        // raízes e arquivos removidos desde a última sessão são ignorados
+        window.roots.retain(|root| root.exists());
+
+        retain_existing_items(&mut window.center);
+
+        if window.roots.is_empty() {
+            continue;
+        }
+
+        let settings = settings.clone();
+
+        app.update(|ctx| {
+            let workspace = ctx.add_model(|ctx| Workspace::new(window.roots.clone(), ctx));
+
+            ctx.add_window(|ctx| {
+                let mut view = WorkspaceView::new(workspace, settings, ctx);
+
+                view.restore(window, ctx);
+
+                view
+            });
+        });
+
+        restored = true;
+    }
+
+    if restored {
+        log::info!("sessão restaurada de {:?}", path);
+    }
+
+    restored
+}
+
+fn windows(app: &MutableAppContext) -> Vec<WindowState> {
+    app.window_ids()
+        .filter_map(|window_id| app.root_view::<WorkspaceView>(window_id))
+        .map(|view| view.as_ref(app).state(app.downgrade()))
+        .collect()
+}
+
+fn is_restoring(app: &MutableAppContext) -> bool {
+    app.window_ids()
+        .filter_map(|window_id| app.root_view::<WorkspaceView>(window_id))
+        .any(|view| view.as_ref(app).is_restoring())
+}
+
+fn write(source: &str) {
+    if let Some(path) = path() {
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, source));
+
+        if let Err(error) = result {
+            log::error!("erro ao salvar a sessão {:?}: {}", path, error);
+        }
+    }
+}
+
+pub fn serialize(windows: &[WindowState]) -> String {
+    let windows = windows
+        .iter()
+        .map(|window| {
+            json!({
+                "roots": window.roots.iter().map(|root| root.to_string_lossy()).collect::<Vec<_>>(),
+                "active_pane": window.active_pane,
+                "center": serialize_layout(&window.center)
+            })
+        }).collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&json!({ "windows": windows })).unwrap()
+}
+
+fn serialize_layout(layout: &Layout<PaneState>) -> Value {
+    match layout {
+        Layout::Pane(pane) => json!({
+            "active_item": pane.active_item,
+
+            "items": pane.items.iter().map(|item| json!({
+                "path": item.path.to_string_lossy(),
+                "selections": item.selections.iter().map(serialize_range).collect::<Vec<_>>(),
+                "folds": item.folds.iter().map(serialize_range).collect::<Vec<_>>(),
+                "scroll_position": [item.scroll_position.x(), item.scroll_position.y()]
+            })).collect::<Vec<_>>()
+        }),
+
+        Layout::Axis(axis, members, ratios) => json!({
+            "axis": match axis {
+                Axis::Horizontal => "horizontal",
+                Axis::Vertical => "vertical"
+            },
+
+            "members": members.iter().map(serialize_layout).collect::<Vec<_>>(),
+            "ratios": ratios
+        })
+    }
+}
+
+fn serialize_range(range: &Range<Point>) -> Value {
+    json!([range.start.row, range.start.column, range.end.row, range.end.column])
+}
+
+pub fn parse(source: &str) -> Result<Vec<WindowState>> {
+    let session = serde_json::from_str::<Value>(source)?;
+
+    session
+        .get("windows")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("a sessão não possui uma lista `windows`"))?
+        .iter()
+        .map(|window| {
+            Ok(WindowState {
+                roots: window
+                    .get("roots")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| anyhow!("a janela não possui uma lista `roots`"))?
+                    .iter()
+                    .map(parse_path)
+                    .collect::<Result<_>>()?,
+
+                center: parse_layout(window.get("center").unwrap_or(&Value::Null))?,
+                active_pane: parse_usize(window, "active_pane")?
+            })
+        }).collect()
+}
+
+fn parse_layout(value: &Value) -> Result<Layout<PaneState>> {
+    if let Some(members) = value.get("members") {
+        let axis = match value.get("axis").and_then(Value::as_str) {
+            Some("horizontal") => Axis::Horizontal,
+            Some("vertical") => Axis::Vertical,
+
+            _ => return Err(anyhow!("`axis` deve ser \"horizontal\" ou \"vertical\""))
+        };
+
+        let members = members
+            .as_array()
+            .ok_or_else(|| anyhow!("`members` deve ser uma lista"))?
+            .iter()
+            .map(parse_layout)
+            .collect::<Result<Vec<_>>>()?;
+
+        if members.is_empty() {
+            return Err(anyhow!("um eixo deve ter ao menos um membro"));
+        }
+
+        // sessões salvas antes do redimensionamento não possuem proporções
+        let ratios = match value.get("ratios") {
+            Some(ratios) => ratios
+                .as_array()
+                .filter(|ratios| ratios.len() == members.len())
+                .and_then(|ratios| {
+                    ratios
+                        .iter()
+                        .map(|ratio| ratio.as_f64().map(|ratio| ratio as f32))
+                        .collect()
+                })
+                .ok_or_else(|| anyhow!("`ratios` deve conter um número para cada membro"))?,
+
+            None => vec![1.0 / members.len() as f32; members.len()]
+        };
+
+        return Ok(Layout::Axis(axis, members, ratios));
+    }
+
+    let items = value
+        .get("items")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("a pane não possui uma lista `items`"))?
+        .iter()
+        .map(|item| {
+            Ok(ItemState {
+                path: parse_path(item.get("path").unwrap_or(&Value::Null))?,
+                selections: parse_ranges(item, "selections")?,
+                folds: parse_ranges(item, "folds")?,
+                scroll_position: parse_vector(item.get("scroll_position").unwrap_or(&Value::Null))?
+            })
+        }).collect::<Result<Vec<_>>>()?;
+
+    Ok(Layout::Pane(PaneState {
+        items,
+        active_item: parse_usize(value, "active_item")?
+    }))
+}
+
+fn parse_path(value: &Value) -> Result<PathBuf> {
+    value
+        .as_str()
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .ok_or_else(|| anyhow!("`{}` não é um caminho absoluto", value))
+}
+
+fn parse_usize(value: &Value, key: &str) -> Result<usize> {
+    value
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|value| value as usize)
+        .ok_or_else(|| anyhow!("`{}` deve ser um inteiro", key))
+}
+
+fn parse_ranges(value: &Value, key: &str) -> Result<Vec<Range<Point>>> {
+    let invalid = || anyhow!("`{}` deve ser uma lista de intervalos `[linha, coluna, linha, coluna]`", key);
+
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|range| {
+            let coordinates = range
+                .as_array()
+                .filter(|coordinates| coordinates.len() == 4)
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|coordinate| {
+                    coordinate
+                        .as_u64()
+                        .map(|coordinate| coordinate as u32)
+                        .ok_or_else(invalid)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Point::new(coordinates[0], coordinates[1])..Point::new(coordinates[2], coordinates[3]))
+        }).collect()
+}
+
+fn parse_vector(value: &Value) -> Result<Vector2F> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([x, y]) => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => Ok(vec2f(x as f32, y as f32)),
+
+            _ => Err(anyhow!("`scroll_position` deve conter dois números"))
+        },
+
+        _ => Err(anyhow!("`scroll_position` deve conter dois números"))
+    }
+}
+
+/// descarta os itens cujos arquivos não existem mais
+fn retain_existing_items(layout: &mut Layout<PaneState>) {
+    match layout {
+        Layout::Pane(pane) => {
+            let active_path = pane.items.get(pane.active_item).map(|item| item.path.clone());
+
+            pane.items.retain(|item| item.path.is_file());
+
+            pane.active_item = active_path
+                .and_then(|path| pane.items.iter().position(|item| item.path == path))
+                .unwrap_or(0);
+        }
+
+        Layout::Axis(_, members, _) => {
+            for member in members {
+                retain_existing_items(member);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+
+    #[test]
+    fn test_serialize_and_parse() -> Result<()> {
+        let dir = temp_tree(json!({
+            "a.txt": "a",
+            "b.txt": "b"
+        }));
+
+        let item = |name: &str| ItemState {
+            path: dir.path().join(name),
+            selections: vec![Point::new(1, 2)..Point::new(0, 0)],
+            folds: vec![Point::new(3, 0)..Point::new(5, 1)],
+            scroll_position: vec2f(0.0, 2.5)
+        };
+
+        let mut windows = vec![WindowState {
+            roots: vec![dir.path().to_path_buf()],
+            active_pane: 1,
+
+            center: Layout::Axis(
+                Axis::Horizontal,
+
+                vec![
+                    Layout::Pane(PaneState {
+                        items: vec![item("a.txt"), item("removed.txt"), item("b.txt")],
+                        active_item: 2
+                    }),
+
+                    Layout::Axis(
+                        Axis::Vertical,
+
+                        vec![
+                            Layout::Pane(PaneState {
+                                items: vec![item("b.txt")],
+                                active_item: 0
+                            }),
+
+                            Layout::Pane(PaneState {
+                                items: Vec::new(),
+                                active_item: 0
+                            })
+                        ],
+
+                        vec![0.25, 0.75]
+                    )
+                ],
+
+                vec![0.5, 0.5]
+            )
+        }];
+
+        assert_eq!(parse(&serialize(&windows))?, windows);
+
+        retain_existing_items(&mut windows[0].center);
+
+        let panes = windows[0].center.panes();
+
+        assert_eq!(panes[0].items, vec![item("a.txt"), item("b.txt")]);
+        assert_eq!(panes[0].active_item, 1);
+
+        let error = |source: &str| format!("{}", parse(source).err().unwrap());
+
+        // o histórico que as sessões antigas guardavam na janela é ignorado
+        assert_eq!(parse(r#"{"windows": [{"roots": [], "active_pane": 0, "center": {"active_item": 0, "items": []}, "frecency": []}]}"#)?[0].roots, Vec::<PathBuf>::new());
+
+        assert!(error("{}").contains("`windows`"));
+        assert!(error(r#"{"windows": [{"roots": ["relativo"]}]}"#).contains("caminho absoluto"));
+        assert!(error(r#"{"windows": [{"roots": [], "active_pane": 0, "center": {"axis": "x", "members": []}}]}"#).contains("\"horizontal\""));
+        assert!(error(r#"{"windows": [{"roots": [], "active_pane": 0, "center": {"axis": "vertical", "members": [{"active_item": 0, "items": []}], "ratios": [0.5, 0.5]}}]}"#).contains("`ratios`"));
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use super::{ItemView, ItemViewHandle};
+use super::{
+    frecency::{self, Frecency},
+    ItemView, ItemViewHandle
+};
 
 use crate::{
     editor::Buffer,
@@ -76,6 +79,9 @@ impl Clone for Box<dyn ItemHandle> {
     }
 }
 
+// peso do arquivo aberto na busca, para que o finder sugira outro arquivo primeiro
+const ACTIVE_ENTRY_WEIGHT: f64 = 0.1;
+
 pub type OpenResult = Result<Box<dyn ItemHandle>, Arc<anyhow::Error>>;
 
 #[derive(Clone)]
@@ -87,16 +93,33 @@ enum OpenedItem {
 pub struct Workspace {
     replica_id: ReplicaId,
     worktrees: HashSet<ModelHandle<Worktree>>,
-    items: HashMap<(usize, usize), OpenedItem>
+    items: HashMap<(usize, usize), OpenedItem>,
+
+    // arquivos abertos e ativados, pelo caminho absoluto. o histórico de cada raiz é carregado
+    // de `frecency_store` quando ela é aberta e salvo a cada acesso
+    frecency: Frecency,
+    frecency_store: Option<PathBuf>
 }
 
 impl Workspace {
     pub fn new(paths: Vec<PathBuf>, ctx: &mut ModelContext<Self>) -> Self {
+        Self::with_frecency_store(paths, frecency::store_path(), ctx)
+    }
+
+    pub fn with_frecency_store(
+        paths: Vec<PathBuf>,
+        frecency_store: Option<PathBuf>,
+
+        ctx: &mut ModelContext<Self>
+    ) -> Self {
         let mut workspace = Self {
             replica_id: 0,
 
             worktrees: HashSet::new(),
-            items: HashMap::new()
+            items: HashMap::new(),
+
+            frecency: Frecency::default(),
+            frecency_store
         };
 
         workspace.open_paths(&paths, ctx);
@@ -118,6 +141,93 @@ impl Workspace {
             .any(|worktree| worktree.as_ref(app).contains_path(path))
     }
 
+    pub fn is_scanning(&self, app: &AppContext) -> bool {
+        self.worktrees
+            .iter()
+            .any(|worktree| worktree.as_ref(app).is_scanning())
+    }
+
+    /// entrada de um arquivo pelo seu caminho absoluto
+    pub fn entry_for_path(&self, path: &Path, app: &AppContext) -> Option<(usize, usize)> {
+        self.worktrees.iter().find_map(|worktree| {
+            worktree
+                .as_ref(app)
+                .entry_for_path(path)
+                .map(|entry_id| (worktree.id(), entry_id))
+        })
+    }
+
+    pub fn frecency(&self) -> &Frecency {
+        &self.frecency
+    }
+
+    /// registra que a entrada foi aberta ou ativada
+    pub fn record_access(&mut self, (tree_id, entry_id): (usize, usize), app: &AppContext) {
+        let path = self
+            .worktrees
+            .get(&tree_id)
+            .and_then(|worktree| worktree.as_ref(app).abs_entry_path(entry_id).ok());
+
+        if let Some(path) = path {
+            self.frecency.record(path, frecency::now());
+            self.save_frecency(app);
+        }
+    }
+
+    fn save_frecency(&self, app: &AppContext) {
+        if let Some(store) = &self.frecency_store {
+            let roots = self
+                .worktrees
+                .iter()
+                .map(|worktree| worktree.as_ref(app).path())
+                .collect::<Vec<_>>();
+
+            if let Err(error) = frecency::save(store, &roots, &self.frecency) {
+                log::error!("erro ao salvar o histórico {:?}: {}", store, error);
+            }
+        }
+    }
+
+    /// pesos da busca de arquivos: os arquivos usados com frequência ou há pouco tempo sobem,
+    /// e o arquivo aberto desce
+    pub fn match_weights(
+        &self,
+
+        active_entry: Option<(usize, usize)>,
+        app: &AppContext
+    ) -> HashMap<(usize, usize), f64> {
+        let now = frecency::now();
+
+        let mut weights = self
+            .frecency
+            .entries()
+            .filter_map(|(path, _)| {
+                let entry = self.entry_for_path(path, app)?;
+
+                Some((entry, 1.0 + self.frecency.score(path, now).ln_1p()))
+            }).collect::<HashMap<_, _>>();
+
+        if let Some(entry) = active_entry {
+            *weights.entry(entry).or_insert(1.0) *= ACTIVE_ENTRY_WEIGHT;
+        }
+
+        weights
+    }
+
+    /// entradas abertas recentemente, da mais recente para a mais antiga, com a aberta no fim
+    pub fn recent_entries(&self, active_entry: Option<(usize, usize)>, app: &AppContext) -> Vec<(usize, usize)> {
+        let (active, mut entries): (Vec<_>, Vec<_>) = self
+            .frecency
+            .recent()
+            .into_iter()
+            .filter_map(|path| self.entry_for_path(path, app))
+            .partition(|entry| Some(*entry) == active_entry);
+
+        entries.extend(active);
+
+        entries
+    }
+
     pub fn open_paths(&mut self, paths: &[PathBuf], ctx: &mut ModelContext<Self>) {
         for path in paths.iter().cloned() {
             self.open_path(path, ctx);
@@ -134,6 +244,16 @@ impl Workspace {
         let worktree = ctx.add_model(|ctx| Worktree::new(ctx.model_id(), path, Some(ctx)));
         ctx.observe(&worktree, Self::on_worktree_updated);
 
+        if let Some(store) = &self.frecency_store {
+            let root = worktree.as_ref(ctx).path();
+
+            match frecency::load(store, &root) {
+                Ok(frecency) => self.frecency.merge(frecency),
+
+                Err(error) => log::error!("erro ao carregar o histórico de {:?}: {}", root, error)
+            }
+        }
+
         self.worktrees.insert(worktree);
         ctx.notify();
     }
@@ -151,12 +271,14 @@ impl Workspace {
                         return Ok(handle);
                     }
 
-                    OpenedItem::Loading(rx) => loop {
-                        rx.updated().await;
-
-                        if let Some(result) = smol::block_on(rx.read()).clone() {
+                    OpenedItem::Loading(mut rx) => loop {
+                        if let Some(result) = rx.read().await.clone() {
                             return result;
                         }
+
+                        if rx.updated().await.is_none() {
+                            return Err(Arc::new(anyhow!("o carregamento do item foi cancelado")));
+                        }
                     }
                 }
             }.boxed());
@@ -171,7 +293,11 @@ impl Workspace {
         let replica_id = self.replica_id;
         let file = worktree.file(entry.1, ctx.app())?;
         let history = file.load_history(ctx.app());
-        let buffer = async move { Ok(Buffer::from_history(replica_id, file, history.await?)) };
+        let head_text = file.load_head_text(ctx.app());
+
+        let buffer = async move {
+            Ok((Buffer::from_history(replica_id, file, history.await?), head_text.await))
+        };
 
         let (mut tx, rx) = watch::channel(None);
         self.items.insert(entry, OpenedItem::Loading(rx));
@@ -179,9 +305,16 @@ impl Workspace {
         let _ = ctx.spawn(
             buffer,
 
-            move |me, buffer: anyhow::Result<Buffer>, ctx| match buffer {
-                Ok(buffer) => {
-                    let handle = Box::new(ctx.add_model(|_| buffer)) as Box<dyn ItemHandle>;
+            move |me, buffer: anyhow::Result<(Buffer, Option<String>)>, ctx| match buffer {
+                Ok((mut buffer, head_text)) => {
+                    let handle = ctx.add_model(|ctx| {
+                        buffer.set_head_text(head_text, ctx);
+                        buffer.observe_head(ctx);
+
+                        buffer
+                    });
+
+                    let handle = Box::new(handle) as Box<dyn ItemHandle>;
                     
                     me.items.insert(entry, OpenedItem::Loaded(handle.clone()));
                     
@@ -231,7 +364,11 @@ impl WorkspaceHandle for ModelHandle<Workspace> {
                 .iter().flat_map(|tree| {
                     let tree_id = tree.id();
 
-                    tree.as_ref(app).files().map(move |file| (tree_id, file.entry_id))
+                    tree.as_ref(app)
+                        .snapshot()
+                        .files(true, 0)
+                        .map(|file| (tree_id, file.id()))
+                        .collect::<Vec<_>>()
                 }).collect::<Vec<_>>()
         })
     }
@@ -260,7 +397,7 @@ mod tests {
             // obtém a primeira entrada do arquivo.
             let entry = workspace.read(&app, |w, app| {
                 let tree = w.worktrees.iter().next().unwrap();
-                let entry_id = tree.as_ref(app).files().next().unwrap().entry_id;
+                let entry_id = tree.as_ref(app).snapshot().files(true, 0).next().unwrap().id();
                 
                 (tree.id(), entry_id)
             });
@@ -288,4 +425,54 @@ mod tests {
             Ok(())
         })
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_frecency_survives_without_session() {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    "a.txt": "a",
+                    "b.txt": "b"
+                }
+            }));
+
+            let root = dir.path().join("root");
+            let store = dir.path().join("frecency.json");
+
+            let workspace = app.add_model(|ctx| {
+                Workspace::with_frecency_store(vec![root.clone()], Some(store.clone()), ctx)
+            });
+
+            app.finish_pending_tasks().await;
+
+            workspace.update(&mut app, |workspace, ctx| {
+                let entry = workspace.entry_for_path(&root.join("a.txt"), ctx.app()).unwrap();
+
+                workspace.record_access(entry, ctx.app());
+                workspace.record_access(entry, ctx.app());
+            });
+
+            // um novo workspace com a mesma raiz, aberto sem restaurar uma sessão, recupera os pesos
+            let workspace = app.add_model(|ctx| {
+                let mut workspace = Workspace::with_frecency_store(Vec::new(), Some(store.clone()), ctx);
+
+                workspace.open_paths(&[root.clone()], ctx);
+
+                workspace
+            });
+
+            app.finish_pending_tasks().await;
+
+            workspace.read(&app, |workspace, app| {
+                let a = workspace.entry_for_path(&root.join("a.txt"), app).unwrap();
+                let b = workspace.entry_for_path(&root.join("b.txt"), app).unwrap();
+
+                let weights = workspace.match_weights(None, app);
+
+                assert!(weights[&a] > 1.0);
+                assert!(!weights.contains_key(&b));
+                assert_eq!(workspace.recent_entries(None, app), vec![a]);
+            });
+        });
+    }
+}
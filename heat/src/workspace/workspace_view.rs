@@ -1,33 +1,76 @@
-use super::{pane, Pane, PaneGroup, SplitDirection, Workspace};
-use crate::{settings::Settings, watch};
+use super::{
+    pane::{self, NavigationPosition},
+    session::{ItemState, PaneState, WindowState},
+    Pane, PaneGroup, SplitBounds, SplitDirection, Workspace
+};
+use crate::{settings::Settings, watch, worktree::GitStatus};
 
 use gpui::{
-    color::rgbu, elements::*, AnyViewHandle, AppContext, Entity, ModelHandle, MutableAppContext,
-    View, ViewContext, ViewHandle
+    elements::*, AnyViewHandle, AppContext, Entity, ModelHandle, MutableAppContext, View,
+    ViewContext, ViewHandle
 };
 
 use log::{error, info};
 use std::{collections::HashSet, path::PathBuf};
 
+const SIDEBAR_WIDTH: f32 = 240.0;
+
+// fração do eixo ganha ou perdida pelo painel ativo a cada redimensionamento pelo teclado
+const RESIZE_STEP: f32 = 0.05;
+
 pub trait ItemView: View {
     fn is_activate_event(event: &Self::Event) -> bool;
+
+    /// eventos que tornam permanente um item aberto como prévia
+    fn is_edit_event(_: &Self::Event) -> bool {
+        false
+    }
+
     fn title(&self, app: &AppContext) -> String;
     fn entry_id(&self, app: &AppContext) -> Option<(usize, usize)>;
 
+    fn git_status(&self, _: &AppContext) -> Option<GitStatus> {
+        None
+    }
+
     fn clone_on_split(&self, _: &mut ViewContext<Self>) -> Option<Self>
     where
         Self: Sized
     {
         None
     }
+
+    /// estado salvo na sessão, ou `None` se o item não pode ser restaurado
+    fn state(&self, _: &AppContext) -> Option<ItemState> {
+        None
+    }
+
+    fn restore_state(&mut self, _: &ItemState, _: &mut ViewContext<Self>) {}
+
+    /// posição registrada no histórico de navegação quando o item deixa de ser o ativo
+    fn navigation_position(&self, _: &AppContext) -> Option<NavigationPosition> {
+        None
+    }
+
+    /// posição anterior a um salto dentro do item, registrada no histórico de navegação
+    fn navigation_event(_: &Self::Event) -> Option<&NavigationPosition> {
+        None
+    }
+
+    fn navigate(&mut self, _: &NavigationPosition, _: &mut ViewContext<Self>) {}
 }
 
 pub trait ItemViewHandle: Send + Sync {
     fn title(&self, app: &AppContext) -> String;
     fn entry_id(&self, app: &AppContext) -> Option<(usize, usize)>;
+    fn git_status(&self, app: &AppContext) -> Option<GitStatus>;
     fn boxed_clone(&self) -> Box<dyn ItemViewHandle>;
     fn clone_on_split(&self, app: &mut MutableAppContext) -> Option<Box<dyn ItemViewHandle>>;
     fn set_parent_pane(&self, pane: &ViewHandle<Pane>, app: &mut MutableAppContext);
+    fn state(&self, app: &AppContext) -> Option<ItemState>;
+    fn restore_state(&self, state: &ItemState, app: &mut MutableAppContext);
+    fn navigation_position(&self, app: &AppContext) -> Option<NavigationPosition>;
+    fn navigate(&self, position: &NavigationPosition, app: &mut MutableAppContext);
     
     fn id(&self) -> usize;
     fn to_any(&self) -> AnyViewHandle;
@@ -42,6 +85,10 @@ impl<T: ItemView> ItemViewHandle for ViewHandle<T> {
         self.as_ref(app).entry_id(app)
     }
 
+    fn git_status(&self, app: &AppContext) -> Option<GitStatus> {
+        self.as_ref(app).git_status(app)
+    }
+
     fn boxed_clone(&self) -> Box<dyn ItemViewHandle> {
         Box::new(self.clone())
     }
@@ -55,6 +102,11 @@ impl<T: ItemView> ItemViewHandle for ViewHandle<T> {
     fn set_parent_pane(&self, pane: &ViewHandle<Pane>, app: &mut MutableAppContext) {
         pane.update(app, |_, ctx| {
             ctx.subscribe_to_view(self, |pane, item, event, ctx| {
+                // o item pode ter sido movido para outro painel
+                if pane.item_index(&item).is_none() {
+                    return;
+                }
+
                 if T::is_activate_event(event) {
                     if let Some(ix) = pane.item_index(&item) {
                         pane.activate_item(ix, ctx);
@@ -62,10 +114,34 @@ impl<T: ItemView> ItemViewHandle for ViewHandle<T> {
                         pane.activate(ctx);
                     }
                 }
+
+                if T::is_edit_event(event) {
+                    pane.keep_item(item.id(), ctx);
+                }
+
+                if let Some(position) = T::navigation_event(event) {
+                    pane.push_navigation(&item, Some(position.clone()), ctx.app());
+                }
             })
         })
     }
 
+    fn state(&self, app: &AppContext) -> Option<ItemState> {
+        self.as_ref(app).state(app)
+    }
+
+    fn restore_state(&self, state: &ItemState, app: &mut MutableAppContext) {
+        self.update(app, |item, ctx| item.restore_state(state, ctx));
+    }
+
+    fn navigation_position(&self, app: &AppContext) -> Option<NavigationPosition> {
+        self.as_ref(app).navigation_position(app)
+    }
+
+    fn navigate(&self, position: &NavigationPosition, app: &mut MutableAppContext) {
+        self.update(app, |item, ctx| item.navigate(position, ctx));
+    }
+
     fn id(&self) -> usize {
         self.id()
     }
@@ -92,10 +168,31 @@ pub struct WorkspaceView {
     pub settings: watch::Receiver<Settings>,
 
     modal: Option<AnyViewHandle>,
+    sidebar: Option<AnyViewHandle>,
+    sidebar_visible: bool,
     center: PaneGroup,
     panes: Vec<ViewHandle<Pane>>,
     active_pane: ViewHandle<Pane>,
-    loading_entries: HashSet<(usize, usize)>
+    loading_entries: HashSet<(usize, usize)>,
+    pending_restore: Option<WindowState>,
+
+    // painéis restaurados cujos itens ainda estão sendo abertos
+    restoring_panes: usize,
+
+    // painel e item da aba sendo arrastada
+    dragged_tab: Option<(usize, usize)>,
+
+    // caminho do eixo e índice da divisória sendo arrastada
+    resizing: Option<(Vec<usize>, usize)>,
+
+    // tamanhos dos painéis no último layout, para achar o vizinho que está na tela
+    split_bounds: SplitBounds,
+
+    // o painel ativo ocupa a janela inteira até que o zoom seja desfeito
+    zoomed: bool,
+
+    // ids das views de item, da ativada mais recentemente para a mais antiga
+    item_history: Vec<usize>
 }
 
 impl WorkspaceView {
@@ -106,7 +203,9 @@ impl WorkspaceView {
     ) -> Self {
         ctx.observe(&workspace, Self::workspace_updated);
 
-        let pane = ctx.add_view(|_| Pane::new(settings.clone()));
+        settings.notify_view_on_change(ctx);
+
+        let pane = ctx.add_view(|ctx| Pane::new(settings.clone(), ctx));
         let pane_id = pane.id();
 
         ctx.subscribe_to_view(&pane, move |me, _, event, ctx| {
@@ -118,10 +217,19 @@ impl WorkspaceView {
         WorkspaceView {
             workspace,
             modal: None,
+            sidebar: None,
+            sidebar_visible: false,
             center: PaneGroup::new(pane.id()),
             panes: vec![pane.clone()],
             active_pane: pane.clone(),
             loading_entries: HashSet::new(),
+            pending_restore: None,
+            restoring_panes: 0,
+            dragged_tab: None,
+            resizing: None,
+            split_bounds: SplitBounds::default(),
+            zoomed: false,
+            item_history: Vec::new(),
             settings
         }
     }
@@ -169,15 +277,70 @@ impl WorkspaceView {
         }
     }
 
+    /// mostra ou esconde a barra lateral do tipo `V`, criando-a na primeira vez
+    ///
+    /// a view da barra lateral sobrevive enquanto escondida, então o seu estado é mantido
+    pub fn toggle_sidebar<V, F>(&mut self, ctx: &mut ViewContext<Self>, add_view: F)
+    where
+        V: 'static + View,
+
+        F: FnOnce(&mut ViewContext<Self>, &mut Self) -> ViewHandle<V>
+    {
+        if self.sidebar.as_ref().map_or(false, |sidebar| sidebar.is::<V>()) {
+            self.sidebar_visible = !self.sidebar_visible;
+        } else {
+            let sidebar = add_view(ctx, self);
+
+            self.sidebar = Some(sidebar.into());
+            self.sidebar_visible = true;
+        }
+
+        match self.sidebar() {
+            Some(sidebar) => ctx.focus(sidebar.clone()),
+
+            None => ctx.focus(&self.active_pane)
+        }
+
+        ctx.notify();
+    }
+
+    /// barra lateral, se estiver visível
+    pub fn sidebar(&self) -> Option<&AnyViewHandle> {
+        self.sidebar.as_ref().filter(|_| self.sidebar_visible)
+    }
+
     pub fn open_entry(&mut self, entry: (usize, usize), ctx: &mut ViewContext<Self>) {
+        self.open_entry_in_active_pane(entry, false, ctx);
+    }
+
+    /// abre a entrada como prévia, que ocupa o lugar da prévia anterior do painel ativo até ser
+    /// editada ou aberta normalmente
+    pub fn open_entry_as_preview(&mut self, entry: (usize, usize), ctx: &mut ViewContext<Self>) {
+        self.open_entry_in_active_pane(entry, true, ctx);
+    }
+
+    fn open_entry_in_active_pane(
+        &mut self,
+
+        entry: (usize, usize),
+        preview: bool,
+        ctx: &mut ViewContext<Self>
+    ) {
         if self.loading_entries.contains(&entry) {
             return;
         }
 
-        if self
-            .active_pane()
-            .update(ctx, |pane, ctx| pane.activate_entry(entry, ctx))
-        {
+        if self.active_pane().update(ctx, |pane, ctx| {
+            let activated = pane.activate_entry(entry, ctx);
+
+            if activated && !preview {
+                if let Some(item) = pane.active_item() {
+                    pane.keep_item(item.id(), ctx);
+                }
+            }
+
+            activated
+        }) {
             return;
         }
 
@@ -198,8 +361,13 @@ impl WorkspaceView {
                     match item {
                         Ok(item) => {
                             let item_view = item.add_view(ctx.window_id(), settings, ctx.app_mut());
-                            
-                            me.add_item(item_view, ctx);
+                            let active_pane = me.active_pane.clone();
+
+                            if preview {
+                                me.add_preview_item_to_pane(&active_pane, item_view, ctx);
+                            } else {
+                                me.add_item_to_pane(&active_pane, item_view, ctx);
+                            }
                         }
 
                         Err(error) => {
@@ -211,12 +379,49 @@ impl WorkspaceView {
         }
     }
 
+    /// reabre no painel uma entrada do seu histórico de navegação, restaurando a posição salva
+    fn reopen_entry(
+        &mut self,
+
+        pane: ViewHandle<Pane>,
+        entry: (usize, usize),
+        position: Option<NavigationPosition>,
+        ctx: &mut ViewContext<Self>
+    ) {
+        match self
+            .workspace
+            .update(ctx, |workspace, ctx| workspace.open_entry(entry, ctx))
+        {
+            Err(error) => error!("{}", error),
+
+            Ok(item) => {
+                let settings = self.settings.clone();
+
+                ctx.spawn(item, move |_, item, ctx| match item {
+                    Ok(item) => {
+                        let item_view = item.add_view(ctx.window_id(), settings, ctx.app_mut());
+
+                        item_view.set_parent_pane(&pane, ctx.app_mut());
+
+                        pane.update(ctx, |pane, ctx| {
+                            pane.add_navigated_item(item_view, position.as_ref(), ctx)
+                        });
+                    }
+
+                    Err(error) => error!("{}", error)
+                }).detach();
+            }
+        }
+    }
+
     pub fn open_example_entry(&mut self, ctx: &mut ViewContext<Self>) {
         if let Some(tree) = self.workspace.as_ref(ctx).worktrees().iter().next() {
-            if let Some(file) = tree.as_ref(ctx).files().next() {
-                info!("open_entry ({}, {})", tree.id(), file.entry_id);
+            let entry_id = tree.as_ref(ctx).snapshot().files(true, 0).next().map(|file| file.id());
 
-                self.open_entry((tree.id(), file.entry_id), ctx);
+            if let Some(entry_id) = entry_id {
+                info!("open_entry ({}, {})", tree.id(), entry_id);
+
+                self.open_entry((tree.id(), entry_id), ctx);
             } else {
                 error!("nenhum arquivo de exemplo encontrado para worktree {}", tree.id());
             }
@@ -225,12 +430,177 @@ impl WorkspaceView {
         }
     }
 
+    /// estado salvo na sessão
+    pub fn state(&self, app: &AppContext) -> WindowState {
+        let mut roots = self
+            .workspace
+            .as_ref(app)
+            .worktrees()
+            .iter()
+            .map(|worktree| worktree.as_ref(app).path())
+            .collect::<Vec<_>>();
+
+        roots.sort();
+
+        let layout = self.center.layout();
+
+        let active_pane = layout
+            .panes()
+            .iter()
+            .position(|pane_id| **pane_id == self.active_pane.id())
+            .unwrap_or(0);
+
+        let center = layout.map(&mut |pane_id| match self.pane(*pane_id) {
+            Some(pane) => pane.as_ref(app).state(app),
+
+            None => PaneState {
+                items: Vec::new(),
+                active_item: 0
+            }
+        });
+
+        WindowState {
+            roots,
+            center,
+            active_pane
+        }
+    }
+
+    /// restaura as divisões e os itens de uma sessão salva assim que as árvores de trabalho
+    /// terminarem de escanear, já que só então os caminhos podem ser resolvidos em entradas
+    pub fn restore(&mut self, state: WindowState, ctx: &mut ViewContext<Self>) {
+        self.pending_restore = Some(state);
+
+        self.apply_pending_restore(ctx);
+    }
+
+    /// se uma sessão ainda está sendo restaurada, quando o estado da janela está incompleto
+    pub fn is_restoring(&self) -> bool {
+        self.pending_restore.is_some() || self.restoring_panes > 0
+    }
+
+    fn apply_pending_restore(&mut self, ctx: &mut ViewContext<Self>) {
+        if self.pending_restore.is_none() || self.workspace.as_ref(ctx).is_scanning(ctx) {
+            return;
+        }
+
+        let state = self.pending_restore.take().unwrap();
+
+        // a pane inicial é reaproveitada para a primeira folha
+        let mut panes = Vec::new();
+
+        let layout = state.center.map(&mut |pane_state| {
+            let pane = if panes.is_empty() {
+                self.active_pane.clone()
+            } else {
+                self.add_pane(ctx)
+            };
+
+            panes.push((pane.clone(), pane_state.clone()));
+
+            pane.id()
+        });
+
+        self.center = PaneGroup::from_layout(&layout);
+
+        for (pane, pane_state) in panes {
+            self.restore_pane(pane, pane_state, ctx);
+        }
+
+        if let Some(pane) = layout
+            .panes()
+            .get(state.active_pane)
+            .and_then(|pane_id| self.pane(**pane_id))
+        {
+            self.activate_pane(pane, ctx);
+        }
+
+        ctx.notify();
+    }
+
+    fn restore_pane(&mut self, pane: ViewHandle<Pane>, state: PaneState, ctx: &mut ViewContext<Self>) {
+        let mut items = Vec::new();
+
+        for (ix, item_state) in state.items.into_iter().enumerate() {
+            let entry = match self.workspace.as_ref(ctx).entry_for_path(&item_state.path, ctx) {
+                Some(entry) => entry,
+
+                None => continue
+            };
+
+            match self
+                .workspace
+                .update(ctx, |workspace, ctx| workspace.open_entry(entry, ctx))
+            {
+                Ok(item) => items.push((ix, item, item_state)),
+
+                Err(error) => error!("{}", error)
+            }
+        }
+
+        let settings = self.settings.clone();
+        let active_item = state.active_item;
+
+        self.restoring_panes += 1;
+
+        // os itens são aguardados em ordem para que as abas mantenham a ordem salva
+        ctx.spawn(
+            async move {
+                let mut results = Vec::new();
+
+                for (ix, item, item_state) in items {
+                    results.push((ix, item.await, item_state));
+                }
+
+                results
+            },
+
+            move |me, results, ctx| {
+                me.restoring_panes -= 1;
+
+                let mut active_ix = None;
+
+                for (ix, item, item_state) in results {
+                    match item {
+                        Ok(item) => {
+                            let item_view = item.add_view(ctx.window_id(), settings.clone(), ctx.app_mut());
+
+                            item_view.restore_state(&item_state, ctx.app_mut());
+
+                            me.add_item_to_pane(&pane, item_view, ctx);
+
+                            if ix == active_item {
+                                active_ix = Some(pane.as_ref(ctx).items().len() - 1);
+                            }
+                        }
+
+                        Err(error) => error!("{}", error)
+                    }
+                }
+
+                if pane.as_ref(ctx).items().is_empty() {
+                    // panes vazias só sobrevivem quando são a única do espaço de trabalho
+                    me.remove_pane(pane, ctx);
+                } else if let Some(ix) = active_ix {
+                    pane.update(ctx, |pane, ctx| pane.activate_item(ix, ctx));
+                }
+            }
+        ).detach();
+    }
+
     fn workspace_updated(&mut self, _: ModelHandle<Workspace>, ctx: &mut ViewContext<Self>) {
+        self.apply_pending_restore(ctx);
+
+        // os títulos das abas mudam quando um arquivo aberto é renomeado ou movido
+        for pane in &self.panes {
+            pane.update(ctx, |_, ctx| ctx.notify());
+        }
+
         ctx.notify();
     }
 
     fn add_pane(&mut self, ctx: &mut ViewContext<Self>) -> ViewHandle<Pane> {
-        let pane = ctx.add_view(|_| Pane::new(self.settings.clone()));
+        let pane = ctx.add_view(|ctx| Pane::new(self.settings.clone(), ctx));
         let pane_id = pane.id();
 
         ctx.subscribe_to_view(&pane, move |me, _, event, ctx| {
@@ -244,6 +614,11 @@ impl WorkspaceView {
     }
 
     fn activate_pane(&mut self, pane: ViewHandle<Pane>, ctx: &mut ViewContext<Self>) {
+        // o zoom vale apenas para o painel que estava ativo
+        if pane != self.active_pane {
+            self.zoomed = false;
+        }
+
         self.active_pane = pane;
 
         ctx.focus(&self.active_pane);
@@ -263,13 +638,53 @@ impl WorkspaceView {
                     self.split_pane(pane, *direction, ctx);
                 }
 
+                pane::Event::ActivateNeighbor(direction) => {
+                    if let Some(neighbor) = self.neighbor(&pane, *direction) {
+                        self.activate_pane(neighbor, ctx);
+                    }
+                }
+
+                pane::Event::SwapWithNeighbor(direction) => {
+                    self.swap_with_neighbor(pane, *direction, ctx);
+                }
+
+                pane::Event::MoveActiveItemToNeighbor(direction) => {
+                    self.move_active_item_to_neighbor(pane, *direction, ctx);
+                }
+
                 pane::Event::Remove => {
                     self.remove_pane(pane, ctx);
                 }
 
+                pane::Event::ItemsRemoved(item_ids) => {
+                    self.item_history.retain(|id| !item_ids.contains(id));
+                }
+
                 pane::Event::Activate => {
                     self.activate_pane(pane, ctx);
                 }
+
+                pane::Event::ReopenEntry(entry, position) => {
+                    self.reopen_entry(pane, *entry, position.clone(), ctx);
+                }
+
+                // abrir um arquivo também ativa a sua aba, então os dois são registrados aqui
+                pane::Event::ActivateItem => {
+                    if let Some(item) = pane.as_ref(ctx).active_item() {
+                        self.item_history.retain(|id| *id != item.id());
+                        self.item_history.insert(0, item.id());
+                    }
+
+                    let entry = pane
+                        .as_ref(ctx)
+                        .active_item()
+                        .and_then(|item| item.entry_id(ctx.app()));
+
+                    if let Some(entry) = entry {
+                        self.workspace
+                            .update(ctx, |workspace, ctx| workspace.record_access(entry, ctx.app()));
+                    }
+                }
             }
         } else {
             error!("pane {} não encontrada", pane_id);
@@ -289,7 +704,7 @@ impl WorkspaceView {
 
         if let Some(item) = pane.as_ref(ctx).active_item() {
             if let Some(clone) = item.clone_on_split(ctx.app_mut()) {
-                self.add_item(clone, ctx);
+                self.add_item_to_pane(&new_pane, clone, ctx);
             }
         }
 
@@ -304,7 +719,107 @@ impl WorkspaceView {
         if self.center.remove(pane.id()).unwrap() {
             self.panes.retain(|p| p != &pane);
 
-            self.activate_pane(self.panes.last().unwrap().clone(), ctx);
+            // os itens que ainda restavam no painel deixam de existir com ele
+            let app = ctx.app();
+
+            let item_ids = self
+                .panes
+                .iter()
+                .flat_map(|pane| pane.as_ref(app).items().iter().map(|item| item.id()))
+                .collect::<HashSet<_>>();
+
+            self.item_history.retain(|id| item_ids.contains(id));
+
+            // um painel esvaziado por um item movido para outro não era o ativo
+            if pane == self.active_pane {
+                self.activate_pane(self.panes.last().unwrap().clone(), ctx);
+            } else {
+                ctx.notify();
+            }
+        }
+    }
+
+    fn neighbor(
+        &self,
+
+        pane: &ViewHandle<Pane>,
+        direction: SplitDirection
+    ) -> Option<ViewHandle<Pane>> {
+        self.center
+            .find_pane_in_direction(pane.id(), direction, &self.split_bounds)
+            .and_then(|pane_id| self.pane(pane_id))
+    }
+
+    /// troca o painel de lugar com o vizinho, mantendo o foco nele
+    fn swap_with_neighbor(
+        &mut self,
+
+        pane: ViewHandle<Pane>,
+        direction: SplitDirection,
+        ctx: &mut ViewContext<Self>
+    ) {
+        if let Some(neighbor) = self.neighbor(&pane, direction) {
+            self.center.swap(pane.id(), neighbor.id()).unwrap();
+
+            ctx.notify();
+        }
+    }
+
+    /// move o item ativo do painel para o vizinho, dividindo o painel na direção quando não há
+    /// vizinho e o item não é o único, caso em que movê-lo não mudaria nada
+    fn move_active_item_to_neighbor(
+        &mut self,
+
+        pane: ViewHandle<Pane>,
+        direction: SplitDirection,
+        ctx: &mut ViewContext<Self>
+    ) {
+        let (item_id, entry_id) = match pane.as_ref(ctx).active_item() {
+            Some(item) => (item.id(), item.entry_id(ctx.app())),
+
+            None => return
+        };
+
+        let target = match self.neighbor(&pane, direction) {
+            Some(neighbor) => neighbor,
+
+            None if pane.as_ref(ctx).items().len() > 1 => {
+                let new_pane = self.add_pane(ctx);
+
+                self.center.split(pane.id(), new_pane.id(), direction).unwrap();
+
+                new_pane
+            }
+
+            None => return
+        };
+
+        // o painel de destino já mostra a mesma entrada, então o item de lá é ativado e o movido
+        // é descartado
+        let existing_idx = entry_id.and_then(|entry_id| {
+            target
+                .as_ref(ctx)
+                .items()
+                .iter()
+                .position(|item| item.entry_id(ctx.app()) == Some(entry_id))
+        });
+
+        if let Some(item) = pane.update(ctx, |pane, ctx| pane.remove_item(item_id, ctx)) {
+            match existing_idx {
+                Some(item_idx) => target.update(ctx, |pane, ctx| pane.activate_item(item_idx, ctx)),
+
+                None => {
+                    item.set_parent_pane(&target, ctx.app_mut());
+
+                    target.update(ctx, |pane, ctx| {
+                        let item_idx = pane.add_item(item, ctx);
+
+                        pane.activate_item(item_idx, ctx);
+                    });
+                }
+            }
+
+            self.activate_pane(target, ctx);
         }
     }
 
@@ -316,50 +831,282 @@ impl WorkspaceView {
         &self.active_pane
     }
 
-    fn add_item(&self, item: Box<dyn ItemViewHandle>, ctx: &mut ViewContext<Self>) {
-        let active_pane = self.active_pane();
-
-        item.set_parent_pane(&active_pane, ctx.app_mut());
+    /// divide o painel ativo como `Pane::split`, mas de imediato, para que itens possam ser
+    /// abertos no novo painel logo em seguida
+    pub fn split_active_pane(
+        &mut self,
 
-        active_pane.update(ctx, |pane, ctx| {
-            let item_idx = pane.add_item(item, ctx);
+        direction: SplitDirection,
+        ctx: &mut ViewContext<Self>
+    ) -> ViewHandle<Pane> {
+        self.split_pane(self.active_pane.clone(), direction, ctx)
+    }
 
-            pane.activate_item(item_idx, ctx);
+    /// itens de todos os painéis, do ativado mais recentemente para o mais antigo, com os que
+    /// nunca foram ativados no fim
+    pub fn items_by_recency(
+        &self,
+
+        app: &AppContext
+    ) -> Vec<(ViewHandle<Pane>, Box<dyn ItemViewHandle>)> {
+        let mut items = self
+            .panes
+            .iter()
+            .flat_map(|pane| {
+                pane.as_ref(app)
+                    .items()
+                    .iter()
+                    .map(move |item| (pane.clone(), item.boxed_clone()))
+            }).collect::<Vec<_>>();
+
+        items.sort_by_key(|(_, item)| {
+            self.item_history
+                .iter()
+                .position(|id| *id == item.id())
+                .unwrap_or(usize::MAX)
         });
+
+        items
     }
-}
 
-impl Entity for WorkspaceView {
-    type Event = ();
-}
+    /// ativa um item e o painel que o contém
+    pub fn activate_item(
+        &mut self,
 
-impl View for WorkspaceView {
-    fn ui_name() -> &'static str {
-        "workspace"
-    }
+        pane: &ViewHandle<Pane>,
+        item_id: usize,
+        ctx: &mut ViewContext<Self>
+    ) {
+        let ix = pane.as_ref(ctx).items().iter().position(|item| item.id() == item_id);
 
-    fn render(&self, _: &AppContext) -> Box<dyn Element> {
-        Container::new(
-            // self.center.render(bump)
+        if let Some(ix) = ix {
+            self.activate_pane(pane.clone(), ctx);
 
-            Stack::new()
-                .with_child(self.center.render())
-                .with_children(self.modal.as_ref().map(|m| ChildView::new(m.id()).boxed()))
-                .boxed(),
-        ).with_background_color(rgbu(0xea, 0xea, 0xeb)).boxed()
+            pane.update(ctx, |pane, ctx| pane.activate_item(ix, ctx));
+        }
     }
 
-    fn on_focus(&mut self, ctx: &mut ViewContext<Self>) {
-        ctx.focus(&self.active_pane);
+    /// entrada do item ativo no painel ativo
+    pub fn active_entry(&self, app: &AppContext) -> Option<(usize, usize)> {
+        self.active_pane
+            .as_ref(app)
+            .active_item()
+            .and_then(|item| item.entry_id(app))
     }
-}
+
+    /// adiciona um item que não vem de uma entrada da árvore de trabalho ao painel ativo
+    pub fn add_item(&mut self, item: Box<dyn ItemViewHandle>, ctx: &mut ViewContext<Self>) {
+        let active_pane = self.active_pane.clone();
+
+        self.add_item_to_pane(&active_pane, item, ctx);
+    }
+
+    fn add_item_to_pane(
+        &self,
+
+        pane: &ViewHandle<Pane>,
+        item: Box<dyn ItemViewHandle>,
+        ctx: &mut ViewContext<Self>
+    ) {
+        item.set_parent_pane(pane, ctx.app_mut());
+
+        pane.update(ctx, |pane, ctx| {
+            let item_idx = pane.add_item(item, ctx);
+
+            pane.activate_item(item_idx, ctx);
+        });
+    }
+
+    fn add_preview_item_to_pane(
+        &self,
+
+        pane: &ViewHandle<Pane>,
+        item: Box<dyn ItemViewHandle>,
+        ctx: &mut ViewContext<Self>
+    ) {
+        item.set_parent_pane(pane, ctx.app_mut());
+
+        pane.update(ctx, |pane, ctx| {
+            let item_idx = pane.add_preview_item(item, ctx);
+
+            pane.activate_item(item_idx, ctx);
+        });
+    }
+
+    pub fn drag_tab(&mut self, tab: &(usize, usize), _: &mut ViewContext<Self>) {
+        self.dragged_tab = Some(*tab);
+    }
+
+    /// solta a aba arrastada na posição `index` do painel, movendo-a entre painéis se preciso
+    pub fn drop_tab(&mut self, &(pane_id, index): &(usize, usize), ctx: &mut ViewContext<Self>) {
+        let (source_id, item_id) = match self.dragged_tab.take() {
+            Some(tab) => tab,
+
+            None => return
+        };
+
+        let (source, target) = match (self.pane(source_id), self.pane(pane_id)) {
+            (Some(source), Some(target)) => (source, target),
+
+            _ => return
+        };
+
+        if source == target {
+            target.update(ctx, |pane, ctx| pane.move_item(item_id, index, ctx));
+
+            return;
+        }
+
+        // uma aba fixada continua fixada no outro painel
+        let pinned = source
+            .as_ref(ctx)
+            .items()
+            .iter()
+            .position(|item| item.id() == item_id)
+            .map_or(false, |ix| source.as_ref(ctx).is_pinned(ix));
+
+        if let Some(item) = source.update(ctx, |pane, ctx| pane.remove_item(item_id, ctx)) {
+            item.set_parent_pane(&target, ctx.app_mut());
+
+            target.update(ctx, |pane, ctx| {
+                let item_idx = if pinned {
+                    pane.insert_pinned_item(index, item, ctx)
+                } else {
+                    pane.insert_item(index, item, ctx)
+                };
+
+                pane.activate_item(item_idx, ctx);
+            });
+
+            self.activate_pane(target, ctx);
+        }
+    }
+
+    /// o botão foi solto fora de qualquer barra de abas
+    pub fn cancel_tab_drag(&mut self, _: &(), _: &mut ViewContext<Self>) {
+        self.dragged_tab = None;
+    }
+
+    pub fn start_resize(&mut self, divider: &(Vec<usize>, usize), ctx: &mut ViewContext<Self>) {
+        self.resizing = Some(divider.clone());
+
+        ctx.notify();
+    }
+
+    /// aplica as proporções do eixo cuja divisória está sendo arrastada
+    pub fn resize(&mut self, (path, ratios): &(Vec<usize>, Vec<f32>), ctx: &mut ViewContext<Self>) {
+        if self.resizing.as_ref().map_or(false, |(resizing_path, _)| resizing_path == path) {
+            if let Err(error) = self.center.resize(path, ratios) {
+                error!("{}", error);
+            }
+
+            ctx.notify();
+        }
+    }
+
+    pub fn end_resize(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.resizing.take().is_some() {
+            ctx.notify();
+        }
+    }
+
+    pub fn grow_active_pane(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.center.resize_pane(self.active_pane.id(), RESIZE_STEP) {
+            ctx.notify();
+        }
+    }
+
+    pub fn shrink_active_pane(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        if self.center.resize_pane(self.active_pane.id(), -RESIZE_STEP) {
+            ctx.notify();
+        }
+    }
+
+    pub fn equalize_panes(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.center.equalize();
+
+        ctx.notify();
+    }
+
+    pub fn toggle_zoom(&mut self, _: &(), ctx: &mut ViewContext<Self>) {
+        self.zoomed = !self.zoomed;
+
+        ctx.notify();
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed
+    }
+}
+
+impl Entity for WorkspaceView {
+    type Event = ();
+}
+
+impl View for WorkspaceView {
+    fn ui_name() -> &'static str {
+        "workspace"
+    }
+
+    fn render(&self, _: &AppContext) -> Box<dyn Element> {
+        let settings = smol::block_on(self.settings.read());
+
+        let mut content = Flex::row();
+
+        if let Some(sidebar) = self.sidebar().filter(|_| !self.zoomed) {
+            content = content.with_child(
+                ConstrainedBox::new(ChildView::new(sidebar.id()).boxed())
+                    .with_width(SIDEBAR_WIDTH)
+                    .boxed()
+            );
+        }
+
+        let center = if self.zoomed {
+            ChildView::new(self.active_pane.id()).boxed()
+        } else {
+            self.center.render(&settings.theme, self.resizing.as_ref(), &self.split_bounds)
+        };
+
+        content = content.with_child(Expanded::new(1.0, center).boxed());
+
+        let container = Container::new(
+            // self.center.render(bump)
+
+            Stack::new()
+                .with_child(content.boxed())
+                .with_children(self.modal.as_ref().map(|m| ChildView::new(m.id()).boxed()))
+                .boxed(),
+        ).with_background_color(settings.theme.ui.workspace_background);
+
+        // as barras de abas tratam o botão solto antes, então um arrasto que chega aqui terminou
+        // fora delas
+        EventHandler::new(container.boxed())
+            .on_mouse_up(|ctx, _| {
+                ctx.dispatch_action("workspace:cancel_tab_drag", ());
+                ctx.dispatch_action("workspace:end_resize", ());
+
+                false
+            }).boxed()
+    }
+
+    fn on_focus(&mut self, ctx: &mut ViewContext<Self>) {
+        ctx.focus(&self.active_pane);
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use super::{pane, Workspace, WorkspaceView};
-    use crate::{settings, test::temp_tree, workspace::WorkspaceHandle as _};
+    use super::{pane, Pane, Workspace, WorkspaceView};
+
+    use crate::{
+        editor::{self, BufferView, DisplayPoint, SelectAction},
+        settings,
+        test::temp_tree,
+        workspace::{Layout, WorkspaceHandle as _}
+    };
+
     use anyhow::Result;
-    use gpui::{App, FontCache};
+    use gpui::{App, FontCache, ViewHandle};
     use serde_json::json;
 
     #[test]
@@ -482,4 +1229,454 @@ mod tests {
             Ok(())
         })
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_state_and_restore() -> Result<()> {
+        App::test((), |mut app| async move {
+            pane::init(&mut app);
+
+            let dir = temp_tree(json!({
+                "a": {
+                    "aa": "conteúdos aa",
+                    "ab": "conteúdos ab"
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            app.finish_pending_tasks().await; // abre e popula a árvore de trabalho
+            let entries = workspace.file_entries(&app);
+
+            let (window_id, workspace_view) =
+                app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings.clone(), ctx));
+
+            workspace_view.update(&mut app, |w, ctx| {
+                w.open_entry(entries[0], ctx);
+                w.open_entry(entries[1], ctx);
+            });
+
+            app.finish_pending_tasks().await;
+
+            let pane_1 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:split_right", ());
+            pane_1.update(&mut app, |pane, ctx| pane.activate_item(0, ctx));
+
+            let state = workspace_view.read(&app, |w, app| w.state(app));
+
+            assert_eq!(state.roots, vec![dir.path().to_path_buf()]);
+            assert_eq!(state.active_pane, 1);
+
+            let panes = state.center.panes();
+
+            assert_eq!(panes.len(), 2);
+            assert_eq!(panes[0].items.len(), 2);
+            assert_eq!(panes[0].active_item, 0);
+            assert_eq!(panes[1].items.len(), 1);
+
+            // uma nova janela restaura as mesmas divisões e abas
+            let workspace = app.add_model(|ctx| Workspace::new(state.roots.clone(), ctx));
+
+            let (_, restored_view) = app.add_window(|ctx| {
+                let mut view = WorkspaceView::new(workspace, settings, ctx);
+
+                view.restore(state.clone(), ctx);
+
+                view
+            });
+
+            restored_view.read(&app, |w, _| assert!(w.is_restoring()));
+
+            app.finish_pending_tasks().await;
+
+            restored_view.read(&app, |w, app| {
+                assert!(!w.is_restoring());
+                assert_eq!(w.panes.len(), 2);
+                assert_eq!(w.state(app), state);
+            });
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_navigation_history() -> Result<()> {
+        App::test((), |mut app| async move {
+            editor::init(&mut app);
+            pane::init(&mut app);
+
+            let dir = temp_tree(json!({
+                "a": {
+                    "aa": "conteúdos aa",
+
+                    "ab": format!(
+                        "{}<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n{}",
+                        "linha\n".repeat(20),
+                        "linha\n".repeat(10)
+                    )
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            app.finish_pending_tasks().await; // abre e popula a árvore de trabalho
+            let entries = workspace.file_entries(&app);
+
+            let (window_id, workspace_view) =
+                app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry(entries[0], ctx));
+            app.finish_pending_tasks().await;
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry(entries[1], ctx));
+            app.finish_pending_tasks().await;
+
+            let pane = workspace_view.read(&app, |w, _| w.active_pane().clone());
+
+            let active_item = |app: &App| {
+                pane.read(app, |pane, app| {
+                    let item = pane.active_item().unwrap();
+                    let view = item.to_any().downcast::<BufferView>().unwrap();
+
+                    (item.entry_id(app), view.as_ref(app).first_selection(app).start.row())
+                })
+            };
+
+            // um clique longe do cursor não entra no histórico, apenas os saltos explícitos
+            let view = pane.read(&app, |pane, _| pane.active_item().unwrap().to_any());
+
+            let click = SelectAction::Begin {
+                position: DisplayPoint::new(28, 0),
+                add: false,
+                click_count: 1
+            };
+
+            let responder_chain = vec![pane.id(), view.id()];
+
+            app.dispatch_action(window_id, responder_chain.clone(), "buffer:select", click);
+            app.dispatch_action(window_id, responder_chain.clone(), "buffer:select", SelectAction::End);
+
+            assert_eq!(active_item(&app), (Some(entries[1]), 28));
+
+            app.dispatch_action(window_id, responder_chain, "buffer:next_conflict", ());
+            assert_eq!(active_item(&app), (Some(entries[1]), 20));
+
+            app.dispatch_action(window_id, vec![pane.id()], "pane:go_back", ());
+            assert_eq!(active_item(&app), (Some(entries[1]), 28));
+
+            app.dispatch_action(window_id, vec![pane.id()], "pane:go_back", ());
+            assert_eq!(active_item(&app), (Some(entries[0]), 0));
+
+            app.dispatch_action(window_id, vec![pane.id()], "pane:go_forward", ());
+            assert_eq!(active_item(&app), (Some(entries[1]), 28));
+
+            app.dispatch_action(window_id, vec![pane.id()], "pane:go_forward", ());
+            assert_eq!(active_item(&app), (Some(entries[1]), 20));
+
+            // voltar para um item fechado o reabre na posição em que estava
+            app.dispatch_action(window_id, vec![pane.id()], "pane:close_active_item", ());
+            assert_eq!(active_item(&app), (Some(entries[0]), 0));
+
+            app.dispatch_action(window_id, vec![pane.id()], "pane:go_back", ());
+            app.finish_pending_tasks().await;
+
+            assert_eq!(active_item(&app), (Some(entries[1]), 20));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_tab_management() -> Result<()> {
+        App::test((), |mut app| async move {
+            crate::workspace::init(&mut app);
+
+            let dir = temp_tree(json!({
+                "a": {
+                    "aa": "conteúdos aa",
+                    "ab": "conteúdos ab",
+                    "ac": "conteúdos ac",
+                    "ad": "conteúdos ad",
+                    "ae": "conteúdos ae"
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            app.finish_pending_tasks().await; // abre e popula a árvore de trabalho
+            let entries = workspace.file_entries(&app);
+
+            let (window_id, workspace_view) =
+                app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            for entry in &entries[0..3] {
+                workspace_view.update(&mut app, |w, ctx| w.open_entry(*entry, ctx));
+                app.finish_pending_tasks().await;
+            }
+
+            let pane_1 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+            let chain_1 = vec![workspace_view.id(), pane_1.id()];
+
+            let titles = |pane: &ViewHandle<Pane>, app: &App| {
+                pane.read(app, |pane, app| {
+                    pane.items().iter().map(|item| item.title(app)).collect::<Vec<_>>()
+                })
+            };
+
+            let item_id = |pane: &ViewHandle<Pane>, ix: usize, app: &App| {
+                pane.read(app, |pane, _| pane.items()[ix].id())
+            };
+
+            // o item fixado vai para o início e sobrevive a "fechar os outros"
+            app.dispatch_action(window_id, chain_1.clone(), "pane:toggle_pin_active_item", ());
+            assert_eq!(titles(&pane_1, &app), ["ac", "aa", "ab"]);
+
+            app.dispatch_action(window_id, chain_1.clone(), "pane:activate_item", 1_usize);
+            app.dispatch_action(window_id, chain_1.clone(), "pane:close_other_items", ());
+            assert_eq!(titles(&pane_1, &app), ["ac", "aa"]);
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry(entries[1], ctx));
+            app.finish_pending_tasks().await;
+            assert_eq!(titles(&pane_1, &app), ["ac", "aa", "ab"]);
+
+            // arrastar uma aba a reordena, sem entrar na região dos fixados
+            let ab = item_id(&pane_1, 2, &app);
+
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drag_tab", (pane_1.id(), ab));
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drop_tab", (pane_1.id(), 1_usize));
+            assert_eq!(titles(&pane_1, &app), ["ac", "ab", "aa"]);
+
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drag_tab", (pane_1.id(), ab));
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drop_tab", (pane_1.id(), 0_usize));
+            assert_eq!(titles(&pane_1, &app), ["ac", "ab", "aa"]);
+
+            // um arrasto que termina fora das abas não move nada
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drag_tab", (pane_1.id(), ab));
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:cancel_tab_drag", ());
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drop_tab", (pane_1.id(), 2_usize));
+            assert_eq!(titles(&pane_1, &app), ["ac", "ab", "aa"]);
+
+            pane_1.read(&app, |pane, _| assert_eq!(pane.active_item().unwrap().id(), ab));
+
+            app.dispatch_action(window_id, chain_1.clone(), "pane:close_items_to_the_right", ());
+            assert_eq!(titles(&pane_1, &app), ["ac", "ab"]);
+
+            // arrastar uma aba para outro painel a move para lá
+            app.dispatch_action(window_id, chain_1.clone(), "pane:split_right", ());
+            let pane_2 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+            let ac = item_id(&pane_1, 0, &app);
+
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drag_tab", (pane_1.id(), ac));
+            app.dispatch_action(window_id, chain_1.clone(), "workspace:drop_tab", (pane_2.id(), 0_usize));
+
+            assert_eq!(titles(&pane_1, &app), ["ab"]);
+            assert_eq!(titles(&pane_2, &app), ["ac", "ab"]);
+            workspace_view.read(&app, |w, _| assert_eq!(w.active_pane(), &pane_2));
+
+            // a aba fixada continua fixada no painel de destino
+            pane_1.read(&app, |pane, _| assert!(!pane.is_pinned(0)));
+            pane_2.read(&app, |pane, _| assert!(pane.is_pinned(0) && !pane.is_pinned(1)));
+
+            // uma prévia é substituída pela seguinte até ser aberta normalmente
+            workspace_view.update(&mut app, |w, ctx| w.open_entry_as_preview(entries[3], ctx));
+            app.finish_pending_tasks().await;
+            assert_eq!(titles(&pane_2, &app), ["ac", "ad", "ab"]);
+
+            let ad = item_id(&pane_2, 1, &app);
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry_as_preview(entries[4], ctx));
+            app.finish_pending_tasks().await;
+            assert_eq!(titles(&pane_2, &app), ["ac", "ae", "ab"]);
+
+            // a prévia substituída sai do histórico de itens
+            workspace_view.read(&app, |w, _| assert!(!w.item_history.contains(&ad)));
+
+            let ae = item_id(&pane_2, 1, &app);
+            pane_2.read(&app, |pane, _| assert!(pane.is_preview(ae)));
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry(entries[4], ctx));
+            pane_2.read(&app, |pane, _| assert!(!pane.is_preview(ae)));
+
+            workspace_view.update(&mut app, |w, ctx| w.open_entry_as_preview(entries[3], ctx));
+            app.finish_pending_tasks().await;
+            assert_eq!(titles(&pane_2, &app), ["ac", "ae", "ad", "ab"]);
+
+            // fechar um item ou o painel inteiro também o tira do histórico
+            let ad = item_id(&pane_2, 2, &app);
+            let ab = item_id(&pane_1, 0, &app);
+
+            app.dispatch_action(window_id, vec![workspace_view.id(), pane_2.id()], "pane:close_active_item", ());
+            assert_eq!(titles(&pane_2, &app), ["ac", "ae", "ab"]);
+
+            app.dispatch_action(window_id, chain_1.clone(), "pane:close_active_item", ());
+            workspace_view.read(&app, |w, _| assert!(!w.panes.contains(&pane_1)));
+
+            workspace_view.read(&app, |w, _| {
+                assert!(!w.item_history.contains(&ad) && !w.item_history.contains(&ab));
+                assert!(w.item_history.len() <= 3);
+            });
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pane_resizing() -> Result<()> {
+        App::test((), |mut app| async move {
+            crate::workspace::init(&mut app);
+
+            let dir = temp_tree(json!({
+                "a": {
+                    "aa": "conteúdos aa"
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            app.finish_pending_tasks().await;
+
+            let (window_id, workspace_view) =
+                app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            let pane_1 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:split_right", ());
+
+            let pane_2 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+            let chain_2 = vec![workspace_view.id(), pane_2.id()];
+
+            let ratios = |app: &App| {
+                workspace_view.read(app, |w, app| match w.state(app).center {
+                    Layout::Axis(_, _, ratios) => {
+                        ratios.iter().map(|ratio| (ratio * 100.0).round() / 100.0).collect::<Vec<_>>()
+                    }
+
+                    Layout::Pane(_) => Vec::new()
+                })
+            };
+
+            assert_eq!(ratios(&app), vec![0.5, 0.5]);
+
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:grow_active_pane", ());
+            assert_eq!(ratios(&app), vec![0.45, 0.55]);
+
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:shrink_active_pane", ());
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:shrink_active_pane", ());
+            assert_eq!(ratios(&app), vec![0.55, 0.45]);
+
+            // proporções só são aplicadas enquanto a divisória do eixo é arrastada
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:resize", (Vec::<usize>::new(), vec![1.0_f32, 3.0]));
+            assert_eq!(ratios(&app), vec![0.55, 0.45]);
+
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:start_resize", (Vec::<usize>::new(), 0_usize));
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:resize", (Vec::<usize>::new(), vec![1.0_f32, 3.0]));
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:end_resize", ());
+            assert_eq!(ratios(&app), vec![0.25, 0.75]);
+
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:equalize_panes", ());
+            assert_eq!(ratios(&app), vec![0.5, 0.5]);
+
+            // o zoom é desfeito quando outro painel se torna o ativo
+            app.dispatch_action(window_id, chain_2.clone(), "workspace:toggle_zoom", ());
+            workspace_view.read(&app, |w, _| assert!(w.is_zoomed()));
+
+            app.dispatch_action(window_id, chain_2.clone(), "pane:split_down", ());
+            workspace_view.read(&app, |w, _| assert!(!w.is_zoomed()));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pane_navigation() -> Result<()> {
+        App::test((), |mut app| async move {
+            crate::workspace::init(&mut app);
+
+            let dir = temp_tree(json!({
+                "a": {
+                    "aa": "conteúdos aa",
+                    "ab": "conteúdos ab"
+                }
+            }));
+
+            let settings = settings::channel(&FontCache::new()).unwrap().1;
+            let workspace = app.add_model(|ctx| Workspace::new(vec![dir.path().into()], ctx));
+
+            app.finish_pending_tasks().await;
+            let entries = workspace.file_entries(&app);
+
+            let (window_id, workspace_view) =
+                app.add_window(|ctx| WorkspaceView::new(workspace.clone(), settings, ctx));
+
+            for entry in &entries[0..2] {
+                workspace_view.update(&mut app, |w, ctx| w.open_entry(*entry, ctx));
+                app.finish_pending_tasks().await;
+            }
+
+            let pane_1 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:split_right", ());
+            let pane_2 = workspace_view.read(&app, |w, _| w.active_pane().clone());
+
+            let active_pane = |app: &App| workspace_view.read(app, |w, _| w.active_pane().clone());
+
+            let pane_ids = |app: &App| {
+                workspace_view.read(app, |w, _| {
+                    w.center.layout().panes().into_iter().copied().collect::<Vec<_>>()
+                })
+            };
+
+            let titles = |pane: &ViewHandle<Pane>, app: &App| {
+                pane.read(app, |pane, app| {
+                    pane.items().iter().map(|item| item.title(app)).collect::<Vec<_>>()
+                })
+            };
+
+            app.dispatch_action(window_id, vec![pane_2.id()], "pane:activate_left", ());
+            assert_eq!(active_pane(&app), pane_1);
+
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:activate_up", ());
+            assert_eq!(active_pane(&app), pane_1);
+
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:activate_right", ());
+            assert_eq!(active_pane(&app), pane_2);
+
+            // a troca mantém o foco no painel que mudou de lugar
+            app.dispatch_action(window_id, vec![pane_2.id()], "pane:swap_left", ());
+            assert_eq!(pane_ids(&app), [pane_2.id(), pane_1.id()]);
+            assert_eq!(active_pane(&app), pane_2);
+
+            // um painel esvaziado pela mudança é removido, e o item cuja entrada já está aberta no
+            // destino dá lugar ao que já estava lá
+            let ab = pane_1.read(&app, |pane, _| pane.items()[1].id());
+
+            app.dispatch_action(window_id, vec![pane_2.id()], "pane:move_item_right", ());
+            assert_eq!(pane_ids(&app), [pane_1.id()]);
+            assert_eq!(active_pane(&app), pane_1);
+            assert_eq!(titles(&pane_1, &app), ["aa", "ab"]);
+            pane_1.read(&app, |pane, _| assert_eq!(pane.active_item().unwrap().id(), ab));
+
+            // sem vizinho na direção, um novo painel é criado para receber o item
+            app.dispatch_action(window_id, vec![pane_1.id()], "pane:move_item_down", ());
+
+            let pane_3 = active_pane(&app);
+
+            assert_eq!(pane_ids(&app), [pane_1.id(), pane_3.id()]);
+            assert_eq!(titles(&pane_1, &app), ["aa"]);
+            assert_eq!(titles(&pane_3, &app), ["ab"]);
+
+            // o único item de um painel sem vizinho fica onde está
+            app.dispatch_action(window_id, vec![pane_3.id()], "pane:move_item_down", ());
+            assert_eq!(pane_ids(&app), [pane_1.id(), pane_3.id()]);
+
+            app.dispatch_action(window_id, vec![pane_3.id()], "pane:activate_up", ());
+            assert_eq!(active_pane(&app), pane_1);
+
+            Ok(())
+        })
+    }
+}
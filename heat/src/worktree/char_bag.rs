@@ -1,5 +1,28 @@
-#[derive(Copy, Clone, Debug)]
-pub struct CharBag(u64);
+// as letras ascii guardam até duas ocorrências em dois bits cada, os dígitos ocupam um bit cada
+// e os demais caracteres, incluindo pontuação e letras não ascii, são distribuídos pelos bits
+// restantes por hash
+const LETTER_BITS: u32 = 26 * 2;
+const DIGIT_BITS: u32 = 10;
+const HASH_BITS: u32 = 128 - LETTER_BITS - DIGIT_BITS;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CharBag(u128);
+
+/// minúscula de `c` quando ela é um único caractere, para que o texto dobrado mantenha as
+/// posições do original
+pub fn fold_case(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+
+    let mut lowercase = c.to_lowercase();
+
+    match (lowercase.next(), lowercase.next()) {
+        (Some(lower), None) => lower,
+
+        _ => c
+    }
+}
 
 impl CharBag {
     pub fn is_superset(self, other: CharBag) -> bool {
@@ -7,9 +30,11 @@ impl CharBag {
     }
 
     fn insert(&mut self, c: char) {
+        let c = fold_case(c);
+
         if c >= 'a' && c <= 'z' {
             let mut count = self.0;
-            let idx = c as u8 - 'a' as u8;
+            let idx = c as u32 - 'a' as u32;
 
             count = count >> (idx * 2);
             count = ((count << 1) | 1) & 3;
@@ -17,11 +42,13 @@ impl CharBag {
 
             self.0 |= count;
         } else if c >= '0' && c <= '9' {
-            let idx = c as u8 - '0' as u8;
+            let idx = c as u32 - '0' as u32;
 
-            self.0 |= 1 << (idx + 52);
-        } else if c == '-' {
-            self.0 |= 1 << 62;
+            self.0 |= 1 << (idx + LETTER_BITS);
+        } else {
+            let idx = c as u32 % HASH_BITS;
+
+            self.0 |= 1 << (idx + LETTER_BITS + DIGIT_BITS);
         }
     }
 }
@@ -45,7 +72,7 @@ impl From<&[char]> for CharBag {
         for c in chars {
             bag.insert(*c);
         }
-        
+
         bag
     }
-}
\ No newline at end of file
+}
@@ -1,22 +1,25 @@
 use easy_parallel::Parallel;
 
-use super::char_bag::CharBag;
+use super::{
+    char_bag::{fold_case, CharBag},
+    worktree::Snapshot
+};
 
 use std::{
     cmp::{max, min, Ordering, Reverse},
-    collections::BinaryHeap
+    collections::{BinaryHeap, HashMap},
+    path::Path
 };
 
 const BASE_DISTANCE_PENALTY: f64 = 0.6;
 const ADDITIONAL_DISTANCE_PENALTY: f64 = 0.05;
 const MIN_DISTANCE_PENALTY: f64 = 0.2;
 
-pub struct PathEntry {
-    pub entry_id: usize,
-    pub path_chars: CharBag,
-    pub path: Vec<char>,
-    pub lowercase_path: Vec<char>,
-    pub is_ignored: bool
+struct PathEntry<'a> {
+    entry_id: usize,
+    path_chars: CharBag,
+    path: &'a Path,
+    is_ignored: bool
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +31,156 @@ pub struct PathMatch {
     pub skipped_prefix_len: usize
 }
 
+#[derive(Clone, Debug)]
+pub struct StringMatchCandidate {
+    pub id: usize,
+    pub string: String,
+    char_bag: CharBag
+}
+
+impl StringMatchCandidate {
+    pub fn new(id: usize, string: String) -> Self {
+        let char_bag = CharBag::from(string.as_str());
+
+        Self { id, string, char_bag }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringMatch {
+    pub candidate_id: usize,
+    pub score: f64,
+    pub positions: Vec<usize>,
+    pub string: String
+}
+
+impl PartialEq for StringMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.score.eq(&other.score)
+    }
+}
+
+impl Eq for StringMatch {}
+
+impl PartialOrd for StringMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.score.partial_cmp(&other.score)
+    }
+}
+
+impl Ord for StringMatch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// estado reaproveitado entre os candidatos de uma mesma thread
+struct Matcher<'a> {
+    query: &'a [char],
+    lowercase_query: &'a [char],
+    query_chars: CharBag,
+    smart_case: bool,
+    max_results: usize,
+    min_score: f64,
+
+    match_positions: Vec<usize>,
+    last_positions: Vec<usize>,
+
+    score_matrix: Vec<Option<f64>>,
+    best_position_matrix: Vec<usize>,
+
+    text: Vec<char>,
+    lowercase_text: Vec<char>
+}
+
+impl<'a> Matcher<'a> {
+    fn new(
+        query: &'a [char],
+        lowercase_query: &'a [char],
+        query_chars: CharBag,
+        smart_case: bool,
+        max_results: usize
+    ) -> Self {
+        Self {
+            query,
+            lowercase_query,
+            query_chars,
+            smart_case,
+            max_results,
+            min_score: 0.0,
+
+            match_positions: vec![0; query.len()],
+            last_positions: vec![0; query.len()],
+
+            score_matrix: Vec::new(),
+            best_position_matrix: Vec::new(),
+
+            text: Vec::new(),
+            lowercase_text: Vec::new()
+        }
+    }
+
+    /// pontua `text` ignorando os primeiros `skipped_prefix_len` caracteres, deixando as
+    /// posições encontradas em `match_positions`
+    ///
+    /// retorna zero quando o texto não contém a consulta ou não supera o pior resultado guardado
+    fn score(&mut self, text_chars: CharBag, text: &str, skipped_prefix_len: usize, weight: f64) -> f64 {
+        if !text_chars.is_superset(self.query_chars) {
+            return 0.0;
+        }
+
+        self.text.clear();
+        self.text.extend(text.chars());
+
+        self.lowercase_text.clear();
+        self.lowercase_text.extend(text.chars().map(fold_case));
+
+        if !find_last_positions(
+            &mut self.last_positions,
+            skipped_prefix_len,
+
+            &self.lowercase_text,
+            self.lowercase_query
+        ) {
+            return 0.0;
+        }
+
+        let matrix_len = self.query.len() * (self.text.len() - skipped_prefix_len);
+
+        self.score_matrix.clear();
+        self.score_matrix.resize(matrix_len, None);
+
+        self.best_position_matrix.clear();
+        self.best_position_matrix.resize(matrix_len, skipped_prefix_len);
+
+        // a poda compara a pontuação sem o peso, então o mínimo precisa ser ajustado a ele
+        weight * score_match(
+            self.query,
+            self.lowercase_query,
+            &self.text,
+            &self.lowercase_text,
+
+            skipped_prefix_len,
+            self.smart_case,
+            &self.last_positions,
+            &mut self.score_matrix,
+            &mut self.best_position_matrix,
+            &mut self.match_positions,
+            self.min_score / weight
+        )
+    }
+
+    /// guarda um resultado, elevando o mínimo para podar os próximos candidatos quando a lista
+    /// estiver cheia
+    fn push<T: Ord>(&mut self, results: &mut BinaryHeap<Reverse<T>>, result: T, score: impl Fn(&T) -> f64) {
+        results.push(Reverse(result));
+
+        if results.len() == self.max_results {
+            self.min_score = score(&results.peek().unwrap().0);
+        }
+    }
+}
+
 impl PartialEq for PathMatch {
     fn eq(&self, other: &Self) -> bool {
         self.score.eq(&other.score)
@@ -48,23 +201,36 @@ impl Ord for PathMatch {
     }
 }
 
+/// `weights` multiplica a pontuação de entradas específicas, identificadas por `(tree_id, entry_id)`,
+/// o que permite favorecer arquivos usados com frequência e rebaixar o arquivo aberto
 pub fn match_paths(
-    paths_by_tree_id: &[(usize, usize, &[PathEntry])],
+    snapshots: &[Snapshot],
     query: &str,
     include_ignored: bool,
     smart_case: bool,
+    weights: &HashMap<(usize, usize), f64>,
     max_results: usize
 ) -> Vec<PathMatch> {
-    let lowercase_query = query.to_lowercase().chars().collect::<Vec<_>>();
+    let lowercase_query = query.chars().map(fold_case).collect::<Vec<_>>();
     let query = query.chars().collect::<Vec<_>>();
     let lowercase_query = &lowercase_query;
 
     let query = &query;
     let query_chars = CharBag::from(&lowercase_query[..]);
 
+    // os segmentos são divididos pelos contadores dos resumos das árvores, então cada thread
+    // começa a ler direto do seu primeiro arquivo sem percorrer os anteriores
+    let file_count = |snapshot: &Snapshot| {
+        if include_ignored {
+            snapshot.file_count()
+        } else {
+            snapshot.visible_file_count()
+        }
+    };
+
     let cpus = num_cpus::get();
-    let path_count = paths_by_tree_id.iter().fold(0, |sum, (_, _, paths)| sum + paths.len());
-    
+    let path_count = snapshots.iter().map(file_count).sum::<usize>();
+
     let segment_size = (path_count + cpus - 1) / cpus;
     let mut segment_results = (0..cpus).map(|_| BinaryHeap::new()).collect::<Vec<_>>();
 
@@ -75,44 +241,41 @@ pub fn match_paths(
                 let segment_start = segment_idx * segment_size;
                 let segment_end = segment_start + segment_size;
 
-                let mut min_score = 0.0;
-                let mut last_positions = Vec::new();
-                last_positions.resize(query.len(), 0);
-
-                let mut match_positions = Vec::new();
-                match_positions.resize(query.len(), 0);
-
-                let mut score_matrix = Vec::new();
-                let mut best_position_matrix = Vec::new();
+                let mut matcher = Matcher::new(query, lowercase_query, query_chars, smart_case, max_results);
 
                 let mut tree_start = 0;
 
-                for (tree_id, skipped_prefix_len, paths) in paths_by_tree_id {
-                    let tree_end = tree_start + paths.len();
+                for snapshot in snapshots {
+                    let tree_end = tree_start + file_count(snapshot);
 
                     if tree_start < segment_end && segment_start < tree_end {
                         let start = max(tree_start, segment_start) - tree_start;
                         let end = min(tree_end, segment_end) - tree_start;
 
+                        let skipped_prefix_len = if snapshots.len() == 1 {
+                            skipped_prefix_len(snapshot)
+                        } else {
+                            0
+                        };
+
+                        let path_entries = snapshot
+                            .files(include_ignored, start)
+                            .take(end - start)
+                            .map(|entry| PathEntry {
+                                entry_id: entry.id(),
+                                path_chars: entry.path_chars(),
+                                path: entry.path(),
+                                is_ignored: entry.is_ignored()
+                            });
+
                         match_single_tree_paths(
-                            *tree_id,
-                            *skipped_prefix_len,
-                            paths,
-                            start,
-                            end,
-                            query,
-                            lowercase_query,
-                            query_chars,
+                            &mut matcher,
+                            snapshot.id(),
+                            skipped_prefix_len,
+                            path_entries,
                             include_ignored,
-                            smart_case,
-                            results,
-                            max_results,
-
-                            &mut min_score,
-                            &mut match_positions,
-                            &mut last_positions,
-                            &mut score_matrix,
-                            &mut best_position_matrix
+                            weights,
+                            results
                         );
                     }
 
@@ -122,8 +285,6 @@ pub fn match_paths(
 
                     tree_start = tree_end;
                 }
-
-                tree_start = tree_end;
             }
         ).run();
 
@@ -135,89 +296,163 @@ pub fn match_paths(
 
     results.sort_unstable_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
     results.truncate(max_results);
-    
+
     results
 }
 
-fn match_single_tree_paths(
-    tree_id: usize,
-    skipped_prefix_len: usize,
-    path_entries: &[PathEntry],
-    start: usize,
-    end: usize,
-    query: &[char],
-    lowercase_query: &[char],
-    query_chars: CharBag,
-    include_ignored: bool,
+/// entradas dadas como resultados sem posições destacadas, na mesma ordem, para listar arquivos
+/// quando ainda não há uma consulta
+pub fn entry_matches(snapshots: &[Snapshot], entries: &[(usize, usize)]) -> Vec<PathMatch> {
+    entries
+        .iter()
+        .filter_map(|(tree_id, entry_id)| {
+            let snapshot = snapshots.iter().find(|snapshot| snapshot.id() == *tree_id)?;
+
+            snapshot.entry(*entry_id)?;
+
+            Some(PathMatch {
+                score: 0.0,
+                positions: Vec::new(),
+                tree_id: *tree_id,
+                entry_id: *entry_id,
+
+                skipped_prefix_len: if snapshots.len() == 1 {
+                    skipped_prefix_len(snapshot)
+                } else {
+                    0
+                }
+            })
+        }).collect()
+}
+
+/// pontua candidatos arbitrários com o mesmo algoritmo de `match_paths`, dividindo-os entre as cpus
+///
+/// uma consulta vazia retorna os primeiros candidatos, na ordem dada
+pub fn match_strings(
+    candidates: &[StringMatchCandidate],
+    query: &str,
     smart_case: bool,
+    max_results: usize
+) -> Vec<StringMatch> {
+    if query.is_empty() {
+        return candidates
+            .iter()
+            .take(max_results)
+            .map(|candidate| StringMatch {
+                candidate_id: candidate.id,
+                score: 0.0,
+                positions: Vec::new(),
+                string: candidate.string.clone()
+            }).collect();
+    }
 
-    results: &mut BinaryHeap<Reverse<PathMatch>>,
-    max_results: usize,
+    let lowercase_query = query.chars().map(fold_case).collect::<Vec<_>>();
+    let query = query.chars().collect::<Vec<_>>();
+    let lowercase_query = &lowercase_query;
 
-    min_score: &mut f64,
+    let query = &query;
+    let query_chars = CharBag::from(&lowercase_query[..]);
 
-    match_positions: &mut Vec<usize>,
-    last_positions: &mut Vec<usize>,
+    let cpus = num_cpus::get();
+    let segment_size = max(1, (candidates.len() + cpus - 1) / cpus);
 
-    score_matrix: &mut Vec<Option<f64>>,
-    best_position_matrix: &mut Vec<usize>
-) {
-    for i in start..end {
-        let path_entry = unsafe { &path_entries.get_unchecked(i) };
+    let mut segment_results = candidates
+        .chunks(segment_size)
+        .map(|segment| (segment, BinaryHeap::new()))
+        .collect::<Vec<_>>();
 
-        if !include_ignored && path_entry.is_ignored {
-            continue;
-        }
+    Parallel::new()
+        .each(segment_results.iter_mut(), |(segment, results)| {
+            let mut matcher = Matcher::new(query, lowercase_query, query_chars, smart_case, max_results);
 
-        if !path_entry.path_chars.is_superset(query_chars) {
-            continue;
-        }
+            for candidate in segment.iter() {
+                let score = matcher.score(candidate.char_bag, &candidate.string, 0, 1.0);
 
-        if !find_last_positions(
-            last_positions,
-            skipped_prefix_len,
+                if score > 0.0 {
+                    let result = StringMatch {
+                        candidate_id: candidate.id,
+                        score,
+                        positions: matcher.match_positions.clone(),
+                        string: candidate.string.clone()
+                    };
 
-            &path_entry.lowercase_path,
-            &lowercase_query[..]
-        ) {
-            continue;
+                    matcher.push(results, result, |result| result.score);
+                }
+            }
+        }).run();
+
+    let mut results = segment_results
+        .into_iter()
+        .flat_map(|(_, results)| results)
+        .map(|r| r.0)
+        .collect::<Vec<_>>();
+
+    // empates mantêm a ordem dos candidatos para que a lista não pule entre buscas
+    results.sort_unstable_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then(a.candidate_id.cmp(&b.candidate_id))
+    });
+
+    results.truncate(max_results);
+
+    results
+}
+
+/// com uma única árvore, o nome da raiz não entra na pontuação
+fn skipped_prefix_len(snapshot: &Snapshot) -> usize {
+    match snapshot.root_entry() {
+        Some(root) if root.is_dir() => {
+            let name = root.path().to_string_lossy();
+
+            if name == "/" {
+                1
+            } else {
+                name.chars().count() + 1
+            }
         }
 
-        let matrix_len = query.len() * (path_entry.path.len() - skipped_prefix_len);
-        
-        score_matrix.clear();
-        score_matrix.resize(matrix_len, None);
+        _ => 0
+    }
+}
 
-        best_position_matrix.clear();
-        best_position_matrix.resize(matrix_len, skipped_prefix_len);
+fn match_single_tree_paths<'a>(
+    matcher: &mut Matcher,
+    tree_id: usize,
+    skipped_prefix_len: usize,
+    path_entries: impl Iterator<Item = PathEntry<'a>>,
+    include_ignored: bool,
+    weights: &HashMap<(usize, usize), f64>,
+    results: &mut BinaryHeap<Reverse<PathMatch>>
+) {
+    for path_entry in path_entries {
+        if !include_ignored && path_entry.is_ignored {
+            continue;
+        }
 
-        let score = score_match(
-            &query[..],
-            &lowercase_query[..],
-            &path_entry.path,
-            &path_entry.lowercase_path,
+        let weight = weights
+            .get(&(tree_id, path_entry.entry_id))
+            .copied()
+            .unwrap_or(1.0);
 
+        let score = matcher.score(
+            path_entry.path_chars,
+            &path_entry.path.to_string_lossy(),
             skipped_prefix_len,
-            smart_case,
-            &last_positions,
-            score_matrix,
-            best_position_matrix,
-            match_positions,
-            *min_score
+            weight
         );
 
         if score > 0.0 {
-            results.push(Reverse(PathMatch {
+            let result = PathMatch {
                 tree_id,
                 entry_id: path_entry.entry_id,
                 score,
-                positions: match_positions.clone(),
+                positions: matcher.match_positions.clone(),
                 skipped_prefix_len
-            }));
+            };
 
-            if results.len() == max_results {
-                *min_score = results.peek().unwrap().0.score;
-            }
+            matcher.push(results, result, |result| result.score);
         }
     }
 }
@@ -459,7 +694,7 @@ mod tests {
         ];
 
         assert_eq!(
-            match_query("abc", false, &paths),
+            match_query("abc", false, &HashMap::new(), &paths),
 
             vec![
                 ("abC", vec![0, 1, 2]),
@@ -471,13 +706,13 @@ mod tests {
         );
 
         assert_eq!(
-            match_query("t/i/a/t/d", false, &paths),
+            match_query("t/i/a/t/d", false, &HashMap::new(), &paths),
 
             vec![("/this/is/a/test/dir", vec![1, 5, 6, 8, 9, 10, 11, 15, 16])]
         );
 
         assert_eq!(
-            match_query("tiatd", false, &paths),
+            match_query("tiatd", false, &HashMap::new(), &paths),
 
             vec![
                 ("/test/tiatd", vec![6, 7, 8, 9, 10]),
@@ -488,67 +723,142 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_match_non_ascii_paths() {
+        let paths = vec![
+            "café/Ñandú.rs",
+            "日本語/テスト.txt",
+            "snake_case/módulo.rs",
+            "İstanbul.txt"
+        ];
+
+        let matches = |query: &str| match_query(query, false, &HashMap::new(), &paths);
+
+        assert_eq!(matches("ñan"), vec![("café/Ñandú.rs", vec![5, 6, 7])]);
+        assert_eq!(matches("CAFÉ"), vec![("café/Ñandú.rs", vec![0, 1, 2, 3])]);
+        assert_eq!(matches("テスト"), vec![("日本語/テスト.txt", vec![4, 5, 6])]);
+        assert_eq!(matches("e_c"), vec![("snake_case/módulo.rs", vec![4, 5, 6])]);
+
+        // a minúscula de `İ` tem dois caracteres, então a letra é mantida para não deslocar as
+        // posições seguintes
+        assert_eq!(matches("stanbul"), vec![("İstanbul.txt", vec![1, 2, 3, 4, 5, 6, 7])]);
+
+        assert!(CharBag::from("Ñandú").is_superset(CharBag::from("ñÚ")));
+        assert!(CharBag::from("日本語").is_superset(CharBag::from("本")));
+        assert!(!CharBag::from("snake-case").is_superset(CharBag::from("_")));
+    }
+
+    #[test]
+    fn test_match_weights() {
+        let paths = vec!["src/abc.rs", "src/a/b/c.rs", "src/alpha/bravo.c"];
+
+        let matches = |weights: &[(usize, f64)]| {
+            let weights = weights.iter().map(|(entry_id, weight)| ((0, *entry_id), *weight)).collect();
+
+            match_query("abc", false, &weights, &paths)
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matches(&[]), vec!["src/abc.rs", "src/a/b/c.rs", "src/alpha/bravo.c"]);
+
+        // um arquivo usado com frequência sobe, e o arquivo aberto desce
+        assert_eq!(matches(&[(2, 1000.0)]), vec!["src/alpha/bravo.c", "src/abc.rs", "src/a/b/c.rs"]);
+        assert_eq!(matches(&[(0, 0.001)]), vec!["src/a/b/c.rs", "src/alpha/bravo.c", "src/abc.rs"]);
+    }
+
+    #[test]
+    fn test_match_strings() {
+        let candidates = string_candidates(&["buffer_view", "BufferView", "Buffer View"]);
+
+        // sem smart case a caixa não conta e a troca de caixa no meio da palavra pesa como início
+        assert_eq!(match_ids(&candidates, "BV", false, 10), vec![1, 0, 2]);
+        assert_eq!(match_ids(&candidates, "bv", false, 10), vec![1, 0, 2]);
+
+        // com smart case as correspondências com a mesma caixa vêm primeiro
+        assert_eq!(match_ids(&candidates, "bv", true, 10), vec![0, 1, 2]);
+        assert_eq!(match_ids(&candidates, "BV", true, 10), vec![1, 2, 0]);
+
+        // as posições contam caracteres, não bytes
+        let candidates = string_candidates(&["Ação: Édição", "Acao: Edicao", "Überblick"]);
+
+        let matches = match_strings(&candidates, "ÉD", false, 10);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].positions, vec![6, 7]);
+
+        assert_eq!(match_strings(&candidates, "ç", false, 10)[0].positions, vec![1]);
+        assert_eq!(match_ids(&candidates, "üb", false, 10), vec![2]);
+
+        assert!(match_strings(&candidates, "xyz", false, 10).is_empty());
+
+        // a consulta vazia devolve os candidatos na ordem original, sem posições
+        let matches = match_strings(&candidates, "", false, 2);
+
+        assert_eq!(matches.iter().map(|m| m.candidate_id).collect::<Vec<_>>(), vec![0, 1]);
+        assert!(matches.iter().all(|m| m.positions.is_empty()));
+
+        assert!(match_strings(&candidates, "", false, 0).is_empty());
+
+        // empates mantêm a ordem dos candidatos, inclusive ao cortar a lista
+        let candidates = string_candidates(&["ab", "ab", "b a b", "ab"]);
+
+        assert_eq!(match_ids(&candidates, "ab", false, 10), vec![0, 1, 3, 2]);
+        assert_eq!(match_ids(&candidates, "ab", false, 2), vec![0, 1]);
+
+        // conjuntos grandes são divididos entre threads sem perder resultados
+        let candidates = (0..1000)
+            .map(|id| StringMatchCandidate::new(id, format!("item {}", id)))
+            .collect::<Vec<_>>();
+
+        let matches = match_strings(&candidates, "item 99", false, 5);
+
+        assert_eq!(matches.len(), 5);
+        assert_eq!(matches[0].string, "item 99");
+    }
+
+    fn string_candidates(strings: &[&str]) -> Vec<StringMatchCandidate> {
+        strings
+            .iter()
+            .enumerate()
+            .map(|(id, string)| StringMatchCandidate::new(id, string.to_string()))
+            .collect()
+    }
+
+    fn match_ids(candidates: &[StringMatchCandidate], query: &str, smart_case: bool, max_results: usize) -> Vec<usize> {
+        match_strings(candidates, query, smart_case, max_results)
+            .into_iter()
+            .map(|m| m.candidate_id)
+            .collect()
+    }
+
     fn match_query<'a>(
         query: &str,
         smart_case: bool,
+        weights: &HashMap<(usize, usize), f64>,
         paths: &Vec<&'a str>
     ) -> Vec<(&'a str, Vec<usize>)> {
-        let lowercase_query = query.to_lowercase().chars().collect::<Vec<_>>();
+        let lowercase_query = query.chars().map(fold_case).collect::<Vec<_>>();
 
         let query = query.chars().collect::<Vec<_>>();
         let query_chars = CharBag::from(&lowercase_query[..]);
 
-        let mut path_entries = Vec::new();
-
-        for (i, path) in paths.iter().enumerate() {
-            let lowercase_path = path.to_lowercase().chars().collect::<Vec<_>>();
-
-            let path_chars = CharBag::from(&lowercase_path[..]);
-            let path = path.chars().collect();
-
-            path_entries.push(PathEntry {
-                entry_id: i,
-                path_chars,
-                path,
-                lowercase_path,
-                is_ignored: false
-            });
-        }
-
-        let mut match_positions = Vec::new();
-        let mut last_positions = Vec::new();
-
-        match_positions.resize(query.len(), 0);
-        last_positions.resize(query.len(), 0);
+        let path_entries = paths.iter().enumerate().map(|(i, path)| PathEntry {
+            entry_id: i,
+            path_chars: CharBag::from(*path),
+            path: Path::new(path),
+            is_ignored: false
+        });
 
+        let mut matcher = Matcher::new(&query, &lowercase_query, query_chars, smart_case, 100);
         let mut results = BinaryHeap::new();
 
-        match_single_tree_paths(
-            0,
-            0,
-            &path_entries,
-            0,
-            path_entries.len(),
-            &query[..],
-            &lowercase_query[..],
-            query_chars,
-            true,
-            smart_case,
-            &mut results,
-            100,
-
-            &mut 0.0,
-            
-            &mut match_positions,
-            &mut last_positions,
-
-            &mut Vec::new(),
-            &mut Vec::new()
-        );
+        match_single_tree_paths(&mut matcher, 0, 0, path_entries, true, weights, &mut results);
 
         results
+            .into_sorted_vec()
             .into_iter()
-            .rev()
             .map(|result| (paths[result.0.entry_id].clone(), result.0.positions))
             .collect()
     }
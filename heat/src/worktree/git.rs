@@ -0,0 +1,202 @@
+use super::worktree::Snapshot;
+use smol::process::{Command, Output};
+
+use std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf}
+};
+
+// o status vem do executável `git`, rodando sempre sobre o repositório local
+//
+// `GIT_OPTIONAL_LOCKS=0` impede que o `git status` reescreva o índice, o que faria o observador
+// de arquivos disparar uma nova atualização a cada status calculado
+
+/// em ordem de prioridade, para resumir o status dos arquivos de um diretório
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum GitStatus {
+    Ignored,
+    Untracked,
+    Added,
+    Modified,
+    Conflicted
+}
+
+/// status das entradas com mudanças em todos os repositórios da árvore, indexados pelo caminho
+/// relativo das entradas
+pub async fn load_statuses(snapshot: &Snapshot) -> BTreeMap<PathBuf, GitStatus> {
+    let mut statuses = BTreeMap::new();
+
+    let root = match snapshot.root_entry() {
+        Some(root) => root,
+
+        None => return statuses
+    };
+
+    let base = snapshot.path().parent().unwrap_or(snapshot.path()).to_path_buf();
+
+    // a própria raiz, que pode estar dentro de um repositório maior, e depois cada diretório com
+    // um `.git` dentro da árvore
+    let mut dirs = snapshot
+        .entries()
+        .filter(|entry| entry.name() == ".git")
+        .filter_map(|entry| Some(base.join(entry.path().parent()?)))
+        .collect::<Vec<_>>();
+
+    dirs.retain(|dir| dir.as_path() != snapshot.path());
+    dirs.sort();
+
+    let root_pathspec = if root.is_dir() {
+        (snapshot.path().to_path_buf(), OsStr::new("."))
+    } else {
+        (base.clone(), root.name())
+    };
+
+    let pathspecs = Some(root_pathspec)
+        .into_iter()
+        .chain(dirs.into_iter().map(|dir| (dir, OsStr::new("."))));
+
+    for (dir, pathspec) in pathspecs {
+        match repository_statuses(&dir, pathspec).await {
+            Ok(Some((work_dir, repository_statuses))) => {
+                // um repositório aninhado aparece como um diretório não rastreado no de fora
+                if let Ok(path) = work_dir.strip_prefix(&base) {
+                    statuses.remove(path);
+                }
+
+                for (path, status) in repository_statuses {
+                    if let Ok(path) = work_dir.join(path).strip_prefix(&base) {
+                        if path.starts_with(root.path()) {
+                            statuses.insert(path.to_path_buf(), status);
+                        }
+                    }
+                }
+            }
+
+            Ok(None) => {}
+
+            Err(error) => log::error!("erro ao ler o status do git em {:?}: {}", dir, error)
+        }
+    }
+
+    statuses
+}
+
+/// conteúdo do arquivo no commit `HEAD` do repositório que o contém
+pub async fn load_head_text(abs_path: &Path) -> Option<String> {
+    let mut spec = OsString::from("HEAD:./");
+
+    spec.push(abs_path.file_name()?);
+
+    let output = git(abs_path.parent()?, &[OsStr::new("show"), spec.as_os_str()]).await.ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// diretório de trabalho do repositório que contém `dir` e o status dos caminhos abaixo de
+/// `pathspec`, relativos a esse diretório, ou `None` se `dir` não está em um repositório
+async fn repository_statuses(dir: &Path, pathspec: &OsStr) -> io::Result<Option<(PathBuf, Vec<(PathBuf, GitStatus)>)>> {
+    let output = git(dir, &[OsStr::new("rev-parse"), OsStr::new("--show-toplevel")]).await?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let stdout = output.stdout.strip_suffix(b"\n").unwrap_or(&output.stdout);
+    let work_dir = PathBuf::from(OsStr::from_bytes(stdout));
+
+    let output = git(
+        dir,
+
+        &[
+            OsStr::new("status"),
+            OsStr::new("--porcelain=v1"),
+            OsStr::new("-z"),
+            OsStr::new("--ignored=matching"),
+            OsStr::new("--untracked-files=all"),
+            OsStr::new("--"),
+            pathspec
+        ]
+    ).await?;
+
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).trim().to_string()
+        ));
+    }
+
+    Ok(Some((work_dir, parse_statuses(&output.stdout))))
+}
+
+/// interpreta a saída de `git status --porcelain=v1 -z`
+fn parse_statuses(output: &[u8]) -> Vec<(PathBuf, GitStatus)> {
+    let mut statuses = Vec::new();
+    let mut fields = output.split(|byte| *byte == 0);
+
+    while let Some(field) = fields.next() {
+        if field.len() < 4 {
+            continue;
+        }
+
+        let (x, y) = (field[0], field[1]);
+
+        // renomeações e cópias trazem o caminho de origem no campo seguinte
+        if x == b'R' || x == b'C' {
+            fields.next();
+        }
+
+        let status = match (x, y) {
+            (b'?', b'?') => GitStatus::Untracked,
+            (b'!', b'!') => GitStatus::Ignored,
+
+            (b'U', _) | (_, b'U') | (b'A', b'A') | (b'D', b'D') => GitStatus::Conflicted,
+            (b'A', _) => GitStatus::Added,
+
+            _ => GitStatus::Modified
+        };
+
+        statuses.push((PathBuf::from(OsStr::from_bytes(&field[3..])), status));
+    }
+
+    statuses
+}
+
+async fn git(dir: &Path, args: &[&OsStr]) -> io::Result<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statuses() {
+        let output = b" M src/main.rs\0A  src/new.rs\0R  src/b.rs\0src/a.rs\0UU src/c.rs\0?? notes.txt\0!! target/\0";
+
+        assert_eq!(
+            parse_statuses(output),
+
+            vec![
+                (PathBuf::from("src/main.rs"), GitStatus::Modified),
+                (PathBuf::from("src/new.rs"), GitStatus::Added),
+                (PathBuf::from("src/b.rs"), GitStatus::Modified),
+                (PathBuf::from("src/c.rs"), GitStatus::Conflicted),
+                (PathBuf::from("notes.txt"), GitStatus::Untracked),
+                (PathBuf::from("target/"), GitStatus::Ignored)
+            ]
+        );
+    }
+}
@@ -1,5 +1,11 @@
 mod char_bag;
 mod fuzzy;
+mod git;
+mod trash;
+#[cfg(target_os = "linux")]
+mod watcher;
 mod worktree;
 
-pub use worktree::{match_paths, FileHandle, PathMatch, Worktree, WorktreeHandle};
\ No newline at end of file
+pub use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+pub use git::GitStatus;
+pub use worktree::{entry_matches, match_paths, Entry, Event, FileHandle, PathMatch, Snapshot, Worktree, WorktreeHandle};
\ No newline at end of file
@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+#[cfg(not(target_os = "macos"))]
+use {
+    super::worktree::copy_recursively,
+    crate::util::post_inc,
+    std::{
+        ffi::{OsStr, OsString},
+        fs, io,
+        io::Write,
+        os::unix::ffi::OsStrExt
+    }
+};
+
+/// move o caminho para a lixeira do usuário usando a API do finder, que escolhe o nome dentro da
+/// lixeira e permite restaurar o item depois
+#[cfg(target_os = "macos")]
+pub fn move_to_trash(path: &Path) -> Result<()> {
+    use cocoa::{
+        base::{id, nil, BOOL, NO},
+        foundation::NSString
+    };
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::{ffi::CStr, os::raw::c_char, ptr};
+
+    let path_str = path.to_str().ok_or_else(|| anyhow!("caminho inválido {:?}", path))?;
+
+    unsafe {
+        let ns_path = NSString::alloc(nil).init_str(path_str);
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: ns_path];
+
+        let manager: id = msg_send![class!(NSFileManager), defaultManager];
+        let mut error: id = nil;
+
+        let trashed: BOOL = msg_send![manager, trashItemAtURL: url resultingItemURL: ptr::null_mut::<id>() error: &mut error];
+        let _: () = msg_send![ns_path, release];
+
+        if trashed == NO {
+            let description: id = msg_send![error, localizedDescription];
+            let description: *const c_char = msg_send![description, UTF8String];
+
+            return Err(anyhow!("erro ao mover {:?} para a lixeira: {}", path, CStr::from_ptr(description).to_string_lossy()));
+        }
+    }
+
+    Ok(())
+}
+
+/// move o caminho para a lixeira do usuário seguindo a especificação do freedesktop: o item vai
+/// para `files` e um `.trashinfo` com o caminho original e a data vai para `info`, o que permite
+/// ao gerenciador de arquivos restaurá-lo
+#[cfg(not(target_os = "macos"))]
+pub fn move_to_trash(path: &Path) -> Result<()> {
+    let trash_dir = dirs::data_dir()
+        .ok_or_else(|| anyhow!("não foi possível localizar o diretório de dados"))?
+        .join("Trash");
+
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+
+    // só o diretório pai é resolvido, para que um link simbólico vá para a lixeira em vez do seu alvo
+    let name = path.file_name().ok_or_else(|| anyhow!("caminho inválido {:?}", path))?;
+    let path = path.parent().unwrap_or(path).canonicalize()?.join(name);
+
+    let (trash_name, mut info) = reserve_name(&files_dir, &info_dir, name)?;
+
+    write!(info, "[Trash Info]\nPath={}\nDeletionDate={}\n", encode_path(&path), deletion_date())?;
+
+    let trash_path = files_dir.join(&trash_name);
+
+    let moved = match fs::rename(&path, &trash_path) {
+        // a lixeira fica no diretório base, que pode estar em outro sistema de arquivos
+        Err(error) if error.raw_os_error() == Some(libc::EXDEV) => {
+            copy_recursively(&path, &trash_path).and_then(|_| {
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)
+                } else {
+                    fs::remove_file(&path)
+                }
+            })
+        }
+
+        result => result
+    };
+
+    if let Err(error) = moved {
+        let mut trash_info = trash_name;
+        trash_info.push(".trashinfo");
+
+        fs::remove_file(info_dir.join(trash_info)).ok();
+
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// escolhe um nome livre na lixeira, acrescentando um número quando já houver um item com o mesmo
+/// nome, e cria o `.trashinfo` correspondente de forma exclusiva para que dois itens nunca recebam
+/// o mesmo nome
+#[cfg(not(target_os = "macos"))]
+fn reserve_name(files_dir: &Path, info_dir: &Path, name: &OsStr) -> io::Result<(OsString, fs::File)> {
+    let mut trash_name = name.to_os_string();
+    let mut ix = 2;
+
+    loop {
+        let mut trash_info = trash_name.clone();
+        trash_info.push(".trashinfo");
+
+        if !files_dir.join(&trash_name).exists() {
+            match fs::OpenOptions::new().write(true).create_new(true).open(info_dir.join(trash_info)) {
+                Ok(info) => return Ok((trash_name, info)),
+
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(error) => return Err(error)
+            }
+        }
+
+        trash_name = OsString::from(format!("{} {}", name.to_string_lossy(), post_inc(&mut ix)));
+    }
+}
+
+/// o caminho original como exige o `.trashinfo`: bytes fora dos caracteres não reservados das
+/// urls são escritos como `%XX`
+#[cfg(not(target_os = "macos"))]
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+/// data e hora locais no formato `AAAA-MM-DDThh:mm:ss`
+#[cfg(not(target_os = "macos"))]
+fn deletion_date() -> String {
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm = std::mem::zeroed::<libc::tm>();
+
+        libc::localtime_r(&now, &mut tm);
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday,
+            tm.tm_hour,
+            tm.tm_min,
+            tm.tm_sec
+        )
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+    use serde_json::json;
+
+    #[test]
+    fn test_reserve_name() -> Result<()> {
+        let dir = temp_tree(json!({
+            "files": {
+                "notes.txt": ""
+            },
+
+            "info": {}
+        }));
+
+        let (files_dir, info_dir) = (dir.path().join("files"), dir.path().join("info"));
+
+        // dois `mod.rs` recebem nomes diferentes na lixeira
+        let (first, _) = reserve_name(&files_dir, &info_dir, OsStr::new("mod.rs"))?;
+        let (second, _) = reserve_name(&files_dir, &info_dir, OsStr::new("mod.rs"))?;
+
+        assert_eq!(first, "mod.rs");
+        assert_eq!(second, "mod.rs 2");
+
+        assert!(info_dir.join("mod.rs.trashinfo").is_file());
+        assert!(info_dir.join("mod.rs 2.trashinfo").is_file());
+
+        // um item já na lixeira também ocupa o nome
+        assert_eq!(reserve_name(&files_dir, &info_dir, OsStr::new("notes.txt"))?.0, "notes.txt 2");
+
+        assert_eq!(encode_path(Path::new("/tmp/a b/ç.rs")), "/tmp/a%20b/%C3%A7.rs");
+
+        Ok(())
+    }
+}
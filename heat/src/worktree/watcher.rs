@@ -0,0 +1,237 @@
+use smol::{prelude::*, Async, Timer};
+
+use std::{
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    fs, io, mem,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsRawFd, RawFd}
+    },
+    path::{Path, PathBuf},
+    time::Duration
+};
+
+// os eventos do inotify são agrupados: depois do primeiro evento, continuamos lendo até que
+// `DEBOUNCE` passe sem novidades e só então entregamos os caminhos alterados de uma vez
+
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_ATTRIB
+    | libc::IN_ONLYDIR;
+
+struct Inotify(RawFd);
+
+impl AsRawFd for Inotify {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.0);
+        }
+    }
+}
+
+pub struct Watcher {
+    inotify: Async<Inotify>,
+    dirs: HashMap<i32, PathBuf>,
+    buffer: Vec<u8>
+}
+
+impl Watcher {
+    pub fn new() -> io::Result<Self> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            inotify: Async::new(Inotify(fd))?,
+            dirs: HashMap::new(),
+            buffer: vec![0; 64 * 1024]
+        })
+    }
+
+    pub fn watch_dir(&mut self, path: &Path) -> io::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let wd = unsafe { libc::inotify_add_watch(self.inotify.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        self.dirs.insert(wd, path.to_path_buf());
+
+        Ok(())
+    }
+
+    /// observa um diretório e todos os seus subdiretórios
+    ///
+    /// de `.git` só interessam o primeiro nível, onde ficam o índice e o `HEAD`, e as referências,
+    /// para que commits, checkouts e mudanças na área de preparação atualizem o status das
+    /// entradas e o texto do `HEAD`
+    pub fn watch_recursively(&mut self, path: &Path) -> io::Result<()> {
+        self.watch_dir(path)?;
+
+        for child in fs::read_dir(path)? {
+            let child = child?;
+
+            if child.file_type()?.is_dir() {
+                if child.file_name() == ".git" {
+                    self.watch_git_dir(&child.path())?;
+                } else {
+                    self.watch_recursively(&child.path())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn watch_git_dir(&mut self, path: &Path) -> io::Result<()> {
+        self.watch_dir(path)?;
+
+        let refs = path.join("refs");
+
+        if refs.is_dir() {
+            self.watch_recursively(&refs)?;
+        }
+
+        Ok(())
+    }
+
+    /// caminhos alterados no próximo lote de eventos lidos
+    async fn read_changes(&mut self) -> io::Result<Vec<PathBuf>> {
+        let buffer = &mut self.buffer;
+
+        let len = self
+            .inotify
+            .read_with(|inotify| {
+                let len = unsafe {
+                    libc::read(inotify.0, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len())
+                };
+
+                if len < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(len as usize)
+                }
+            }).await?;
+
+        let mut changes = Vec::new();
+        let mut new_dirs = Vec::new();
+        let mut new_git_dirs = Vec::new();
+        let mut offset = 0;
+
+        while offset + mem::size_of::<libc::inotify_event>() <= len {
+            let event = unsafe {
+                (self.buffer.as_ptr().add(offset) as *const libc::inotify_event).read_unaligned()
+            };
+
+            let name_start = offset + mem::size_of::<libc::inotify_event>();
+            let name_end = name_start + event.len as usize;
+
+            let name = self.buffer[name_start..name_end]
+                .split(|byte| *byte == 0)
+                .next()
+                .unwrap_or(&[]);
+
+            offset = name_end;
+
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                // eventos foram perdidos, então todos os diretórios observados são sincronizados
+                log::warn!("a fila de eventos do inotify transbordou");
+
+                changes.extend(self.dirs.values().cloned());
+
+                continue;
+            }
+
+            if event.mask & libc::IN_IGNORED != 0 {
+                self.dirs.remove(&event.wd);
+
+                continue;
+            }
+
+            let dir = match self.dirs.get(&event.wd) {
+                Some(dir) => dir,
+
+                None => continue
+            };
+
+            let path = if name.is_empty() {
+                dir.clone()
+            } else {
+                dir.join(OsStr::from_bytes(name))
+            };
+
+            let is_new_dir = event.mask & libc::IN_ISDIR != 0 && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+
+            // um `.git` criado depois do início, como num `git init`, é observado como os que já
+            // existiam; as suas `refs` podem surgir só depois dele
+            if is_new_dir {
+                if name == b".git" {
+                    new_git_dirs.push(path.clone());
+                } else if dir.file_name() != Some(OsStr::new(".git")) || name == b"refs" {
+                    new_dirs.push(path.clone());
+                }
+            }
+
+            changes.push(path);
+        }
+
+        for dir in new_dirs {
+            if let Err(error) = self.watch_recursively(&dir) {
+                log::error!("erro ao observar {:?}: {}", dir, error);
+            }
+        }
+
+        for dir in new_git_dirs {
+            if let Err(error) = self.watch_git_dir(&dir) {
+                log::error!("erro ao observar {:?}: {}", dir, error);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// próximo lote de caminhos alterados, ordenados e sem repetições
+    pub async fn next_batch(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut batch = Vec::new();
+
+        while batch.is_empty() {
+            batch = self.read_changes().await?;
+        }
+
+        loop {
+            let changes = self
+                .read_changes()
+                .or(async {
+                    Timer::after(DEBOUNCE).await;
+
+                    Ok(Vec::new())
+                }).await?;
+
+            if changes.is_empty() {
+                break;
+            }
+
+            batch.extend(changes);
+        }
+
+        batch.sort();
+        batch.dedup();
+
+        Ok(batch)
+    }
+}
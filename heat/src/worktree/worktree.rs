@@ -1,11 +1,21 @@
-pub use super::fuzzy::PathMatch;
+pub use super::fuzzy::{entry_matches, match_paths, PathMatch};
+
+#[cfg(target_os = "linux")]
+use super::watcher::Watcher;
 
 use super::{
     char_bag::CharBag,
-    fuzzy::{self, PathEntry}
+    git::{self, GitStatus},
+    trash::move_to_trash
+};
+
+use crate::{
+    editor::History,
+    sum_tree::{self, Cursor, Edit, SeekBias, SumTree},
+    timer,
+    util::post_inc
 };
 
-use crate::{editor::History, timer, util::post_inc};
 use anyhow::{anyhow, Result};
 use crossbeam_channel as channel;
 use easy_parallel::Parallel;
@@ -15,26 +25,60 @@ use parking_lot::RwLock;
 use smol::prelude::*;
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     ffi::{OsStr, OsString},
-    fmt, fs, io,
+    fmt, fs, io, mem,
+    ops::AddAssign,
     os::unix::fs::MetadataExt,
     path::Path,
     path::PathBuf,
     sync::Arc,
-    time::Duration
+    time::{Duration, SystemTime}
 };
 
 #[derive(Clone)]
 pub struct Worktree(Arc<RwLock<WorktreeState>>);
 
 struct WorktreeState {
-    id: usize,
-    path: PathBuf,
-    entries: Vec<Entry>,
-    file_paths: Vec<PathEntry>,
+    snapshot: Snapshot,
+    next_entry_id: usize,
+
+    // caminhos de entradas que saíram da árvore, para que `FileHandle`s antigos ainda
+    // consigam descrever o arquivo que abriram; cada caminho é descartado quando o último
+    // `FileHandle` da entrada deixa de existir
+    removed_paths: HashMap<usize, Arc<Path>>,
+    open_files: HashMap<usize, usize>,
+
     histories: HashMap<usize, History>,
-    scanning: bool
+    scanning: bool,
+
+    // lotes do observador que chegaram durante a varredura, aplicados quando ela termina
+    pending_changes: Vec<PathBuf>,
+
+    // caminhos alterados pela própria árvore, com a data de modificação que deixaram, para que o
+    // observador não os devolva como mudanças externas
+    own_changes: HashMap<PathBuf, Option<SystemTime>>,
+
+    // descarta resultados de cálculos de status que foram ultrapassados por outros mais novos
+    git_scan_id: usize,
+
+    // algo mudou em `.git` desde o último status carregado, como o `HEAD` ou uma referência
+    git_dir_changed: bool
+}
+
+/// cópia imutável das entradas de uma árvore de trabalho
+///
+/// as entradas ficam em `SumTree`s persistentes, então clonar um snapshot só custa alguns
+/// contadores de referência e tarefas em segundo plano podem lê-lo sem segurar a trava da árvore
+#[derive(Clone)]
+pub struct Snapshot {
+    id: usize,
+    path: Arc<Path>,
+
+    entries: SumTree<Entry>,
+    entries_by_id: SumTree<IdEntry>,
+
+    git_statuses: Arc<BTreeMap<PathBuf, GitStatus>>
 }
 
 struct DirToScan {
@@ -50,23 +94,47 @@ impl Worktree {
     where
         T: Into<PathBuf>
     {
+        let path: PathBuf = path.into();
+
         let tree = Self(Arc::new(RwLock::new(WorktreeState {
-            id,
+            snapshot: Snapshot {
+                id,
 
-            path: path.into(),
-            entries: Vec::new(),
-            file_paths: Vec::new(),
+                path: path.into(),
+                entries: SumTree::new(),
+                entries_by_id: SumTree::new(),
+
+                git_statuses: Arc::new(BTreeMap::new())
+            },
+
+            next_entry_id: 0,
+            removed_paths: HashMap::new(),
+            open_files: HashMap::new(),
             histories: HashMap::new(),
-            scanning: ctx.is_some()
+            scanning: ctx.is_some(),
+
+            pending_changes: Vec::new(),
+            own_changes: HashMap::new(),
+
+            git_scan_id: 0,
+            git_dir_changed: false
         })));
 
         if let Some(ctx) = ctx {
             tree.0.write().scanning = true;
 
+            // o observador começa antes da varredura para que nenhuma mudança feita durante ela
+            // se perca
+            #[cfg(target_os = "linux")]
+            let watching = tree.watch(ctx);
+
             let tree = tree.clone();
             let (tx, rx) = smol::channel::bounded(1);
 
             let task = ctx.background_executor().spawn(async move {
+                #[cfg(target_os = "linux")]
+                let _ = watching.recv().await;
+
                 let _ = tx.send(tree.scan_dirs()?).await;
 
                 Ok(())
@@ -87,17 +155,17 @@ impl Worktree {
     }
 
     fn scan_dirs(&self) -> io::Result<()> {
-        let path = self.0.read().path.clone();
+        let path = self.path();
         let metadata = fs::metadata(&path)?;
 
         let ino = metadata.ino();
         let is_symlink = fs::symlink_metadata(&path)?.file_type().is_symlink();
         let name = path.file_name().map(|name| OsString::from(name)).unwrap_or(OsString::from("/"));
-        
+
         let relative_path = PathBuf::from(&name);
 
         let mut ignore = IgnoreBuilder::new().build().add_parents(&path).unwrap();
-        
+
         if metadata.is_dir() {
             ignore = ignore.add_child(&path).unwrap();
         }
@@ -106,7 +174,9 @@ impl Worktree {
 
         if metadata.file_type().is_dir() {
             let is_ignored = is_ignored || name == ".git";
-            let id = self.push_dir(None, name, ino, is_symlink, is_ignored);
+            let id = self.next_entry_id();
+
+            self.insert_entries(vec![Entry::new(id, None, relative_path.clone(), true, ino, is_symlink, is_ignored)]);
 
             let (tx, rx) = channel::unbounded();
 
@@ -135,14 +205,17 @@ impl Worktree {
                 .into_iter()
                 .collect::<io::Result<()>>()?;
         } else {
-            self.push_file(None, name, ino, is_symlink, is_ignored, relative_path);
+            let id = self.next_entry_id();
+
+            self.insert_entries(vec![Entry::new(id, None, relative_path, false, ino, is_symlink, is_ignored)]);
         }
 
         Ok(())
     }
 
     fn scan_dir(&self, to_scan: DirToScan) -> io::Result<()> {
-        let mut new_children = Vec::new();
+        let mut new_entries = Vec::new();
+        let mut new_dirs = Vec::new();
 
         for child_entry in fs::read_dir(&to_scan.path)? {
             let child_entry = child_entry?;
@@ -151,6 +224,7 @@ impl Worktree {
             let metadata = child_entry.metadata()?;
             let ino = metadata.ino();
             let is_symlink = metadata.file_type().is_symlink();
+            let id = self.next_entry_id();
 
             if metadata.is_dir() {
                 let path = to_scan.path.join(&name);
@@ -160,210 +234,126 @@ impl Worktree {
 
                 if let Some(parent_ignore) = to_scan.ignore.as_ref() {
                     let child_ignore = parent_ignore.add_child(&path).unwrap();
-                    
+
                     is_ignored = child_ignore.matched(&path, true).is_ignore() || name == ".git";
-                    
+
                     if !is_ignored {
                         ignore = Some(child_ignore);
                     }
                 }
 
-                let id = self.push_dir(Some(to_scan.id), name, ino, is_symlink, is_ignored);
-                new_children.push(id);
-
-                let dirs_to_scan = to_scan.dirs_to_scan.clone();
-
-                let _ = to_scan.dirs_to_scan.send(Ok(DirToScan {
-                    id,
-                    path,
-                    relative_path,
-                    ignore,
-                    dirs_to_scan
-                }));
+                new_entries.push(Entry::new(id, Some(to_scan.id), relative_path.clone(), true, ino, is_symlink, is_ignored));
+                new_dirs.push((id, path, relative_path, ignore));
             } else {
                 let is_ignored = to_scan.ignore.as_ref().map_or(true, |i| {
                     i.matched(to_scan.path.join(&name), false).is_ignore()
                 });
 
-                new_children.push(self.push_file(
-                    Some(to_scan.id),
-                    name,
-                    ino,
-                    is_symlink,
-                    is_ignored,
-                    relative_path
-                ));
+                new_entries.push(Entry::new(id, Some(to_scan.id), relative_path, false, ino, is_symlink, is_ignored));
             };
         }
 
-        if let Entry::Dir { children, .. } = &mut self.0.write().entries[to_scan.id] {
-            *children = new_children.clone();
+        // os filhos entram na árvore antes de serem varridos, para que os netos encontrem o pai
+        self.insert_entries(new_entries);
+
+        for (id, path, relative_path, ignore) in new_dirs {
+            let dirs_to_scan = to_scan.dirs_to_scan.clone();
+
+            let _ = to_scan.dirs_to_scan.send(Ok(DirToScan {
+                id,
+                path,
+                relative_path,
+                ignore,
+                dirs_to_scan
+            }));
         }
 
         Ok(())
     }
 
-    fn push_dir(
-        &self,
-
-        parent: Option<usize>,
-        name: OsString,
-        ino: u64,
-
-        is_symlink: bool,
-        is_ignored: bool
-    ) -> usize {
-        let entries = &mut self.0.write().entries;
-        let dir_id = entries.len();
-
-        entries.push(Entry::Dir {
-            parent,
-            name,
-            ino,
-            is_symlink,
-            is_ignored,
-            children: Vec::new()
-        });
-
-        dir_id
+    fn next_entry_id(&self) -> usize {
+        post_inc(&mut self.0.write().next_entry_id)
     }
 
-    fn push_file(
-        &self,
-
-        parent: Option<usize>,
-        name: OsString,
-        ino: u64,
-
-        is_symlink: bool,
-        is_ignored: bool,
-
-        path: PathBuf
-    ) -> usize {
-        let path = path.to_string_lossy();
-        let lowercase_path = path.to_lowercase().chars().collect::<Vec<_>>();
-
-        let path = path.chars().collect::<Vec<_>>();
-        let path_chars = CharBag::from(&path[..]);
-
-        let mut state = self.0.write();
-        let entry_id = state.entries.len();
-
-        state.entries.push(Entry::File {
-            parent,
-            name,
-            ino,
-            is_symlink,
-            is_ignored
-        });
-
-        state.file_paths.push(PathEntry {
-            entry_id,
-            path_chars,
-            path,
-            lowercase_path,
-            is_ignored
-        });
+    fn insert_entries(&self, entries: Vec<Entry>) {
+        self.0.write().snapshot.insert_entries(entries);
+    }
 
-        entry_id
+    /// cópia barata do estado atual da árvore, para ser lida fora da thread principal
+    pub fn snapshot(&self) -> Snapshot {
+        self.0.read().snapshot.clone()
     }
 
-    pub fn entry_path(&self, mut entry_id: usize) -> Result<PathBuf> {
+    pub fn entry_path(&self, entry_id: usize) -> Result<PathBuf> {
         let state = self.0.read();
 
-        if entry_id >= state.entries.len() {
-            return Err(anyhow!("a entrada não existe na árvore"));
-        }
-
-        let mut entries = Vec::new();
-
-        loop {
-            let entry = &state.entries[entry_id];
-
-            entries.push(entry);
-
-            if let Some(parent_id) = entry.parent() {
-                entry_id = parent_id;
-            } else {
-                break;
-            }
-        }
-
-        let mut path = PathBuf::new();
-
-        for entry in entries.into_iter().rev() {
-            path.push(entry.name());
-        }
-
-        Ok(path)
+        state
+            .snapshot
+            .entry(entry_id)
+            .map(|entry| entry.path.to_path_buf())
+            .or_else(|| state.removed_paths.get(&entry_id).map(|path| path.to_path_buf()))
+            .ok_or_else(|| anyhow!("a entrada não existe na árvore"))
     }
 
     pub fn abs_entry_path(&self, entry_id: usize) -> Result<PathBuf> {
-        let mut path = self.0.read().path.clone();
+        let mut path = self.path();
 
         path.pop();
 
         Ok(path.join(self.entry_path(entry_id)?))
     }
 
-    fn fmt_entry(&self, f: &mut fmt::Formatter<'_>, entry_id: usize, indent: usize) -> fmt::Result {
-        match &self.0.read().entries[entry_id] {
-            Entry::Dir { name, children, .. } => {
-                write!(
-                    f,
-                    "{}{}/ ({})\n",
-                    " ".repeat(indent),
-                    name.to_string_lossy(),
-                    entry_id
-                )?;
+    pub fn path(&self) -> PathBuf {
+        self.0.read().snapshot.path.to_path_buf()
+    }
 
-                for child_id in children.iter() {
-                    self.fmt_entry(f, *child_id, indent + 2)?;
-                }
+    pub fn contains_path(&self, path: &Path) -> bool {
+        path.starts_with(self.path())
+    }
 
-                Ok(())
-            }
+    /// entrada de um caminho absoluto dentro da árvore, se ele já foi encontrado pela varredura
+    pub fn entry_for_path(&self, path: &Path) -> Option<usize> {
+        let state = self.0.read();
+        let snapshot = &state.snapshot;
 
-            Entry::File { name, .. } => write!(
-                f,
-                "{}{} ({})\n",
-                " ".repeat(indent),
-                name.to_string_lossy(),
-                entry_id
-            )
-        }
-    }
+        let relative_path = path.strip_prefix(&snapshot.path).ok()?;
+        let root = snapshot.root_entry()?;
 
-    pub fn path(&self) -> PathBuf {
-        PathBuf::from(&self.0.read().path)
+        snapshot
+            .entry_for_path(&root.path.join(relative_path))
+            .map(|entry| entry.id)
     }
 
-    pub fn contains_path(&self, path: &Path) -> bool {
-        path.starts_with(self.path())
+    pub fn entry(&self, entry_id: usize) -> Option<Entry> {
+        self.0.read().snapshot.entry(entry_id).cloned()
     }
 
-    pub fn iter(&self) -> Iter {
-        Iter {
-            tree: self.clone(),
-            stack: Vec::new(),
+    /// filhos de um diretório, com os subdiretórios primeiro e cada grupo em ordem alfabética
+    pub fn children(&self, entry_id: usize) -> Vec<usize> {
+        let state = self.0.read();
+        let mut children = state.snapshot.children(entry_id);
+
+        children.sort_by(|a, b| b.is_dir().cmp(&a.is_dir()).then_with(|| a.name().cmp(b.name())));
 
-            started: false
-        }
+        children.into_iter().map(|entry| entry.id).collect()
     }
 
-    pub fn files(&self) -> FilesIter {
-        FilesIter {
-            iter: self.iter(),
-            path: PathBuf::new()
-        }
+    pub fn is_scanning(&self) -> bool {
+        self.0.read().scanning
     }
 
     pub fn entry_count(&self) -> usize {
-        self.0.read().entries.len()
+        self.0.read().snapshot.entry_count()
     }
 
     pub fn file_count(&self) -> usize {
-        self.0.read().file_paths.len()
+        self.0.read().snapshot.file_count()
+    }
+
+    pub fn git_status(&self, entry_id: usize) -> Option<GitStatus> {
+        let state = self.0.read();
+
+        state.snapshot.git_status(state.snapshot.entry(entry_id)?)
     }
 
     pub fn load_history(&self, entry_id: usize) -> impl Future<Output = Result<History>> {
@@ -383,291 +373,1319 @@ impl Worktree {
 
             let history = History { base_text };
             tree.0.write().histories.insert(entry_id, history.clone());
-            
+
             Ok(history)
         }
     }
 
-    fn scanning(&mut self, _: (), ctx: &mut ModelContext<Self>) {
-        if self.0.read().scanning {
-            ctx.notify();
-        } else {
-            ctx.halt_stream();
+    /// grava `text` no arquivo da entrada, que passa a ser o texto carregado por `load_history`
+    pub fn save(&self, entry_id: usize, text: String) -> impl Future<Output = Result<()>> {
+        let tree = self.clone();
+
+        async move {
+            let path = tree.abs_entry_path(entry_id)?;
+
+            smol::fs::write(&path, text.as_bytes()).await?;
+
+            tree.record_own_change(&path);
+            tree.0.write().histories.insert(entry_id, History { base_text: text });
+
+            Ok(())
         }
     }
 
-    fn done_scanning(&mut self, result: io::Result<()>, ctx: &mut ModelContext<Self>) {
-        log::info!("digitalização concluída");
+    /// cria um arquivo ou diretório vazio dentro do diretório `parent_id`
+    pub fn create_entry(
+        &mut self,
 
-        self.0.write().scanning = false;
+        parent_id: usize,
+        name: &OsStr,
+        is_dir: bool,
 
-        if let Err(error) = result {
-            log::error!("erro ao preencher a árvore de trabalho: {}", error);
-        } else {
-            ctx.notify();
+        ctx: &mut ModelContext<Self>
+    ) -> Result<usize> {
+        let abs_path = self.abs_dir_path(parent_id)?.join(validate_name(name)?);
+
+        if abs_path.exists() {
+            return Err(anyhow!("{:?} já existe", abs_path));
         }
-    }
-}
 
-impl fmt::Debug for Worktree {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.entry_count() == 0 {
-            write!(f, "árvore vazia\n")
+        if is_dir {
+            fs::create_dir(&abs_path)?;
         } else {
-            self.fmt_entry(f, 0, 0)
+            fs::OpenOptions::new().write(true).create_new(true).open(&abs_path)?;
         }
-    }
-}
 
-impl Entity for Worktree {
-    type Event = ();
-}
+        let entry_id = self.insert_entry(parent_id, name.into(), &abs_path)?;
 
-pub trait WorktreeHandle {
-    fn file(&self, entry_id: usize, app: &AppContext) -> Result<FileHandle>;
-}
+        self.record_own_change(&abs_path);
 
-impl WorktreeHandle for ModelHandle<Worktree> {
-    fn file(&self, entry_id: usize, app: &AppContext) -> Result<FileHandle> {
-        if entry_id >= self.as_ref(app).entry_count() {
-            return Err(anyhow!("a entrada não existe na árvore"));
-        }
+        ctx.emit(Event::Created(entry_id));
+        ctx.notify();
 
-        Ok(FileHandle {
-            worktree: self.clone(),
-            entry_id
-        })
+        self.refresh_git_statuses(ctx);
+
+        Ok(entry_id)
     }
-}
 
-#[derive(Clone, Debug)]
-pub enum Entry {
-    Dir {
-        parent: Option<usize>,
-        name: OsString,
-        ino: u64,
+    pub fn rename_entry(&mut self, entry_id: usize, name: &OsStr, ctx: &mut ModelContext<Self>) -> Result<()> {
+        let parent_id = self
+            .entry(entry_id)
+            .and_then(|entry| entry.parent())
+            .ok_or_else(|| anyhow!("a raiz da árvore não pode ser renomeada"))?;
 
-        is_symlink: bool,
-        is_ignored: bool,
+        self.move_entry(entry_id, parent_id, name, ctx)
+    }
 
-        children: Vec<usize>
-    },
+    /// move uma entrada para o diretório `parent_id` com o nome `name`
+    ///
+    /// a entrada mantém o seu id, então os `FileHandle`s abertos passam a apontar para o novo
+    /// caminho sem precisarem ser atualizados
+    pub fn move_entry(
+        &mut self,
 
-    File {
-        parent: Option<usize>,
-        name: OsString,
-        ino: u64,
+        entry_id: usize,
+        parent_id: usize,
+        name: &OsStr,
 
-        is_symlink: bool,
-        is_ignored: bool
-    }
-}
+        ctx: &mut ModelContext<Self>
+    ) -> Result<()> {
+        let entry = self.entry(entry_id).ok_or_else(|| anyhow!("a entrada não existe na árvore"))?;
 
-impl Entry {
-    fn parent(&self) -> Option<usize> {
-        match self {
-            Entry::Dir { parent, .. } | Entry::File { parent, .. } => *parent
+        if entry.parent().is_none() {
+            return Err(anyhow!("a raiz da árvore não pode ser movida"));
         }
-    }
 
-    fn name(&self) -> &OsStr {
-        match self {
-            Entry::Dir { name, .. } | Entry::File { name, .. } => name
+        if self.ancestors(parent_id).contains(&entry_id) {
+            return Err(anyhow!("um diretório não pode ser movido para dentro de si mesmo"));
         }
-    }
-}
 
-#[derive(Clone)]
-pub struct FileHandle {
-    worktree: ModelHandle<Worktree>,
-    entry_id: usize
-}
+        let old_path = self.abs_entry_path(entry_id)?;
+        let new_path = self.abs_dir_path(parent_id)?.join(validate_name(name)?);
 
-impl FileHandle {
-    pub fn path(&self, app: &AppContext) -> PathBuf {
-        self.worktree.as_ref(app).entry_path(self.entry_id).unwrap()
-    }
+        if new_path == old_path {
+            return Ok(());
+        }
 
-    pub fn load_history(&self, app: &AppContext) -> impl Future<Output = Result<History>> {
-        self.worktree.as_ref(app).load_history(self.entry_id)
-    }
+        // em sistemas de arquivos que ignoram maiúsculas, renomear `a.rs` para `A.rs` encontra a
+        // própria entrada no destino, o que não é um conflito
+        if fs::symlink_metadata(&new_path).is_ok() && !is_same_entry(&old_path, &new_path) {
+            return Err(anyhow!("{:?} já existe", new_path));
+        }
 
-    pub fn entry_id(&self) -> (usize, usize) {
-        (self.worktree.id(), self.entry_id)
-    }
-}
+        fs::rename(&old_path, &new_path)?;
 
-struct IterStackEntry {
-    entry_id: usize,
-    child_idx: usize
-}
+        self.record_own_change(&old_path);
+        self.record_own_change(&new_path);
 
-pub struct Iter {
-    tree: Worktree,
-    stack: Vec<IterStackEntry>,
-    started: bool
-}
+        let new_relative_path = self.entry_path(parent_id)?.join(name);
 
-impl Iterator for Iter {
-    type Item = Traversal;
+        {
+            let mut state = self.0.write();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let state = self.tree.0.read();
+            // as chaves da entrada e de tudo abaixo dela mudam, então a subárvore é retirada e
+            // inserida de novo com os caminhos reescritos
+            let moved = state
+                .snapshot
+                .remove_subtree(&entry.path)
+                .into_iter()
+                .map(|mut moved| {
+                    let suffix = moved.path.strip_prefix(&entry.path).unwrap();
 
-        if !self.started {
-            self.started = true;
+                    let path = if suffix.as_os_str().is_empty() {
+                        new_relative_path.clone()
+                    } else {
+                        new_relative_path.join(suffix)
+                    };
 
-            return if let Some(entry) = state.entries.first().cloned() {
-                self.stack.push(IterStackEntry {
-                    entry_id: 0,
-                    child_idx: 0
-                });
+                    if moved.id == entry_id {
+                        moved.parent = Some(parent_id);
+                    }
 
-                Some(Traversal::Push { entry_id: 0, entry })
-            } else {
-                None
-            };
+                    moved.with_path(path)
+                })
+                .collect();
+
+            state.snapshot.insert_entries(moved);
         }
 
-        while let Some(parent) = self.stack.last_mut() {
-            if let Entry::Dir { children, .. } = &state.entries[parent.entry_id] {
-                if parent.child_idx < children.len() {
-                    let child_id = children[post_inc(&mut parent.child_idx)];
+        ctx.emit(Event::Moved(entry_id));
+        ctx.notify();
 
-                    self.stack.push(IterStackEntry {
-                        entry_id: child_id,
-                        child_idx: 0
-                    });
+        self.refresh_git_statuses(ctx);
 
-                    return Some(Traversal::Push {
-                        entry_id: child_id,
-                        entry: state.entries[child_id].clone()
-                    });
-                } else {
-                    self.stack.pop();
+        Ok(())
+    }
 
-                    return Some(Traversal::Pop);
-                }
-            } else {
-                self.stack.pop();
+    /// copia uma entrada para o mesmo diretório, com um nome como `main copy.rs`
+    pub fn duplicate_entry(&mut self, entry_id: usize, ctx: &mut ModelContext<Self>) -> Result<usize> {
+        let entry = self.entry(entry_id).ok_or_else(|| anyhow!("a entrada não existe na árvore"))?;
+        let parent_id = entry.parent().ok_or_else(|| anyhow!("a raiz da árvore não pode ser duplicada"))?;
+
+        let old_path = self.abs_entry_path(entry_id)?;
+        let dir_path = self.abs_dir_path(parent_id)?;
 
-                return Some(Traversal::Pop);
+        // diretórios não têm extensão, mesmo que o nome contenha um ponto
+        let name = Path::new(entry.name());
+
+        let (stem, extension) = match (name.file_stem(), name.extension()) {
+            (Some(stem), Some(extension)) if !entry.is_dir() => {
+                (stem.to_string_lossy(), format!(".{}", extension.to_string_lossy()))
             }
-        }
 
-        None
-    }
-}
+            _ => (name.as_os_str().to_string_lossy(), String::new())
+        };
 
-#[derive(Debug)]
-pub enum Traversal {
-    Push { entry_id: usize, entry: Entry },
-    Pop
-}
+        let new_name = (1..)
+            .map(|ix| match ix {
+                1 => format!("{} copy{}", stem, extension),
 
-pub struct FilesIter {
-    iter: Iter,
-    path: PathBuf
-}
+                _ => format!("{} copy {}{}", stem, ix, extension)
+            })
+            .find(|name| !dir_path.join(name).exists())
+            .unwrap();
 
-pub struct FilesIterItem {
-    pub entry_id: usize,
-    pub path: PathBuf
-}
+        let new_path = dir_path.join(&new_name);
 
-impl Iterator for FilesIter {
-    type Item = FilesIterItem;
+        copy_recursively(&old_path, &new_path)?;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.iter.next() {
-                Some(Traversal::Push {
-                    entry_id, entry, ..
-                }) => match entry {
-                    Entry::Dir { name, .. } => {
-                        self.path.push(name);
-                    }
+        let new_entry_id = self.insert_entry(parent_id, new_name.into(), &new_path)?;
 
-                    Entry::File { name, .. } => {
-                        self.path.push(name);
+        self.record_own_change(&new_path);
 
-                        return Some(FilesIterItem {
-                            entry_id,
+        ctx.emit(Event::Created(new_entry_id));
+        ctx.notify();
 
-                            path: self.path.clone()
-                        });
-                    }
-                },
+        self.refresh_git_statuses(ctx);
 
-                Some(Traversal::Pop) => {
-                    self.path.pop();
-                }
+        Ok(new_entry_id)
+    }
 
-                None => {
-                    return None;
-                }
+    /// remove uma entrada do disco, movendo-a para a lixeira a menos que `permanently` seja
+    /// verdadeiro
+    pub fn delete_entry(&mut self, entry_id: usize, permanently: bool, ctx: &mut ModelContext<Self>) -> Result<()> {
+        let entry = self.entry(entry_id).ok_or_else(|| anyhow!("a entrada não existe na árvore"))?;
+
+        if entry.parent().is_none() {
+            return Err(anyhow!("a raiz da árvore não pode ser removida"));
+        }
+
+        let path = self.abs_entry_path(entry_id)?;
+
+        if permanently {
+            if entry.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
             }
+        } else {
+            move_to_trash(&path)?;
         }
-    }
-}
 
-trait UnwrapIgnoreTuple {
-    fn unwrap(self) -> Ignore;
-}
+        self.record_own_change(&path);
+        self.remove_entry(entry_id);
 
-impl UnwrapIgnoreTuple for (Ignore, Option<ignore::Error>) {
-    fn unwrap(self) -> Ignore {
-        if let Some(error) = self.1 {
-            log::error!("erro ao carregar dados do gitignore: {}", error);
+        ctx.emit(Event::Removed(entry_id));
+        ctx.notify();
+
+        self.refresh_git_statuses(ctx);
+
+        Ok(())
+    }
+
+    /// guarda o estado em que a própria árvore deixou um caminho; o observador só existe no linux,
+    /// então nos outros sistemas nada precisa ser guardado
+    fn record_own_change(&self, abs_path: &Path) {
+        if cfg!(target_os = "linux") {
+            self.0.write().own_changes.insert(abs_path.to_path_buf(), modified_time(abs_path));
         }
+    }
 
-        self.0
+    /// tira da árvore uma entrada que não existe mais no disco, junto com tudo abaixo dela
+    fn remove_entry(&self, entry_id: usize) {
+        let mut state = self.0.write();
+
+        let path = match state.snapshot.entry(entry_id) {
+            Some(entry) => entry.path.clone(),
+
+            None => return
+        };
+
+        for removed in state.snapshot.remove_subtree(&path) {
+            state.histories.remove(&removed.id);
+
+            if state.open_files.contains_key(&removed.id) {
+                state.removed_paths.insert(removed.id, removed.path);
+            }
+        }
     }
-}
 
-pub fn match_paths(
-    trees: &[Worktree],
-    query: &str,
-    include_ignored: bool,
-    smart_case: bool,
-    max_results: usize
-) -> Vec<PathMatch> {
-    let tree_states = trees.iter().map(|tree| tree.0.read()).collect::<Vec<_>>();
+    /// adiciona à árvore uma entrada que acabou de ser criada no disco, junto com o seu conteúdo
+    fn insert_entry(&self, parent_id: usize, name: OsString, abs_path: &Path) -> Result<usize> {
+        let parent = self.entry(parent_id).ok_or_else(|| anyhow!("a entrada não existe na árvore"))?;
 
-    fuzzy::match_paths(
-        &tree_states
-            .iter()
-            .map(|tree| {
-                let skip_prefix = if trees.len() == 1 {
-                    if let Some(Entry::Dir { name, .. }) = tree.entries.get(0) {
-                        let name = name.to_string_lossy();
-
-                        if name == "/" {
-                            1
-                        } else {
-                            name.chars().count() + 1
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
+        let metadata = fs::symlink_metadata(abs_path)?;
+        let is_symlink = metadata.file_type().is_symlink();
+        let metadata = fs::metadata(abs_path)?;
 
-                (tree.id, skip_prefix, &tree.file_paths[..])
-            })
-            .collect::<Vec<_>>()[..],
+        let is_ignored = parent.is_ignored() || self.is_path_ignored(abs_path, metadata.is_dir());
 
-        query,
-        include_ignored,
-        smart_case,
-        max_results
-    )
-}
+        let entry_id = self.next_entry_id();
 
-// #[cfg(test)]
-// mod test {
-//     use super::*;
+        self.insert_entries(vec![Entry::new(
+            entry_id,
+            Some(parent_id),
+            parent.path.join(&name),
+            metadata.is_dir(),
+            metadata.ino(),
+            is_symlink,
+            is_ignored
+        )]);
+
+        if metadata.is_dir() {
+            for child in fs::read_dir(abs_path)? {
+                let child = child?;
+
+                self.insert_entry(entry_id, child.file_name(), &child.path())?;
+            }
+        }
+
+        Ok(entry_id)
+    }
+
+    /// regras de ignore que valem dentro do diretório `dir`, incluindo o seu próprio `.gitignore`
+    fn dir_ignore(&self, dir: &Path) -> Ignore {
+        let root = self.path();
+        let mut ignore = IgnoreBuilder::new().build().add_parents(&root).unwrap();
+
+        if root.is_dir() {
+            ignore = ignore.add_child(&root).unwrap();
+        }
+
+        if let Ok(relative_path) = dir.strip_prefix(&root) {
+            let mut path = root.clone();
+
+            for component in relative_path.components() {
+                path.push(component);
+
+                ignore = ignore.add_child(&path).unwrap();
+            }
+        }
+
+        ignore
+    }
+
+    fn is_path_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if is_dir && path.file_name() == Some(OsStr::new(".git")) {
+            return true;
+        }
+
+        match path.parent() {
+            Some(parent) => self.dir_ignore(parent).matched(path, is_dir).is_ignore(),
+
+            None => false
+        }
+    }
+
+    /// recalcula quais entradas abaixo do diretório `dir_id` são ignoradas, depois que um
+    /// `.gitignore` mudou
+    fn update_ignored(&self, dir_id: usize) {
+        let dir_path = match self.abs_entry_path(dir_id) {
+            Ok(path) => path,
+
+            Err(_) => return
+        };
+
+        let is_ignored = self.entry(dir_id).map_or(false, |entry| entry.is_ignored());
+        let ignore = if is_ignored { None } else { Some(self.dir_ignore(&dir_path)) };
+
+        let mut ignored = HashMap::new();
+
+        for child_id in self.children(dir_id) {
+            self.collect_ignored(child_id, dir_path.join(self.entry(child_id).unwrap().name()), ignore.as_ref(), &mut ignored);
+        }
+
+        let mut state = self.0.write();
+
+        let updated = ignored
+            .into_iter()
+            .filter_map(|(entry_id, is_ignored)| {
+                let entry = state.snapshot.entry(entry_id)?;
+
+                if entry.is_ignored == is_ignored {
+                    None
+                } else {
+                    Some(Entry { is_ignored, ..entry.clone() })
+                }
+            })
+            .collect();
+
+        state.snapshot.insert_entries(updated);
+    }
+
+    /// assim como na varredura, `ignore` é `None` dentro de diretórios ignorados
+    fn collect_ignored(&self, entry_id: usize, path: PathBuf, ignore: Option<&Ignore>, ignored: &mut HashMap<usize, bool>) {
+        let entry = match self.entry(entry_id) {
+            Some(entry) => entry,
+
+            None => return
+        };
+
+        if entry.is_dir() {
+            let child_ignore = ignore.map(|ignore| ignore.add_child(&path).unwrap());
+
+            let is_ignored = match &child_ignore {
+                Some(child_ignore) => child_ignore.matched(&path, true).is_ignore() || entry.name() == ".git",
+
+                None => true
+            };
+
+            ignored.insert(entry_id, is_ignored);
+
+            let child_ignore = child_ignore.filter(|_| !is_ignored);
+
+            for child_id in self.children(entry_id) {
+                let child_path = path.join(self.entry(child_id).unwrap().name());
+
+                self.collect_ignored(child_id, child_path, child_ignore.as_ref(), ignored);
+            }
+        } else {
+            let is_ignored = ignore.map_or(true, |ignore| ignore.matched(&path, false).is_ignore());
+
+            ignored.insert(entry_id, is_ignored);
+        }
+    }
+
+    /// aplica um lote de caminhos alterados no disco, vindo do observador de arquivos
+    fn apply_changes(&mut self, mut paths: Vec<PathBuf>, ctx: &mut ModelContext<Self>) {
+        {
+            let mut state = self.0.write();
+
+            // as entradas ainda estão sendo inseridas pela varredura
+            if state.scanning {
+                state.pending_changes.extend(paths);
+
+                return;
+            }
+
+            // os caminhos que continuam como a própria árvore os deixou já estão aplicados, e os
+            // que mudaram desde então passam a ser tratados como mudanças externas
+            let own_changes = &mut state.own_changes;
+
+            paths.retain(|path| match own_changes.remove(path) {
+                Some(modified) => modified != modified_time(path),
+
+                None => true
+            });
+        }
+
+        let root = self.path();
+
+        if paths.iter().any(|path| is_in_git_dir(&root, path)) {
+            self.0.write().git_dir_changed = true;
+        }
+
+        let mut events = Vec::new();
+        let mut ignore_dirs = Vec::new();
+
+        // os caminhos estão ordenados, então um diretório novo é inserido com todo o seu
+        // conteúdo antes que os seus filhos sejam considerados
+        for path in &paths {
+            if path.file_name() == Some(OsStr::new(".gitignore")) {
+                ignore_dirs.extend(path.parent().and_then(|dir| self.entry_for_path(dir)));
+            }
+
+            let parent_id = match path.parent().and_then(|parent| self.entry_for_path(parent)) {
+                Some(parent_id) => parent_id,
+
+                // a raiz ou algo fora da árvore
+                None => {
+                    if let Some(root_id) = self.entry_for_path(path) {
+                        events.extend(self.sync_dir(root_id, path));
+                    }
+
+                    continue;
+                }
+            };
+
+            let metadata = fs::metadata(path).ok();
+            let entry_id = self.entry_for_path(path);
+
+            match (entry_id, metadata) {
+                (Some(entry_id), None) => {
+                    self.remove_entry(entry_id);
+
+                    events.push(Event::Removed(entry_id));
+                }
+
+                (None, Some(_)) => match self.insert_entry(parent_id, path.file_name().unwrap().into(), path) {
+                    Ok(entry_id) => events.push(Event::Created(entry_id)),
+
+                    Err(error) => log::error!("erro ao adicionar {:?} à árvore de trabalho: {}", path, error)
+                },
+
+                (Some(entry_id), Some(metadata)) => {
+                    let is_dir = self.entry(entry_id).map_or(false, |entry| entry.is_dir());
+
+                    if is_dir != metadata.is_dir() {
+                        // um arquivo foi trocado por um diretório com o mesmo nome, ou vice-versa
+                        self.remove_entry(entry_id);
+
+                        events.push(Event::Removed(entry_id));
+
+                        match self.insert_entry(parent_id, path.file_name().unwrap().into(), path) {
+                            Ok(entry_id) => events.push(Event::Created(entry_id)),
+
+                            Err(error) => log::error!("erro ao adicionar {:?} à árvore de trabalho: {}", path, error)
+                        }
+                    } else if is_dir {
+                        events.extend(self.sync_dir(entry_id, path));
+                    } else {
+                        // o conteúdo em cache não corresponde mais ao arquivo
+                        self.0.write().histories.remove(&entry_id);
+
+                        events.push(Event::Modified(entry_id));
+                    }
+                }
+
+                (None, None) => {}
+            }
+        }
+
+        for dir_id in ignore_dirs {
+            self.update_ignored(dir_id);
+        }
+
+        if !events.is_empty() {
+            for event in events {
+                ctx.emit(event);
+            }
+
+            ctx.notify();
+        }
+
+        // mudanças em `.git`, como um commit, também alteram o status das entradas
+        self.refresh_git_statuses(ctx);
+    }
+
+    /// recalcula em segundo plano o status do git de todas as entradas
+    fn refresh_git_statuses(&self, ctx: &mut ModelContext<Self>) {
+        let snapshot = self.snapshot();
+
+        let scan_id = {
+            let mut state = self.0.write();
+
+            state.git_scan_id += 1;
+            state.git_scan_id
+        };
+
+        let task = ctx.background_executor().spawn(async move {
+            (scan_id, git::load_statuses(&snapshot).await)
+        });
+
+        ctx.spawn(task, Self::git_statuses_loaded).detach();
+    }
+
+    fn git_statuses_loaded(
+        &mut self,
+
+        (scan_id, statuses): (usize, BTreeMap<PathBuf, GitStatus>),
+        ctx: &mut ModelContext<Self>
+    ) {
+        {
+            let mut state = self.0.write();
+
+            if scan_id != state.git_scan_id {
+                return;
+            }
+
+            // um commit ou um checkout podem mudar o `HEAD` sem mudar nenhum status, e quem
+            // mostra o texto do `HEAD` precisa recarregá-lo mesmo assim
+            let git_dir_changed = mem::take(&mut state.git_dir_changed);
+
+            if *state.snapshot.git_statuses == statuses && !git_dir_changed {
+                return;
+            }
+
+            state.snapshot.git_statuses = Arc::new(statuses);
+        }
+
+        ctx.emit(Event::GitStatusChanged);
+        ctx.notify();
+    }
+
+    /// compara os filhos de um diretório com o disco, usado quando eventos podem ter sido perdidos
+    fn sync_dir(&self, dir_id: usize, path: &Path) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        let names = match fs::read_dir(path) {
+            Ok(children) => children
+                .filter_map(|child| child.ok().map(|child| child.file_name()))
+                .collect::<HashSet<_>>(),
+
+            Err(_) => return events
+        };
+
+        for child_id in self.children(dir_id) {
+            let name = self.entry(child_id).unwrap().name().to_os_string();
+
+            if !names.contains(&name) {
+                self.remove_entry(child_id);
+
+                events.push(Event::Removed(child_id));
+            }
+        }
+
+        for name in names {
+            if self.entry_for_path(&path.join(&name)).is_none() {
+                match self.insert_entry(dir_id, name.clone(), &path.join(&name)) {
+                    Ok(entry_id) => events.push(Event::Created(entry_id)),
+
+                    Err(error) => log::error!("erro ao adicionar {:?} à árvore de trabalho: {}", name, error)
+                }
+            }
+        }
+
+        events
+    }
+
+    /// observa a árvore no disco e aplica as mudanças incrementalmente
+    ///
+    /// o canal retornado fecha assim que os diretórios passam a ser observados, ou quando o
+    /// observador falha
+    #[cfg(target_os = "linux")]
+    fn watch(&self, ctx: &mut ModelContext<Self>) -> smol::channel::Receiver<()> {
+        let root = self.path();
+        let (tx, rx) = smol::channel::unbounded();
+        let (watching_tx, watching_rx) = smol::channel::bounded::<()>(1);
+
+        ctx.background_executor()
+            .spawn(async move {
+                let result: io::Result<()> = async {
+                    let mut watcher = Watcher::new()?;
+
+                    watcher.watch_recursively(&root)?;
+
+                    drop(watching_tx);
+
+                    loop {
+                        let batch = watcher.next_batch().await?;
+
+                        // a árvore foi descartada
+                        if tx.send(batch).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }.await;
+
+                if let Err(error) = result {
+                    log::error!("erro ao observar {:?}: {}", root, error);
+                }
+            }).detach();
+
+        ctx.spawn_stream(rx, Self::apply_changes, |_, _| {}).detach();
+
+        watching_rx
+    }
+
+    fn abs_dir_path(&self, entry_id: usize) -> Result<PathBuf> {
+        match self.entry(entry_id) {
+            Some(entry) if entry.is_dir() => self.abs_entry_path(entry_id),
+
+            Some(_) => Err(anyhow!("a entrada não é um diretório")),
+
+            None => Err(anyhow!("a entrada não existe na árvore"))
+        }
+    }
+
+    /// a própria entrada e os seus ancestrais
+    fn ancestors(&self, mut entry_id: usize) -> Vec<usize> {
+        let state = self.0.read();
+        let mut ancestors = vec![entry_id];
+
+        while let Some(parent_id) = state.snapshot.entry(entry_id).and_then(Entry::parent) {
+            ancestors.push(parent_id);
+
+            entry_id = parent_id;
+        }
+
+        ancestors
+    }
+
+    fn scanning(&mut self, _: (), ctx: &mut ModelContext<Self>) {
+        if self.0.read().scanning {
+            ctx.notify();
+        } else {
+            ctx.halt_stream();
+        }
+    }
+
+    fn done_scanning(&mut self, result: io::Result<()>, ctx: &mut ModelContext<Self>) {
+        log::info!("digitalização concluída");
+
+        let mut pending_changes = {
+            let mut state = self.0.write();
+
+            state.scanning = false;
+
+            mem::take(&mut state.pending_changes)
+        };
+
+        if let Err(error) = result {
+            log::error!("erro ao preencher a árvore de trabalho: {}", error);
+        }
+
+        // notifica mesmo em caso de erro para que os observadores saibam que a varredura acabou
+        ctx.notify();
+
+        if pending_changes.is_empty() {
+            self.refresh_git_statuses(ctx);
+        } else {
+            pending_changes.sort();
+            pending_changes.dedup();
+
+            // também recalcula o status do git
+            self.apply_changes(pending_changes, ctx);
+        }
+    }
+}
+
+impl fmt::Debug for Worktree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0.read().snapshot, f)
+    }
+}
+
+impl Snapshot {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entries.summary().count
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.entries.summary().file_count
+    }
+
+    pub fn visible_file_count(&self) -> usize {
+        self.entries.summary().visible_file_count
+    }
+
+    pub fn root_entry(&self) -> Option<&Entry> {
+        self.entry(0)
+    }
+
+    /// todas as entradas, na ordem dos caminhos
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.cursor::<(), ()>()
+    }
+
+    /// status do git de uma entrada
+    ///
+    /// um diretório ignorado ou não rastreado passa o seu status para o conteúdo, e um diretório
+    /// rastreado mostra o status mais importante entre os arquivos abaixo dele
+    pub fn git_status(&self, entry: &Entry) -> Option<GitStatus> {
+        for ancestor in entry.path.ancestors() {
+            match self.git_statuses.get(ancestor) {
+                Some(status) if ancestor == entry.path.as_ref() => return Some(*status),
+
+                Some(GitStatus::Ignored) => return Some(GitStatus::Ignored),
+                Some(GitStatus::Untracked) => return Some(GitStatus::Untracked),
+
+                _ => {}
+            }
+        }
+
+        if entry.is_dir() {
+            self.git_statuses
+                .range(entry.path.to_path_buf()..)
+                .take_while(|(path, _)| path.starts_with(&entry.path))
+                .map(|(_, status)| *status)
+                .filter(|status| *status != GitStatus::Ignored)
+                .max()
+        } else {
+            None
+        }
+    }
+
+    pub fn entry(&self, entry_id: usize) -> Option<&Entry> {
+        let mut cursor = self.entries_by_id.cursor::<EntryId, ()>();
+
+        cursor.seek(&EntryId(entry_id), SeekBias::Left);
+
+        let path = &cursor.item().filter(|entry| entry.id == entry_id)?.path;
+
+        self.entry_for_path(path)
+    }
+
+    /// entrada com o caminho relativo `path`, que começa pelo nome da raiz
+    pub fn entry_for_path(&self, path: &Path) -> Option<&Entry> {
+        let mut cursor = self.entries.cursor::<PathKey, ()>();
+
+        cursor.seek(&PathKey(path.into()), SeekBias::Left);
+        cursor.item().filter(|entry| entry.path.as_ref() == path)
+    }
+
+    /// filhos diretos de um diretório, na ordem dos caminhos
+    pub fn children(&self, entry_id: usize) -> Vec<&Entry> {
+        let parent = match self.entry(entry_id) {
+            Some(parent) if parent.is_dir() => parent,
+
+            _ => return Vec::new()
+        };
+
+        let mut cursor = self.entries.cursor::<PathKey, ()>();
+        let mut children = Vec::new();
+
+        cursor.seek(&PathKey(parent.path.clone()), SeekBias::Right);
+
+        while let Some(child) = cursor.item() {
+            if !child.path.starts_with(&parent.path) {
+                break;
+            }
+
+            children.push(child);
+
+            // pula o conteúdo do filho, que fica logo depois dele na ordem dos caminhos
+            cursor.seek_forward(&PathKey(successor(&child.path)), SeekBias::Left);
+        }
+
+        children
+    }
+
+    /// arquivos a partir do `start`-ésimo, contando só os visíveis se `include_ignored` for falso
+    pub fn files(&self, include_ignored: bool, start: usize) -> FileIter {
+        if include_ignored {
+            let mut cursor = self.entries.cursor::<FileCount, ()>();
+
+            cursor.seek(&FileCount(start), SeekBias::Right);
+
+            FileIter(FileCursor::All(cursor))
+        } else {
+            let mut cursor = self.entries.cursor::<VisibleFileCount, ()>();
+
+            cursor.seek(&VisibleFileCount(start), SeekBias::Right);
+
+            FileIter(FileCursor::Visible(cursor))
+        }
+    }
+
+    fn insert_entries(&mut self, entries: Vec<Entry>) {
+        let mut id_edits = entries
+            .iter()
+            .map(|entry| Edit::Insert(IdEntry {
+                id: entry.id,
+                path: entry.path.clone()
+            }))
+            .collect::<Vec<_>>();
+
+        let mut path_edits = entries.into_iter().map(Edit::Insert).collect::<Vec<_>>();
+
+        self.entries.edit(&mut path_edits);
+        self.entries_by_id.edit(&mut id_edits);
+    }
+
+    /// remove a entrada em `path` e todas as entradas abaixo dela, devolvendo-as
+    fn remove_subtree(&mut self, path: &Path) -> Vec<Entry> {
+        let mut removed = Vec::new();
+
+        self.entries = {
+            let mut cursor = self.entries.cursor::<PathKey, ()>();
+            let mut new_entries = cursor.slice(&PathKey(path.into()), SeekBias::Left);
+
+            while let Some(entry) = cursor.item() {
+                if !entry.path.starts_with(path) {
+                    break;
+                }
+
+                removed.push(entry.clone());
+
+                cursor.next();
+            }
+
+            new_entries.push_tree(cursor.suffix());
+            new_entries
+        };
+
+        let mut id_edits = removed
+            .iter()
+            .map(|entry| Edit::Remove(IdEntry {
+                id: entry.id,
+                path: entry.path.clone()
+            }))
+            .collect::<Vec<_>>();
+
+        self.entries_by_id.edit(&mut id_edits);
+
+        removed
+    }
+}
+
+impl fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root_depth = match self.root_entry() {
+            Some(root) => root.path.components().count(),
+
+            None => return write!(f, "árvore vazia\n")
+        };
+
+        for entry in self.entries.cursor::<(), ()>() {
+            write!(
+                f,
+                "{}{}{} ({})\n",
+                " ".repeat(2 * (entry.path.components().count() - root_depth)),
+                entry.name().to_string_lossy(),
+                if entry.is_dir() { "/" } else { "" },
+                entry.id
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+    Created(usize),
+    Removed(usize),
+    Modified(usize),
+    Moved(usize),
+    GitStatusChanged
+}
+
+impl Entity for Worktree {
+    type Event = Event;
+}
+
+pub trait WorktreeHandle {
+    fn file(&self, entry_id: usize, app: &AppContext) -> Result<FileHandle>;
+}
+
+impl WorktreeHandle for ModelHandle<Worktree> {
+    fn file(&self, entry_id: usize, app: &AppContext) -> Result<FileHandle> {
+        let tree = self.as_ref(app);
+
+        tree.entry_path(entry_id)?;
+
+        *tree.0.write().open_files.entry(entry_id).or_default() += 1;
+
+        Ok(FileHandle {
+            worktree: self.clone(),
+            entry_id,
+
+            _open_file: Arc::new(OpenFile {
+                tree: tree.clone(),
+                entry_id
+            })
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    id: usize,
+    parent: Option<usize>,
+    path: Arc<Path>,
+    kind: EntryKind,
+    ino: u64,
+
+    is_symlink: bool,
+    is_ignored: bool
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum EntryKind {
+    Dir,
+    File(CharBag)
+}
+
+impl Entry {
+    fn new(
+        id: usize,
+        parent: Option<usize>,
+        path: PathBuf,
+        is_dir: bool,
+        ino: u64,
+
+        is_symlink: bool,
+        is_ignored: bool
+    ) -> Self {
+        let kind = if is_dir { EntryKind::Dir } else { EntryKind::File(path_chars(&path)) };
+
+        Self {
+            id,
+            parent,
+
+            path: path.into(),
+            kind,
+            ino,
+
+            is_symlink,
+            is_ignored
+        }
+    }
+
+    /// troca o caminho da entrada, recalculando os caracteres usados pela busca aproximada
+    fn with_path(mut self, path: PathBuf) -> Self {
+        if let EntryKind::File(chars) = &mut self.kind {
+            *chars = path_chars(&path);
+        }
+
+        self.path = path.into();
+
+        self
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// caminho relativo à árvore, começando pelo nome da raiz
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == EntryKind::Dir
+    }
+
+    pub fn is_ignored(&self) -> bool {
+        self.is_ignored
+    }
+
+    pub fn path_chars(&self) -> CharBag {
+        match self.kind {
+            EntryKind::Dir => CharBag::default(),
+
+            EntryKind::File(path_chars) => path_chars
+        }
+    }
+}
+
+impl sum_tree::Item for Entry {
+    type Summary = EntrySummary;
+
+    fn summary(&self) -> Self::Summary {
+        let file_count = if self.is_dir() { 0 } else { 1 };
+
+        EntrySummary {
+            max_path: self.path.clone(),
+            count: 1,
+            file_count,
+            visible_file_count: if self.is_ignored { 0 } else { file_count }
+        }
+    }
+}
+
+impl sum_tree::KeyedItem for Entry {
+    type Key = PathKey;
+
+    fn key(&self) -> Self::Key {
+        PathKey(self.path.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EntrySummary {
+    max_path: Arc<Path>,
+    count: usize,
+    file_count: usize,
+    visible_file_count: usize
+}
+
+impl Default for EntrySummary {
+    fn default() -> Self {
+        Self {
+            max_path: Arc::from(Path::new("")),
+            count: 0,
+            file_count: 0,
+            visible_file_count: 0
+        }
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for EntrySummary {
+    fn add_assign(&mut self, other: &Self) {
+        self.max_path = other.max_path.clone();
+        self.count += other.count;
+        self.file_count += other.file_count;
+        self.visible_file_count += other.visible_file_count;
+    }
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PathKey(Arc<Path>);
+
+impl Default for PathKey {
+    fn default() -> Self {
+        PathKey(Arc::from(Path::new("")))
+    }
+}
+
+impl<'a> sum_tree::Dimension<'a, EntrySummary> for PathKey {
+    fn add_summary(&mut self, summary: &'a EntrySummary) {
+        self.0 = summary.max_path.clone();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+struct FileCount(usize);
+
+impl<'a> sum_tree::Dimension<'a, EntrySummary> for FileCount {
+    fn add_summary(&mut self, summary: &'a EntrySummary) {
+        self.0 += summary.file_count;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+struct VisibleFileCount(usize);
+
+impl<'a> sum_tree::Dimension<'a, EntrySummary> for VisibleFileCount {
+    fn add_summary(&mut self, summary: &'a EntrySummary) {
+        self.0 += summary.visible_file_count;
+    }
+}
+
+/// índice dos caminhos pelo id da entrada, que não muda quando ela é renomeada ou movida
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct IdEntry {
+    id: usize,
+    path: Arc<Path>
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct IdSummary {
+    max_id: usize
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+struct EntryId(usize);
+
+impl sum_tree::Item for IdEntry {
+    type Summary = IdSummary;
+
+    fn summary(&self) -> Self::Summary {
+        IdSummary { max_id: self.id }
+    }
+}
+
+impl sum_tree::KeyedItem for IdEntry {
+    type Key = EntryId;
+
+    fn key(&self) -> Self::Key {
+        EntryId(self.id)
+    }
+}
+
+impl<'a> AddAssign<&'a Self> for IdSummary {
+    fn add_assign(&mut self, other: &Self) {
+        self.max_id = other.max_id;
+    }
+}
+
+impl<'a> sum_tree::Dimension<'a, IdSummary> for EntryId {
+    fn add_summary(&mut self, summary: &'a IdSummary) {
+        self.0 = summary.max_id;
+    }
+}
+
+pub struct FileIter<'a>(FileCursor<'a>);
+
+enum FileCursor<'a> {
+    All(Cursor<'a, Entry, FileCount, ()>),
+    Visible(Cursor<'a, Entry, VisibleFileCount, ()>)
+}
+
+impl<'a> Iterator for FileIter<'a> {
+    type Item = &'a Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            FileCursor::All(cursor) => next_file(cursor, true),
+
+            FileCursor::Visible(cursor) => next_file(cursor, false)
+        }
+    }
+}
+
+fn next_file<'a, S>(cursor: &mut Cursor<'a, Entry, S, ()>, include_ignored: bool) -> Option<&'a Entry>
+where
+    S: sum_tree::Dimension<'a, EntrySummary>
+{
+    loop {
+        let entry = cursor.item()?;
+
+        cursor.next();
+
+        if !entry.is_dir() && (include_ignored || !entry.is_ignored) {
+            return Some(entry);
+        }
+    }
+}
+
+fn path_chars(path: &Path) -> CharBag {
+    CharBag::from(path.to_string_lossy().as_ref())
+}
+
+/// menor caminho que vem depois de `path` e de tudo que está abaixo dele
+///
+/// os caminhos são comparados componente a componente, então acrescentar um byte nulo ao último
+/// componente produz um nome maior que o original mas menor que qualquer irmão seguinte
+fn successor(path: &Path) -> Arc<Path> {
+    let mut path = path.as_os_str().to_os_string();
+
+    path.push("\0");
+
+    PathBuf::from(path).into()
+}
+
+#[derive(Clone)]
+pub struct FileHandle {
+    worktree: ModelHandle<Worktree>,
+    entry_id: usize,
+
+    _open_file: Arc<OpenFile>
+}
+
+/// mantém o caminho da entrada acessível mesmo depois que ela sai da árvore
+struct OpenFile {
+    tree: Worktree,
+    entry_id: usize
+}
+
+impl FileHandle {
+    pub fn path(&self, app: &AppContext) -> PathBuf {
+        self.worktree.as_ref(app).entry_path(self.entry_id).unwrap()
+    }
+
+    pub fn abs_path(&self, app: &AppContext) -> PathBuf {
+        self.worktree.as_ref(app).abs_entry_path(self.entry_id).unwrap()
+    }
+
+    pub fn load_history(&self, app: &AppContext) -> impl Future<Output = Result<History>> {
+        self.worktree.as_ref(app).load_history(self.entry_id)
+    }
+
+    pub fn save(&self, text: String, app: &AppContext) -> impl Future<Output = Result<()>> {
+        self.worktree.as_ref(app).save(self.entry_id, text)
+    }
+
+    /// conteúdo do arquivo no `HEAD` do repositório, ou `None` se ele não está versionado
+    pub fn load_head_text(&self, app: &AppContext) -> impl Future<Output = Option<String>> {
+        let abs_path = self.worktree.as_ref(app).abs_entry_path(self.entry_id);
+
+        async move { git::load_head_text(&abs_path.ok()?).await }
+    }
+
+    pub fn git_status(&self, app: &AppContext) -> Option<GitStatus> {
+        self.worktree.as_ref(app).git_status(self.entry_id)
+    }
+
+    pub fn worktree(&self) -> &ModelHandle<Worktree> {
+        &self.worktree
+    }
+
+    pub fn entry_id(&self) -> (usize, usize) {
+        (self.worktree.id(), self.entry_id)
+    }
+}
+
+impl Drop for OpenFile {
+    fn drop(&mut self) {
+        let mut state = self.tree.0.write();
+
+        if let Some(count) = state.open_files.get_mut(&self.entry_id) {
+            *count -= 1;
+
+            if *count == 0 {
+                state.open_files.remove(&self.entry_id);
+                state.removed_paths.remove(&self.entry_id);
+            }
+        }
+    }
+}
+
+fn validate_name(name: &OsStr) -> Result<&OsStr> {
+    let is_valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.to_string_lossy().contains('/');
+
+    if is_valid {
+        Ok(name)
+    } else {
+        Err(anyhow!("nome inválido {:?}", name))
+    }
+}
+
+pub(super) fn copy_recursively(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::metadata(from)?.is_dir() {
+        fs::create_dir(to)?;
+
+        for child in fs::read_dir(from)? {
+            let child = child?;
+
+            copy_recursively(&child.path(), &to.join(child.file_name()))?;
+        }
+
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+fn is_in_git_dir(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|path| path.iter().next())
+        .map_or(false, |name| name == ".git")
+}
+
+/// se os dois caminhos levam à mesma entrada do diretório; links físicos apontam para o mesmo
+/// inode a partir de entradas diferentes, então um arquivo com mais de um link nunca é considerado
+/// a mesma entrada
+fn is_same_entry(a: &Path, b: &Path) -> bool {
+    match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino() && (a.is_dir() || a.nlink() == 1),
+
+        _ => false
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+trait UnwrapIgnoreTuple {
+    fn unwrap(self) -> Ignore;
+}
+
+impl UnwrapIgnoreTuple for (Ignore, Option<ignore::Error>) {
+    fn unwrap(self) -> Ignore {
+        if let Some(error) = self.1 {
+            log::error!("erro ao carregar dados do gitignore: {}", error);
+        }
+
+        self.0
+    }
+}
+// #[cfg(test)]
+// mod test {
+//     use super::*;
 //     use crate::test_utils::*;
 //     use anyhow::Result;
 //     use std::os::unix;
@@ -712,4 +1730,453 @@ pub fn match_paths(
 //     //
 //     //     Ok(())
 //     // }
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::temp_tree;
+    use gpui::App;
+    use serde_json::json;
+
+    #[test]
+    fn test_file_operations() -> Result<()> {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    "a": {
+                        "main.rs": "fn main() {}"
+                    },
+
+                    "b": {},
+                    "notes.txt": "notas"
+                }
+            }));
+
+            let root = dir.path().join("root");
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), root.clone(), Some(ctx)));
+
+            app.finish_pending_tasks().await; // popula a árvore de trabalho
+
+            let entry = |path: &str, app: &App| tree.read(app, |tree, _| tree.entry_for_path(&root.join(path)).unwrap());
+            let paths = |app: &App| {
+                tree.read(app, |tree, _| {
+                    let mut paths = tree
+                        .snapshot()
+                        .files(true, 0)
+                        .map(|file| file.path().to_string_lossy().to_string())
+                        .collect::<Vec<_>>();
+
+                    paths.sort();
+
+                    paths
+                })
+            };
+
+            let (a, b, main_rs) = (entry("a", &app), entry("b", &app), entry("a/main.rs", &app));
+
+            // criar
+            tree.update(&mut app, |tree, ctx| tree.create_entry(b, OsStr::new("lib.rs"), false, ctx))?;
+            tree.update(&mut app, |tree, ctx| tree.create_entry(0, OsStr::new("c"), true, ctx))?;
+
+            assert!(root.join("b/lib.rs").is_file());
+            assert!(root.join("c").is_dir());
+
+            assert!(tree.update(&mut app, |tree, ctx| tree.create_entry(b, OsStr::new("lib.rs"), false, ctx)).is_err());
+            assert!(tree.update(&mut app, |tree, ctx| tree.create_entry(b, OsStr::new("x/y"), false, ctx)).is_err());
+
+            // renomear e mover mantêm o id da entrada
+            let c = entry("c", &app);
+
+            tree.update(&mut app, |tree, ctx| tree.rename_entry(main_rs, OsStr::new("app.rs"), ctx))?;
+            tree.update(&mut app, |tree, ctx| tree.move_entry(a, c, OsStr::new("a"), ctx))?;
+
+            assert!(root.join("c/a/app.rs").is_file());
+            assert_eq!(tree.read(&app, |tree, _| tree.entry_path(main_rs))?, PathBuf::from("root/c/a/app.rs"));
+
+            assert!(tree.update(&mut app, |tree, ctx| tree.move_entry(a, main_rs, OsStr::new("a"), ctx)).is_err());
+
+            // duplicar
+            let notes = entry("notes.txt", &app);
+            let copy = tree.update(&mut app, |tree, ctx| tree.duplicate_entry(notes, ctx))?;
+
+            assert_eq!(tree.read(&app, |tree, _| tree.entry_path(copy))?, PathBuf::from("root/notes copy.txt"));
+            assert_eq!(fs::read_to_string(root.join("notes copy.txt"))?, "notas");
+
+            // excluir
+            tree.update(&mut app, |tree, ctx| tree.delete_entry(b, true, ctx))?;
+
+            assert!(!root.join("b").exists());
+
+            // o caminho de uma entrada excluída só é lembrado enquanto algum arquivo aberto o usa
+            let file = tree.read(&app, |_, app| tree.file(copy, app))?;
+
+            tree.update(&mut app, |tree, ctx| tree.delete_entry(copy, true, ctx))?;
+
+            assert_eq!(tree.read(&app, |_, app| file.path(app)), PathBuf::from("root/notes copy.txt"));
+            assert!(tree.read(&app, |tree, _| tree.entry_path(b)).is_err());
+
+            drop(file);
+
+            assert!(tree.read(&app, |tree, _| tree.entry_path(copy)).is_err());
+
+            assert_eq!(paths(&app), vec!["root/c/a/app.rs", "root/notes.txt"]);
+
+            // os caminhos pesquisáveis acompanham as entradas
+            let matches = tree.read(&app, |tree, _| match_paths(&[tree.snapshot()], "capp", false, false, &HashMap::new(), 10));
+
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].entry_id, main_rs);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_is_same_entry() -> Result<()> {
+        let dir = temp_tree(json!({
+            "a": {
+                "main.rs": "fn main() {}",
+                "lib.rs": ""
+            }
+        }));
+
+        let a = dir.path().join("a");
+
+        // um diretório acessado por outro caminho, como acontece com outra grafia num sistema de
+        // arquivos que ignora maiúsculas
+        std::os::unix::fs::symlink(&a, dir.path().join("b"))?;
+
+        assert!(is_same_entry(&a.join("main.rs"), &dir.path().join("b/main.rs")));
+        assert!(is_same_entry(&a, &a));
+
+        assert!(!is_same_entry(&a.join("main.rs"), &a.join("lib.rs")));
+        assert!(!is_same_entry(&a.join("main.rs"), &a.join("missing.rs")));
+
+        // um link físico é outra entrada, e renomear sobre ele não moveria o arquivo
+        fs::hard_link(a.join("main.rs"), a.join("link.rs"))?;
+
+        assert!(!is_same_entry(&a.join("main.rs"), &a.join("link.rs")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshots() -> Result<()> {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    ".gitignore": "*.log\n",
+                    "a": {
+                        "b.txt": "",
+                        "debug.log": ""
+                    },
+
+                    "a-c.txt": "",
+                    "d": {}
+                }
+            }));
+
+            let root = dir.path().join("root");
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), root.clone(), Some(ctx)));
+
+            app.finish_pending_tasks().await; // popula a árvore de trabalho
+
+            let snapshot = tree.read(&app, |tree, _| tree.snapshot());
+
+            assert_eq!(snapshot.entry_count(), 7);
+            assert_eq!(snapshot.file_count(), 4);
+            assert_eq!(snapshot.visible_file_count(), 3);
+
+            // os filhos de `a` não se misturam com `a-c.txt`, que vem depois deles na ordem dos caminhos
+            let a = snapshot.entry_for_path(Path::new("root/a")).unwrap().id();
+
+            let names = |snapshot: &Snapshot, entry_id: usize| {
+                snapshot
+                    .children(entry_id)
+                    .iter()
+                    .map(|entry| entry.name().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(names(&snapshot, a), vec!["b.txt", "debug.log"]);
+            assert_eq!(names(&snapshot, 0), vec![".gitignore", "a", "a-c.txt", "d"]);
+
+            let visible_files = snapshot
+                .files(false, 1)
+                .map(|entry| entry.path().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+
+            assert_eq!(visible_files, vec!["root/a/b.txt", "root/a-c.txt"]);
+
+            // um snapshot não enxerga as mudanças feitas depois dele
+            let d = snapshot.entry_for_path(Path::new("root/d")).unwrap().id();
+
+            tree.update(&mut app, |tree, ctx| tree.create_entry(d, OsStr::new("e.txt"), false, ctx))?;
+            tree.update(&mut app, |tree, ctx| tree.move_entry(a, d, OsStr::new("a"), ctx))?;
+
+            assert_eq!(snapshot.file_count(), 4);
+            assert!(snapshot.entry_for_path(Path::new("root/d/e.txt")).is_none());
+            assert_eq!(snapshot.entry(a).unwrap().path(), Path::new("root/a"));
+
+            let snapshot = tree.read(&app, |tree, _| tree.snapshot());
+
+            assert_eq!(snapshot.file_count(), 5);
+            assert_eq!(snapshot.entry(a).unwrap().path(), Path::new("root/d/a"));
+            assert_eq!(names(&snapshot, d), vec!["a", "e.txt"]);
+            assert_eq!(names(&snapshot, a), vec!["b.txt", "debug.log"]);
+
+            Ok(())
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_watching() -> Result<()> {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    ".gitignore": "",
+                    "a": {
+                        "old.txt": ""
+                    },
+
+                    "b.txt": ""
+                }
+            }));
+
+            let root = dir.path().join("root");
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), root.clone(), Some(ctx)));
+
+            app.finish_pending_tasks().await; // popula a árvore de trabalho e começa a observá-la
+
+            let files = |app: &App| {
+                tree.read(app, |tree, _| {
+                    let mut files = tree
+                        .snapshot()
+                        .files(true, 0)
+                        .map(|file| (file.path().to_string_lossy().to_string(), file.is_ignored()))
+                        .collect::<Vec<_>>();
+
+                    files.sort();
+
+                    files
+                })
+            };
+
+            // as mudanças chegam em lotes, então esperamos até que a árvore as reflita
+            let wait_for = |expected: Vec<(&str, bool)>, app: &App| {
+                let expected = expected
+                    .into_iter()
+                    .map(|(path, is_ignored)| (path.to_string(), is_ignored))
+                    .collect::<Vec<_>>();
+
+                let app = app.clone();
+
+                async move {
+                    for _ in 0..250 {
+                        if files(&app) == expected {
+                            return;
+                        }
+
+                        smol::Timer::after(Duration::from_millis(20)).await;
+                    }
+
+                    assert_eq!(files(&app), expected);
+                }
+            };
+
+            fs::write(root.join("c.txt"), "")?;
+            fs::create_dir_all(root.join("d/e"))?;
+            fs::write(root.join("d/e/f.txt"), "")?;
+            fs::remove_file(root.join("a/old.txt"))?;
+            fs::rename(root.join("b.txt"), root.join("a/b.txt"))?;
+
+            wait_for(
+                vec![
+                    ("root/.gitignore", false),
+                    ("root/a/b.txt", false),
+                    ("root/c.txt", false),
+                    ("root/d/e/f.txt", false)
+                ],
+                &app
+            ).await;
+
+            // as novas regras valem para as entradas existentes e para as que vierem depois
+            fs::write(root.join(".gitignore"), "d\n*.log\n")?;
+            fs::write(root.join("a/debug.log"), "")?;
+
+            wait_for(
+                vec![
+                    ("root/.gitignore", false),
+                    ("root/a/b.txt", false),
+                    ("root/a/debug.log", true),
+                    ("root/c.txt", false),
+                    ("root/d/e/f.txt", true)
+                ],
+                &app
+            ).await;
+
+            // o que a própria árvore muda não volta do observador como uma modificação, que
+            // descartaria o conteúdo em cache
+            let c = tree.read(&app, |tree, _| tree.entry_for_path(&root.join("c.txt")).unwrap());
+
+            tree.read(&app, |tree, _| tree.load_history(c)).await?;
+            tree.update(&mut app, |tree, ctx| tree.rename_entry(c, OsStr::new("g.txt"), ctx))?;
+
+            smol::Timer::after(Duration::from_millis(300)).await;
+
+            assert!(tree.read(&app, |tree, _| tree.0.read().histories.contains_key(&c)));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_git_statuses() -> Result<()> {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    ".gitignore": "*.log\n",
+                    "a": {
+                        "committed.txt": "um",
+                        "debug.log": ""
+                    },
+
+                    "b.txt": "dois"
+                }
+            }));
+
+            let root = dir.path().join("root");
+
+            let git = |args: &[&str]| {
+                let status = std::process::Command::new("git")
+                    .current_dir(&root)
+                    .args(&["-c", "user.name=heat", "-c", "user.email=heat@example.com"])
+                    .args(args)
+                    .output()
+                    .unwrap()
+                    .status;
+
+                assert!(status.success(), "git {:?} falhou", args);
+            };
+
+            git(&["init", "--quiet"]);
+            git(&["add", ".gitignore", "a/committed.txt"]);
+            git(&["commit", "--quiet", "-m", "inicial"]);
+
+            fs::write(root.join("a/committed.txt"), "um e meio")?;
+
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), root.clone(), Some(ctx)));
+
+            let statuses = |app: &App| {
+                tree.read(app, |tree, _| {
+                    let snapshot = tree.snapshot();
+
+                    let status = |path: &str| {
+                        snapshot.entry_for_path(Path::new(path)).and_then(|entry| snapshot.git_status(entry))
+                    };
+
+                    (
+                        status("root/a/committed.txt"),
+                        status("root/a/debug.log"),
+                        status("root/b.txt"),
+                        status("root/a"),
+                        status("root/.gitignore")
+                    )
+                })
+            };
+
+            // a árvore é populada e, em seguida, o status é lido em segundo plano
+            for _ in 0..250 {
+                app.finish_pending_tasks().await;
+
+                if statuses(&app).0.is_some() {
+                    break;
+                }
+
+                smol::Timer::after(Duration::from_millis(20)).await;
+            }
+
+            assert_eq!(
+                statuses(&app),
+
+                (
+                    Some(GitStatus::Modified),
+                    Some(GitStatus::Ignored),
+                    Some(GitStatus::Untracked),
+                    Some(GitStatus::Modified),
+                    None
+                )
+            );
+
+            Ok(())
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_git_init_while_watching() -> Result<()> {
+        App::test((), |mut app| async move {
+            let dir = temp_tree(json!({
+                "root": {
+                    "a.txt": "um"
+                }
+            }));
+
+            let root = dir.path().join("root");
+
+            let git = |args: &[&str]| {
+                let status = std::process::Command::new("git")
+                    .current_dir(&root)
+                    .args(args)
+                    .output()
+                    .unwrap()
+                    .status;
+
+                assert!(status.success(), "git {:?} falhou", args);
+            };
+
+            let tree = app.add_model(|ctx| Worktree::new(ctx.model_id(), root.clone(), Some(ctx)));
+
+            app.finish_pending_tasks().await; // popula a árvore de trabalho e começa a observá-la
+
+            let status = |app: &App| {
+                tree.read(app, |tree, _| {
+                    let snapshot = tree.snapshot();
+
+                    snapshot.entry_for_path(Path::new("root/a.txt")).and_then(|entry| snapshot.git_status(entry))
+                })
+            };
+
+            let wait_for = |expected: Option<GitStatus>, app: &App| {
+                let app = app.clone();
+
+                async move {
+                    for _ in 0..250 {
+                        app.finish_pending_tasks().await;
+
+                        if status(&app) == expected {
+                            return;
+                        }
+
+                        smol::Timer::after(Duration::from_millis(20)).await;
+                    }
+
+                    assert_eq!(status(&app), expected);
+                }
+            };
+
+            git(&["init", "--quiet"]);
+
+            wait_for(Some(GitStatus::Untracked), &app).await;
+
+            // só o índice dentro do `.git` criado depois do início muda
+            git(&["add", "a.txt"]);
+
+            wait_for(Some(GitStatus::Added), &app).await;
+
+            Ok(())
+        })
+    }
+}